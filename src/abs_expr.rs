@@ -63,8 +63,8 @@ impl<T: EtlValueType + Abs, Expr: WrappableExpr<T>> EtlExpr<T> for AbsExpr<T, Ex
         self.expr.value.rows()
     }
 
-    fn columns(&self) -> usize {
-        self.expr.value.columns()
+    fn dim(&self, i: usize) -> usize {
+        self.expr.value.dim(i)
     }
 
     fn at(&self, i: usize) -> T {
@@ -103,6 +103,9 @@ pub fn abs<T: EtlValueType + Abs, Expr: WrappableExpr<T>>(expr: Expr) -> AbsExpr
     AbsExpr::<T, Expr>::new(expr)
 }
 
+crate::impl_rank_unary_expr_trait!(Abs, AbsExpr<T, Expr>);
+crate::impl_fixed_shape_unary_expr_trait!(Abs, AbsExpr<T, Expr>);
+
 crate::impl_add_op_unary_expr_trait!(Abs, AbsExpr<T, Expr>);
 crate::impl_sub_op_unary_expr_trait!(Abs, AbsExpr<T, Expr>);
 crate::impl_mul_op_unary_expr_trait!(Abs, AbsExpr<T, Expr>);
@@ -165,4 +168,16 @@ mod tests {
         assert_relative_eq!(b.at(3), 2.0 * 4.0, epsilon = 1e-6);
         assert_relative_eq!(b.at(4), 2.0 * 5.0, epsilon = 1e-6);
     }
+
+    #[test]
+    fn small_signed() {
+        let a = Vector::<i8>::new_iota(5, -3);
+
+        let mut b = Vector::<i8>::new(5);
+        b |= abs(&a);
+
+        assert_eq!(b.at(0), 3);
+        assert_eq!(b.at(3), 0);
+        assert_eq!(b.at(4), 1);
+    }
 }
@@ -1,3 +1,4 @@
+use crate::etl_error::EtlError;
 use crate::etl_expr::*;
 
 // The declaration of AddExpr
@@ -12,14 +13,25 @@ pub struct AddExpr<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: Wrapp
 
 impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> AddExpr<T, LeftExpr, RightExpr> {
     pub fn new(lhs: LeftExpr, rhs: RightExpr) -> Self {
+        match Self::try_new(lhs, rhs) {
+            Ok(expr) => expr,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    pub fn try_new(lhs: LeftExpr, rhs: RightExpr) -> Result<Self, EtlError> {
         if LeftExpr::DIMENSIONS > 0 && RightExpr::DIMENSIONS > 0 && lhs.size() != rhs.size() {
-            panic!("Cannot add expressions of different sizes ({} + {})", lhs.size(), rhs.size());
+            return Err(EtlError::ShapeMismatch {
+                op: "addition",
+                lhs: expr_dims(&lhs),
+                rhs: expr_dims(&rhs),
+            });
         }
 
-        Self {
+        Ok(Self {
             lhs: lhs.wrap(),
             rhs: rhs.wrap(),
-        }
+        })
     }
 }
 
@@ -83,11 +95,11 @@ impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> E
         }
     }
 
-    fn columns(&self) -> usize {
+    fn dim(&self, i: usize) -> usize {
         if LeftExpr::DIMENSIONS > 0 {
-            self.lhs.value.columns()
+            self.lhs.value.dim(i)
         } else {
-            self.rhs.value.columns()
+            self.rhs.value.dim(i)
         }
     }
 
@@ -95,6 +107,11 @@ impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> E
     fn at(&self, i: usize) -> T {
         self.lhs.value.at(i) + self.rhs.value.at(i)
     }
+
+    #[inline(always)]
+    fn compute_at(&self, i: usize) -> T::Compute {
+        self.lhs.value.compute_at(i) + self.rhs.value.compute_at(i)
+    }
 }
 
 // AddExpr is an EtlWrappable
@@ -127,8 +144,8 @@ impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> E
 
 #[macro_export]
 macro_rules! impl_add_op_value {
-    ($type:ty) => {
-        impl<'a, T: EtlValueType, RightExpr: WrappableExpr<T>> std::ops::Add<RightExpr> for &'a $type {
+    ($type:ty $(, const $dim:ident)*) => {
+        impl<'a, T: EtlValueType, $(const $dim: usize,)* RightExpr: WrappableExpr<T>> std::ops::Add<RightExpr> for &'a $type {
             type Output = $crate::add_expr::AddExpr<T, &'a $type, RightExpr>;
 
             fn add(self, other: RightExpr) -> Self::Output {
@@ -136,10 +153,11 @@ macro_rules! impl_add_op_value {
             }
         }
 
-        impl<T: EtlValueType, RightExpr: EtlExpr<T>> std::ops::AddAssign<RightExpr> for $type {
+        impl<T: EtlValueType, $(const $dim: usize,)* RightExpr: EtlExpr<T>> std::ops::AddAssign<RightExpr> for $type {
             fn add_assign(&mut self, other: RightExpr) {
                 validate_assign(self, &other);
-                add_assign_direct(&mut self.data, &other);
+                let size = self.size();
+                add_assign_direct(&mut self.data[..size], &other);
             }
         }
     };
@@ -191,7 +209,7 @@ macro_rules! impl_add_op_binary_expr_simd {
     ($type:ty) => {
         impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>, OuterRightExpr: WrappableExpr<T>> std::ops::Add<OuterRightExpr> for $type
         where
-            Simd<T, 8>: SimdHelper,
+            T: $crate::base_traits::Vectorizable,
         {
             type Output = $crate::add_expr::AddExpr<T, $type, OuterRightExpr>;
 
@@ -228,6 +246,9 @@ macro_rules! impl_add_op_unary_expr_trait {
     };
 }
 
+crate::impl_rank_binary_expr!(AddExpr<T, LeftExpr, RightExpr>);
+crate::impl_fixed_shape_binary_expr!(AddExpr<T, LeftExpr, RightExpr>);
+
 crate::impl_add_op_binary_expr!(AddExpr<T, LeftExpr, RightExpr>);
 crate::impl_sub_op_binary_expr!(AddExpr<T, LeftExpr, RightExpr>);
 crate::impl_mul_op_binary_expr!(AddExpr<T, LeftExpr, RightExpr>);
@@ -238,8 +259,12 @@ crate::impl_scale_op_binary_expr!(AddExpr<T, LeftExpr, RightExpr>);
 
 #[cfg(test)]
 mod tests {
-    use crate::etl_expr::EtlExpr;
+    use crate::add_expr::AddExpr;
+    use crate::constant::cst;
+    use crate::etl_error::EtlError;
+    use crate::etl_expr::{Etl2d, Etl3d, EtlExpr};
     use crate::matrix_2d::Matrix2d;
+    use crate::matrix_3d::Matrix3d;
     use crate::vector::Vector;
 
     #[test]
@@ -419,4 +444,79 @@ mod tests {
             index_b += 1;
         }
     }
+
+    #[test]
+    fn try_new_invalid() {
+        let a = Vector::<i64>::new(8);
+        let b = Vector::<i64>::new(9);
+
+        assert!(AddExpr::try_new(&a, &b).is_err());
+        assert!(AddExpr::try_new(&a, &a).is_ok());
+
+        let c = Matrix2d::<i64>::new(2, 3);
+        let d = Matrix2d::<i64>::new(3, 3);
+
+        assert_eq!(
+            AddExpr::try_new(&c, &d).err(),
+            Some(EtlError::ShapeMismatch {
+                op: "addition",
+                lhs: vec![2, 3],
+                rhs: vec![3, 3],
+            })
+        );
+    }
+
+    #[test]
+    fn rank_accessors() {
+        let a = Matrix2d::<i64>::new_iota(2, 3, 1);
+        let b = Matrix2d::<i64>::new_iota(2, 3, 2);
+
+        let expr = &a + &b;
+
+        assert_eq!(expr.columns(), 3);
+        assert_eq!(expr.at2(0, 0), 3);
+        assert_eq!(expr.at2(1, 2), 13);
+
+        let c = Matrix3d::<i64>::new_iota(2, 2, 2, 1);
+
+        let expr = &c + cst(10);
+
+        assert_eq!(expr.at3(0, 0, 1), 12);
+        assert_eq!(expr.at3(1, 0, 1), 16);
+    }
+
+    #[test]
+    fn unsigned() {
+        let a = Vector::<u8>::new_iota(11, 1);
+        let b = Vector::<u16>::new_iota(11, 100);
+        let c = Vector::<usize>::new_iota(11, 7);
+
+        let mut d = Vector::<u8>::new(11);
+        let mut e = Vector::<u16>::new(11);
+        let mut f = Vector::<usize>::new(11);
+
+        d |= &a + &a;
+        e |= &b + cst(1);
+        f |= &c + &c + &c;
+
+        assert_eq!(d.at(10), 22);
+        assert_eq!(e.at(10), 111);
+        assert_eq!(f.at(10), 51);
+    }
+
+    #[test]
+    fn small_signed() {
+        let a = Vector::<i8>::new_iota(9, -4);
+        let b = Vector::<i16>::new_iota(9, -300);
+
+        let mut c = Vector::<i8>::new(9);
+        let mut d = Vector::<i16>::new(9);
+
+        c |= &a + &a;
+        d |= &b + &b;
+
+        assert_eq!(c.at(0), -8);
+        assert_eq!(c.at(8), 8);
+        assert_eq!(d.at(8), -584);
+    }
 }
@@ -0,0 +1,280 @@
+use crate::base_traits::{SimdHelper, Vectorizable};
+use crate::workspace;
+
+use std::alloc::{Layout, alloc, dealloc, handle_alloc_error};
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+
+/// The alignment, in bytes, of the storage of the containers
+pub const ALIGNMENT: usize = 64;
+
+// The declaration of AlignedBuffer<T>
+
+/// A fixed-size heap buffer whose first element is aligned to ALIGNMENT bytes
+///
+/// This is the storage of the dynamic containers and of the temporaries of the smart expressions.
+/// Together with padded_size, this lets the kernels use aligned SIMD loads and stores.
+///
+/// Scratch buffers (see from_workspace) are borrowed from the workspace of the current thread and
+/// given back to it when they are dropped.
+pub struct AlignedBuffer<T: Copy + Default> {
+    ptr: NonNull<T>,
+    len: usize,
+    pooled: bool,
+}
+
+// The buffer owns its elements, exactly like a Vec<T>
+unsafe impl<T: Copy + Default + Send> Send for AlignedBuffer<T> {}
+unsafe impl<T: Copy + Default + Sync> Sync for AlignedBuffer<T> {}
+
+// The functions of AlignedBuffer<T>
+
+impl<T: Copy + Default> AlignedBuffer<T> {
+    /// Allocate a buffer of len default values
+    pub fn new(len: usize) -> Self {
+        Self::from_fn(len, false, |_| T::default())
+    }
+
+    /// Allocate a buffer holding a copy of the given slice
+    pub fn from_slice(data: &[T]) -> Self {
+        Self::from_fn(data.len(), false, |i| data[i])
+    }
+
+    /// Borrow a scratch buffer of len default values from the workspace
+    pub fn from_workspace(len: usize) -> Self {
+        Self::from_fn(len, true, |_| T::default())
+    }
+
+    /// Borrow a scratch buffer holding a copy of the given slice from the workspace
+    pub fn from_workspace_slice(data: &[T]) -> Self {
+        Self::from_fn(data.len(), true, |i| data[i])
+    }
+
+    fn layout(len: usize) -> Layout {
+        Layout::array::<T>(len)
+            .and_then(|layout| layout.align_to(ALIGNMENT))
+            .expect("AlignedBuffer is too large")
+    }
+
+    fn from_fn(len: usize, pooled: bool, f: impl Fn(usize) -> T) -> Self {
+        let layout = Self::layout(len);
+
+        let ptr = if layout.size() == 0 {
+            // Nothing to allocate, but the pointer must still be aligned
+            NonNull::new(std::ptr::without_provenance_mut(ALIGNMENT)).unwrap()
+        } else if pooled {
+            workspace::acquire(layout).cast::<T>()
+        } else {
+            // Safety: the layout has a non-zero size
+            let raw = unsafe { alloc(layout) } as *mut T;
+            NonNull::new(raw).unwrap_or_else(|| handle_alloc_error(layout))
+        };
+
+        for i in 0..len {
+            // Safety: the allocation holds len elements
+            unsafe { ptr.as_ptr().add(i).write(f(i)) };
+        }
+
+        Self { ptr, len, pooled }
+    }
+}
+
+impl<T: Copy + Default> Drop for AlignedBuffer<T> {
+    fn drop(&mut self) {
+        let layout = Self::layout(self.len);
+
+        if layout.size() == 0 {
+            return;
+        }
+
+        if self.pooled {
+            workspace::release(self.ptr.cast::<u8>(), layout);
+        } else {
+            // Safety: the buffer was allocated with this same layout
+            unsafe { dealloc(self.ptr.as_ptr() as *mut u8, layout) };
+        }
+    }
+}
+
+// A clone is never a scratch buffer, it would otherwise be given to the workspace when dropped
+impl<T: Copy + Default> Clone for AlignedBuffer<T> {
+    fn clone(&self) -> Self {
+        Self::from_fn(self.len, false, |i| self[i])
+    }
+}
+
+impl<T: Copy + Default> Deref for AlignedBuffer<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        // Safety: the allocation holds len initialized elements
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T: Copy + Default> DerefMut for AlignedBuffer<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        // Safety: the allocation holds len initialized elements and self is borrowed mutably
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T: Copy + Default + std::fmt::Debug> std::fmt::Debug for AlignedBuffer<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+// SIMD helpers for the kernels
+
+/// Indicates if a slice can be read with aligned 8-lane SIMD loads
+pub fn is_simd_aligned<T: Vectorizable>(data: &[T]) -> bool {
+    data.as_ptr().cast::<T::Lanes>().is_aligned()
+}
+
+/// Load 8 elements starting at data[i]
+///
+/// # Safety
+///
+/// When ALIGNED is true, &data[i] must be aligned for the lanes of T
+#[inline(always)]
+pub unsafe fn simd_load<T: Vectorizable, const ALIGNED: bool>(data: &[T], i: usize) -> T::Lanes {
+    if ALIGNED {
+        debug_assert!(is_simd_aligned(&data[i..]));
+        // Safety: the alignment is guaranteed by the caller
+        unsafe { T::Lanes::from_aligned_slice(&data[i..]) }
+    } else {
+        T::Lanes::from_slice(&data[i..])
+    }
+}
+
+/// Store 8 elements starting at data[i]
+///
+/// # Safety
+///
+/// When ALIGNED is true, &data[i] must be aligned for the lanes of T
+#[inline(always)]
+pub unsafe fn simd_store<T: Vectorizable, const ALIGNED: bool>(data: &mut [T], i: usize, value: T::Lanes) {
+    if ALIGNED {
+        debug_assert!(is_simd_aligned(&data[i..]));
+        // Safety: the alignment is guaranteed by the caller
+        unsafe { value.copy_to_aligned_slice(&mut data[i..]) };
+    } else {
+        value.copy_to_slice(&mut data[i..]);
+    }
+}
+
+// The tests
+
+#[cfg(test)]
+mod tests {
+    use crate::aligned_buffer::*;
+    use crate::argmax_expr::argmax;
+    use crate::batch_outer_expr::batch_outer;
+    use crate::batch_softmax_expr::batch_softmax;
+    use crate::batch_stable_softmax_expr::batch_stable_softmax;
+    use crate::bias_add_expr::bias_add;
+    use crate::bias_batch_sum_expr::bias_batch_sum;
+    use crate::etl_expr::EtlExpr;
+    use crate::fixed_matrix_2d::FixedMatrix2d;
+    use crate::fixed_vector::FixedVector;
+    use crate::matrix_2d::Matrix2d;
+    use crate::matrix_3d::Matrix3d;
+    use crate::matrix_4d::Matrix4d;
+    use crate::tensor::Tensor;
+    use crate::vector::Vector;
+
+    fn is_aligned<T>(data: &[T]) -> bool {
+        (data.as_ptr() as usize).is_multiple_of(ALIGNMENT)
+    }
+
+    #[test]
+    fn new() {
+        let buffer = AlignedBuffer::<f32>::new(13);
+
+        assert_eq!(buffer.len(), 13);
+        assert!(buffer.iter().all(|v| *v == 0.0));
+        assert!(is_aligned(&buffer));
+    }
+
+    #[test]
+    fn empty() {
+        let buffer = AlignedBuffer::<f64>::new(0);
+
+        assert!(buffer.is_empty());
+        assert!(is_aligned(&buffer));
+    }
+
+    #[test]
+    fn clone() {
+        let mut buffer = AlignedBuffer::<i64>::from_slice(&[1, 2, 3]);
+        let copy = buffer.clone();
+        buffer[0] = 9;
+
+        assert_eq!(&buffer[..], &[9, 2, 3]);
+        assert_eq!(&copy[..], &[1, 2, 3]);
+        assert!(is_aligned(&copy));
+
+        // The clone of a scratch buffer is not given to the workspace
+        let scratch = AlignedBuffer::<i64>::from_workspace_slice(&[4, 5]);
+        assert!(scratch.pooled);
+        assert!(!scratch.clone().pooled);
+    }
+
+    #[test]
+    fn simd_load_store() {
+        let mut buffer = AlignedBuffer::<i32>::from_slice(&(0..16).collect::<Vec<_>>());
+
+        assert!(is_simd_aligned(&buffer));
+        assert!(!is_simd_aligned(&buffer[1..]));
+
+        let aligned = unsafe { simd_load::<i32, true>(&buffer, 8) };
+        let unaligned = unsafe { simd_load::<i32, false>(&buffer, 3) };
+
+        assert_eq!(aligned.to_array(), [8, 9, 10, 11, 12, 13, 14, 15]);
+        assert_eq!(unaligned.to_array(), [3, 4, 5, 6, 7, 8, 9, 10]);
+
+        unsafe { simd_store::<i32, true>(&mut buffer, 0, aligned) };
+        unsafe { simd_store::<i32, false>(&mut buffer, 1, unaligned) };
+
+        assert_eq!(&buffer[..9], &[8, 3, 4, 5, 6, 7, 8, 9, 10]);
+    }
+
+    #[test]
+    fn containers_are_aligned() {
+        for size in [1, 3, 8, 17] {
+            assert!(is_aligned(&Vector::<f32>::new(size).data));
+            assert!(is_aligned(&Matrix2d::<f64>::new(size, 3).data));
+            assert!(is_aligned(&Matrix3d::<i32>::new(2, size, 3).data));
+            assert!(is_aligned(&Matrix4d::<i64>::new(2, 2, size, 3).data));
+            assert!(is_aligned(&Tensor::<f32, 5>::new([2, 1, size, 1, 3]).data));
+
+            let a = Matrix2d::<f32>::new(size, 3);
+            assert!(is_aligned(&a.clone().data));
+            assert!(is_aligned(&Matrix2d::<f32>::new_from_expr(&a + &a).data));
+        }
+
+        let v = FixedVector::<f32, 3>::new();
+        let m = FixedMatrix2d::<i64, 3, 5>::new();
+
+        assert!(is_aligned(&v.data));
+        assert!(is_aligned(m.data.as_flattened()));
+        assert!(is_aligned(&Box::new(v).data));
+    }
+
+    #[test]
+    fn temps_are_aligned() {
+        let a = Matrix2d::<f32>::new_iota(5, 3, 1.0);
+        let b = Matrix2d::<f32>::new_iota(3, 7, 2.0);
+        let c = Matrix2d::<f32>::new_iota(5, 7, 3.0);
+        let bias = Vector::<f32>::new_iota(3, 1.0);
+
+        assert!(is_aligned((&a * &b).get_data()));
+        assert!(is_aligned(argmax(&a).get_data()));
+        assert!(is_aligned(batch_outer(&a, &c).get_data()));
+        assert!(is_aligned(batch_softmax(&a).get_data()));
+        assert!(is_aligned(batch_stable_softmax(&a).get_data()));
+        assert!(is_aligned(bias_add(&a, &bias).get_data()));
+        assert!(is_aligned(bias_batch_sum(&a).get_data()));
+    }
+}
@@ -1,3 +1,5 @@
+use crate::deferred_temp::DeferredTemp;
+use crate::etl_error::EtlError;
 use crate::etl_expr::*;
 
 // The declaration of ArgMaxExpr
@@ -5,7 +7,7 @@ use crate::etl_expr::*;
 #[derive(Clone)]
 pub struct ArgMaxExpr<T: EtlValueType, Expr: WrappableExpr<T>> {
     expr: EtlWrapper<T, Expr::WrappedAs>,
-    pub temp: Vec<T>,
+    temp: DeferredTemp<T>,
 }
 
 // The functions of ArgMaxExpr
@@ -16,60 +18,24 @@ impl<T: EtlValueType, Expr: WrappableExpr<T>> ArgMaxExpr<T, Expr> {
             panic!("argmax only works on 2D expression");
         }
 
-        let mut expr = Self {
+        Self {
             expr: expr.wrap(),
-            temp: Vec::<T>::new(),
-        };
-
-        let mut temp = vec![T::default(); padded_size(expr.size())];
-        expr.compute_argmax_impl(&mut temp);
-        expr.temp = temp;
-
-        expr
-    }
-
-    fn compute_argmax(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        output[..self.temp.len()].copy_from_slice(&self.temp[..]);
-    }
-
-    fn compute_argmax_add(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (lhs, rhs) in output.iter_mut().zip(self.temp.iter()) {
-            *lhs += *rhs;
+            temp: DeferredTemp::new(),
         }
     }
 
-    fn compute_argmax_sub(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (lhs, rhs) in output.iter_mut().zip(self.temp.iter()) {
-            *lhs -= *rhs;
-        }
+    fn temp(&self) -> &[T] {
+        self.temp.get_or_compute(self.size(), |temp| self.compute_argmax_impl(temp))
     }
 
-    fn compute_argmax_scale(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (lhs, rhs) in output.iter_mut().zip(self.temp.iter()) {
-            *lhs *= *rhs;
-        }
-    }
-
-    fn compute_argmax_div(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (lhs, rhs) in output.iter_mut().zip(self.temp.iter()) {
-            *lhs /= *rhs;
-        }
+    fn compute_argmax(&self, output: &mut [T]) {
+        self.temp.compute_into(output, |output| self.compute_argmax_impl(output));
     }
 
-    fn compute_argmax_impl(&self, output: &mut Vec<T>) {
+    fn compute_argmax_impl(&self, output: &mut [T]) {
         if Expr::DIMENSIONS == 2 {
             let rows = self.expr.value.rows();
-            let columns = self.expr.value.columns();
+            let columns = self.expr.value.dim(1);
 
             let functor = |out: &mut [T], expr: &[T]| {
                 for row in 0..rows {
@@ -96,18 +62,28 @@ impl<T: EtlValueType, Expr: WrappableExpr<T>> ArgMaxExpr<T, Expr> {
         }
     }
 
-    fn validate_batch_softmax<OutputExpr: EtlExpr<T>>(&self, expr: &OutputExpr) {
+    fn validate_batch_softmax<OutputExpr: EtlExpr<T>>(&self, expr: &OutputExpr) -> Result<(), EtlError> {
         if OutputExpr::DIMENSIONS != 1 {
-            panic!("The output of argmax must be a 1D Vector");
+            return Err(EtlError::RankMismatch {
+                op: "assignment of argmax result",
+                expected: 1,
+                actual: OutputExpr::DIMENSIONS,
+            });
         }
 
         if Expr::DIMENSIONS == 2 {
             if expr.size() != self.expr.value.rows() {
-                panic!("Invalid dimensions for assignment of batch_softmax result");
+                return Err(EtlError::ShapeMismatch {
+                    op: "assignment of argmax result",
+                    lhs: expr_dims(expr),
+                    rhs: expr_dims(self),
+                });
             }
         } else {
             panic!("This code should be unreachable!");
         }
+
+        Ok(())
     }
 }
 
@@ -124,11 +100,11 @@ impl<T: EtlValueType, Expr: WrappableExpr<T>> EtlExpr<T> for ArgMaxExpr<T, Expr>
         Self: 'x;
 
     fn iter(&self) -> Self::Iter<'_> {
-        self.temp.iter().cloned()
+        self.temp().iter().cloned()
     }
 
     fn iter_range(&self, range: std::ops::Range<usize>) -> Self::Iter<'_> {
-        self.temp[range].iter().cloned()
+        self.temp()[range].iter().cloned()
     }
 
     fn size(&self) -> usize {
@@ -139,40 +115,36 @@ impl<T: EtlValueType, Expr: WrappableExpr<T>> EtlExpr<T> for ArgMaxExpr<T, Expr>
         self.expr.value.rows()
     }
 
-    fn columns(&self) -> usize {
-        self.expr.value.columns()
-    }
-
-    fn validate_assign<OutputExpr: EtlExpr<T>>(&self, expr: &OutputExpr) {
-        self.validate_batch_softmax(expr);
+    fn try_validate_assign<OutputExpr: EtlExpr<T>>(&self, expr: &OutputExpr) -> Result<(), EtlError> {
+        self.validate_batch_softmax(expr)
     }
 
-    fn compute_into(&self, output: &mut Vec<T>) {
+    fn compute_into(&self, output: &mut [T]) {
         self.compute_argmax(output);
     }
 
-    fn compute_into_add(&self, output: &mut Vec<T>) {
-        self.compute_argmax_add(output);
+    fn compute_into_add(&self, output: &mut [T]) {
+        self.temp.compute_into_add(output, self.size(), |temp| self.compute_argmax_impl(temp));
     }
 
-    fn compute_into_sub(&self, output: &mut Vec<T>) {
-        self.compute_argmax_sub(output);
+    fn compute_into_sub(&self, output: &mut [T]) {
+        self.temp.compute_into_sub(output, self.size(), |temp| self.compute_argmax_impl(temp));
     }
 
-    fn compute_into_scale(&self, output: &mut Vec<T>) {
-        self.compute_argmax_scale(output);
+    fn compute_into_scale(&self, output: &mut [T]) {
+        self.temp.compute_into_scale(output, self.size(), |temp| self.compute_argmax_impl(temp));
     }
 
-    fn compute_into_div(&self, output: &mut Vec<T>) {
-        self.compute_argmax_div(output);
+    fn compute_into_div(&self, output: &mut [T]) {
+        self.temp.compute_into_div(output, self.size(), |temp| self.compute_argmax_impl(temp));
     }
 
     fn at(&self, i: usize) -> T {
-        self.temp[i]
+        self.temp()[i]
     }
 
     fn get_data(&self) -> &[T] {
-        &self.temp
+        self.temp()
     }
 }
 
@@ -192,7 +164,7 @@ impl<T: EtlValueType, Expr: WrappableExpr<T>> EtlWrappable<T> for ArgMaxExpr<T,
 // ArgMaxExpr computes as copy
 impl<T: EtlValueType, Expr: WrappableExpr<T>> EtlComputable<T> for ArgMaxExpr<T, Expr> {
     fn to_data(&self) -> Vec<T> {
-        self.temp.clone()
+        self.temp().to_vec()
     }
 }
 
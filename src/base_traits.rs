@@ -1,11 +1,181 @@
+use crate::etl_expr::EtlValueType;
+
 use std::simd::Simd;
 
-pub trait SimdHelper: Sized + std::ops::AddAssign + std::ops::Add<Output = Self> + std::ops::MulAssign + std::ops::Mul<Output = Self> {}
+/// Eight lanes of values, as used by the vectorized kernels
+pub trait SimdHelper<T>: Sized + Copy + std::ops::AddAssign + std::ops::Add<Output = Self> + std::ops::MulAssign + std::ops::Mul<Output = Self> {
+    fn splat(value: T) -> Self;
+    fn from_slice(slice: &[T]) -> Self;
+    fn as_array(&self) -> &[T; 8];
+    fn to_array(self) -> [T; 8];
+    fn copy_to_slice(self, slice: &mut [T]);
+
+    /// Load the first 8 values of an aligned slice
+    ///
+    /// # Safety
+    ///
+    /// The slice must be aligned for Self
+    unsafe fn from_aligned_slice(slice: &[T]) -> Self {
+        Self::from_slice(slice)
+    }
+
+    /// Store the lanes into the first 8 values of an aligned slice
+    ///
+    /// # Safety
+    ///
+    /// The slice must be aligned for Self
+    unsafe fn copy_to_aligned_slice(self, slice: &mut [T]) {
+        self.copy_to_slice(slice)
+    }
+}
+
+macro_rules! impl_simd_helper {
+    ($type:ty) => {
+        impl SimdHelper<$type> for Simd<$type, 8> {
+            fn splat(value: $type) -> Self {
+                Simd::splat(value)
+            }
+
+            fn from_slice(slice: &[$type]) -> Self {
+                Simd::from_slice(slice)
+            }
+
+            fn as_array(&self) -> &[$type; 8] {
+                Simd::as_array(self)
+            }
+
+            fn to_array(self) -> [$type; 8] {
+                Simd::to_array(self)
+            }
+
+            fn copy_to_slice(self, slice: &mut [$type]) {
+                Simd::copy_to_slice(self, slice)
+            }
+
+            unsafe fn from_aligned_slice(slice: &[$type]) -> Self {
+                let lanes = &slice[..8];
+                // Safety: the bounds are checked above and the alignment is guaranteed by the caller
+                unsafe { lanes.as_ptr().cast::<Self>().read() }
+            }
+
+            unsafe fn copy_to_aligned_slice(self, slice: &mut [$type]) {
+                let lanes = &mut slice[..8];
+                // Safety: the bounds are checked above and the alignment is guaranteed by the caller
+                unsafe { lanes.as_mut_ptr().cast::<Self>().write(self) };
+            }
+        }
+    };
+}
+
+impl_simd_helper!(i64);
+impl_simd_helper!(i32);
+impl_simd_helper!(i16);
+impl_simd_helper!(i8);
+impl_simd_helper!(u64);
+impl_simd_helper!(u32);
+impl_simd_helper!(u16);
+impl_simd_helper!(u8);
+impl_simd_helper!(usize);
+impl_simd_helper!(f32);
+impl_simd_helper!(f64);
+
+/// Eight values processed one at a time, for the value types that std::simd does not support
+#[derive(Clone, Copy)]
+pub struct ScalarLanes<T>(pub [T; 8]);
+
+impl<T: Copy + std::ops::Add<Output = T>> std::ops::Add for ScalarLanes<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] + other.0[i]))
+    }
+}
+
+impl<T: Copy + std::ops::Add<Output = T>> std::ops::AddAssign for ScalarLanes<T> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Copy + std::ops::Mul<Output = T>> std::ops::Mul for ScalarLanes<T> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] * other.0[i]))
+    }
+}
+
+impl<T: Copy + std::ops::Mul<Output = T>> std::ops::MulAssign for ScalarLanes<T> {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+impl<T: Copy + std::ops::Add<Output = T> + std::ops::Mul<Output = T>> SimdHelper<T> for ScalarLanes<T> {
+    fn splat(value: T) -> Self {
+        Self([value; 8])
+    }
+
+    fn from_slice(slice: &[T]) -> Self {
+        Self(slice[..8].try_into().unwrap())
+    }
+
+    fn as_array(&self) -> &[T; 8] {
+        &self.0
+    }
+
+    fn to_array(self) -> [T; 8] {
+        self.0
+    }
+
+    fn copy_to_slice(self, slice: &mut [T]) {
+        slice[..8].copy_from_slice(&self.0);
+    }
+}
+
+/// The value types that have eight lanes for the vectorized kernels
+pub trait Vectorizable: Sized {
+    type Lanes: SimdHelper<Self>;
+
+    /// The type the kernels that accumulate and the element-wise expressions compute in, Self
+    /// except for the reduced precision types
+    type Compute: EtlValueType + Vectorizable<Compute = Self::Compute>;
+
+    /// Indicates if Compute is not Self and the values must be converted
+    const CONVERTED: bool = false;
+
+    fn to_compute(self) -> Self::Compute;
+    fn from_compute(value: Self::Compute) -> Self;
+}
+
+macro_rules! impl_vectorizable {
+    ($type:ty) => {
+        impl Vectorizable for $type {
+            type Lanes = Simd<$type, 8>;
+            type Compute = $type;
 
-impl SimdHelper for Simd<i64, 8> {}
-impl SimdHelper for Simd<i32, 8> {}
-impl SimdHelper for Simd<f32, 8> {}
-impl SimdHelper for Simd<f64, 8> {}
+            fn to_compute(self) -> Self {
+                self
+            }
+
+            fn from_compute(value: Self) -> Self {
+                value
+            }
+        }
+    };
+}
+
+impl_vectorizable!(i64);
+impl_vectorizable!(i32);
+impl_vectorizable!(i16);
+impl_vectorizable!(i8);
+impl_vectorizable!(u64);
+impl_vectorizable!(u32);
+impl_vectorizable!(u16);
+impl_vectorizable!(u8);
+impl_vectorizable!(usize);
+impl_vectorizable!(f32);
+impl_vectorizable!(f64);
 
 // Rust is pretty much retarded for getting constants out a generic type
 pub trait Constants {
@@ -31,25 +201,31 @@ impl Constants for f32 {
     }
 }
 
-impl Constants for i64 {
-    fn one() -> Self {
-        1
-    }
-    fn zero() -> Self {
-        0
-    }
+macro_rules! impl_integer_constants {
+    ($type:ty) => {
+        impl Constants for $type {
+            fn one() -> Self {
+                1
+            }
+            fn zero() -> Self {
+                0
+            }
+        }
+    };
 }
 
-impl Constants for i32 {
-    fn one() -> Self {
-        1
-    }
-    fn zero() -> Self {
-        0
-    }
-}
+impl_integer_constants!(i64);
+impl_integer_constants!(i32);
+impl_integer_constants!(i16);
+impl_integer_constants!(i8);
+impl_integer_constants!(u64);
+impl_integer_constants!(u32);
+impl_integer_constants!(u16);
+impl_integer_constants!(u8);
+impl_integer_constants!(usize);
 
-pub trait Float {
+// The floating point types are all signed
+pub trait Float: std::ops::Neg<Output = Self> {
     fn exp(self) -> Self;
     fn sqrt(self) -> Self;
     fn ln(self) -> Self;
@@ -83,10 +259,23 @@ impl Float for f64 {
     }
 }
 
+// The unsigned types cannot be negated and do not have abs
 pub trait Abs {
     fn abs(self) -> Self;
 }
 
+impl Abs for i8 {
+    fn abs(self) -> Self {
+        self.abs()
+    }
+}
+
+impl Abs for i16 {
+    fn abs(self) -> Self {
+        self.abs()
+    }
+}
+
 impl Abs for i32 {
     fn abs(self) -> Self {
         self.abs()
@@ -110,3 +299,32 @@ impl Abs for f64 {
         self.abs()
     }
 }
+
+/// Conversion between value types, with the semantics of the as operator
+pub trait Cast<U> {
+    fn cast(self) -> U;
+}
+
+macro_rules! impl_primitive_cast {
+    ($from:ty => $($to:ty),*) => {
+        $(
+            impl Cast<$to> for $from {
+                fn cast(self) -> $to {
+                    self as $to
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive_cast!(i64 => i64, i32, i16, i8, u64, u32, u16, u8, usize, f32, f64);
+impl_primitive_cast!(i32 => i64, i32, i16, i8, u64, u32, u16, u8, usize, f32, f64);
+impl_primitive_cast!(i16 => i64, i32, i16, i8, u64, u32, u16, u8, usize, f32, f64);
+impl_primitive_cast!(i8 => i64, i32, i16, i8, u64, u32, u16, u8, usize, f32, f64);
+impl_primitive_cast!(u64 => i64, i32, i16, i8, u64, u32, u16, u8, usize, f32, f64);
+impl_primitive_cast!(u32 => i64, i32, i16, i8, u64, u32, u16, u8, usize, f32, f64);
+impl_primitive_cast!(u16 => i64, i32, i16, i8, u64, u32, u16, u8, usize, f32, f64);
+impl_primitive_cast!(u8 => i64, i32, i16, i8, u64, u32, u16, u8, usize, f32, f64);
+impl_primitive_cast!(usize => i64, i32, i16, i8, u64, u32, u16, u8, usize, f32, f64);
+impl_primitive_cast!(f32 => i64, i32, i16, i8, u64, u32, u16, u8, usize, f32, f64);
+impl_primitive_cast!(f64 => i64, i32, i16, i8, u64, u32, u16, u8, usize, f32, f64);
@@ -1,85 +1,54 @@
+use crate::aligned_buffer::*;
 use crate::base_traits::*;
+use crate::deferred_temp::DeferredTemp;
+use crate::etl_error::EtlError;
 use crate::etl_expr::*;
 
-use std::simd::*;
-
 // The declaration of BatchOuterExpr
 
 /// Expression representing the batched addition of biases to a matrix
 #[derive(Clone)]
 pub struct BatchOuterExpr<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>>
 where
-    Simd<T, 8>: SimdHelper,
+    T: Vectorizable,
 {
     lhs: EtlWrapper<T, LeftExpr::WrappedAs>,
     rhs: EtlWrapper<T, RightExpr::WrappedAs>,
-    pub temp: Vec<T>,
+    temp: DeferredTemp<T>,
 }
 
 // The functions of BatchOuterExpr
 
 impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> BatchOuterExpr<T, LeftExpr, RightExpr>
 where
-    Simd<T, 8>: SimdHelper,
+    T: Vectorizable,
 {
     pub fn new(lhs: LeftExpr, rhs: RightExpr) -> Self {
         if LeftExpr::DIMENSIONS == 2 && RightExpr::DIMENSIONS == 2 {
             if lhs.rows() != rhs.rows() {
-                panic!("Invalid batch_outer dimensions ([{},{}]*[{},{}])", lhs.rows(), lhs.columns(), rhs.rows(), rhs.columns());
+                panic!("Invalid batch_outer dimensions ([{},{}]*[{},{}])", lhs.rows(), lhs.dim(1), rhs.rows(), rhs.dim(1));
             }
         } else {
             panic!("Invalid batch_outer dimensions ({}D*{}D)", LeftExpr::DIMENSIONS, RightExpr::DIMENSIONS);
         }
 
-        let mut expr = Self {
+        Self {
             lhs: lhs.wrap(),
             rhs: rhs.wrap(),
-            temp: Vec::<T>::new(),
-        };
-
-        let mut temp = vec![T::default(); padded_size(expr.size())];
-        expr.compute_batch_outer_impl(&mut temp);
-        expr.temp = temp;
-
-        expr
-    }
-
-    fn compute_batch_outer(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        output[..self.temp.len()].copy_from_slice(&self.temp[..]);
-    }
-
-    fn compute_batch_outer_add(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (lhs, rhs) in output.iter_mut().zip(self.temp.iter()) {
-            *lhs += *rhs;
+            temp: DeferredTemp::new(),
         }
     }
 
-    fn compute_batch_outer_sub(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (lhs, rhs) in output.iter_mut().zip(self.temp.iter()) {
-            *lhs -= *rhs;
-        }
+    fn temp(&self) -> &[T] {
+        self.temp.get_or_compute(self.size(), |temp| self.compute_batch_outer_impl(temp))
     }
 
-    fn compute_batch_outer_scale(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (lhs, rhs) in output.iter_mut().zip(self.temp.iter()) {
-            *lhs *= *rhs;
-        }
-    }
-
-    fn compute_batch_outer_div(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (lhs, rhs) in output.iter_mut().zip(self.temp.iter()) {
-            *lhs /= *rhs;
-        }
+    fn compute_batch_outer(&self, output: &mut [T]) {
+        self.temp.compute_into(output, |output| {
+            // The kernel accumulates into the output
+            output.fill(T::default());
+            self.compute_batch_outer_impl(output);
+        });
     }
 
     // Note: Cannot use reduce_sum in generic code
@@ -152,6 +121,19 @@ where
     // For medium-to-large matrices, we can transpose lhs and rhs and then we can vectorize the
     // inner loop properly
     fn transposed_kernel(m_start: usize, m_end: usize, n: usize, b: usize, out: &mut [T], lhs_opp: &[T], rhs_opp: &[T]) {
+        // The transposed inputs are aligned buffers, so every row starts aligned when b is a
+        // multiple of 8
+        if b.is_multiple_of(8) && is_simd_aligned(lhs_opp) && is_simd_aligned(rhs_opp) {
+            // Safety: the alignment of lhs_opp and rhs_opp is checked above
+            unsafe { Self::transposed_kernel_impl::<true>(m_start, m_end, n, b, out, lhs_opp, rhs_opp) }
+        } else {
+            // Safety: unaligned loads have no requirements
+            unsafe { Self::transposed_kernel_impl::<false>(m_start, m_end, n, b, out, lhs_opp, rhs_opp) }
+        }
+    }
+
+    // Safety: when ALIGNED is true, lhs_opp and rhs_opp must be aligned for Simd<T, 8> and b a multiple of 8
+    unsafe fn transposed_kernel_impl<const ALIGNED: bool>(m_start: usize, m_end: usize, n: usize, b: usize, out: &mut [T], lhs_opp: &[T], rhs_opp: &[T]) {
         let lanes = 8;
 
         let mut row = m_start;
@@ -168,25 +150,25 @@ where
                 let c3 = column + 2;
                 let c4 = column + 3;
 
-                let mut xmm1 = Simd::<T, 8>::splat(T::default());
-                let mut xmm2 = Simd::<T, 8>::splat(T::default());
-                let mut xmm3 = Simd::<T, 8>::splat(T::default());
-                let mut xmm4 = Simd::<T, 8>::splat(T::default());
-                let mut xmm5 = Simd::<T, 8>::splat(T::default());
-                let mut xmm6 = Simd::<T, 8>::splat(T::default());
-                let mut xmm7 = Simd::<T, 8>::splat(T::default());
-                let mut xmm8 = Simd::<T, 8>::splat(T::default());
+                let mut xmm1 = T::Lanes::splat(T::default());
+                let mut xmm2 = T::Lanes::splat(T::default());
+                let mut xmm3 = T::Lanes::splat(T::default());
+                let mut xmm4 = T::Lanes::splat(T::default());
+                let mut xmm5 = T::Lanes::splat(T::default());
+                let mut xmm6 = T::Lanes::splat(T::default());
+                let mut xmm7 = T::Lanes::splat(T::default());
+                let mut xmm8 = T::Lanes::splat(T::default());
 
                 let mut batch = 0;
 
                 while batch + lanes - 1 < b {
-                    let l1 = Simd::<T, 8>::from_slice(&lhs_opp[r1 * b + batch..]);
-                    let l2 = Simd::<T, 8>::from_slice(&lhs_opp[r2 * b + batch..]);
+                    let l1 = unsafe { simd_load::<T, ALIGNED>(lhs_opp, r1 * b + batch) };
+                    let l2 = unsafe { simd_load::<T, ALIGNED>(lhs_opp, r2 * b + batch) };
 
-                    let r1 = Simd::<T, 8>::from_slice(&rhs_opp[c1 * b + batch..]);
-                    let r2 = Simd::<T, 8>::from_slice(&rhs_opp[c2 * b + batch..]);
-                    let r3 = Simd::<T, 8>::from_slice(&rhs_opp[c3 * b + batch..]);
-                    let r4 = Simd::<T, 8>::from_slice(&rhs_opp[c4 * b + batch..]);
+                    let r1 = unsafe { simd_load::<T, ALIGNED>(rhs_opp, c1 * b + batch) };
+                    let r2 = unsafe { simd_load::<T, ALIGNED>(rhs_opp, c2 * b + batch) };
+                    let r3 = unsafe { simd_load::<T, ALIGNED>(rhs_opp, c3 * b + batch) };
+                    let r4 = unsafe { simd_load::<T, ALIGNED>(rhs_opp, c4 * b + batch) };
 
                     // TODO: Missed opportunity to use FMA here
                     xmm1 += l1 * r1;
@@ -243,19 +225,19 @@ where
                 let c1 = column;
                 let c2 = column + 1;
 
-                let mut xmm1 = Simd::<T, 8>::splat(T::default());
-                let mut xmm2 = Simd::<T, 8>::splat(T::default());
-                let mut xmm3 = Simd::<T, 8>::splat(T::default());
-                let mut xmm4 = Simd::<T, 8>::splat(T::default());
+                let mut xmm1 = T::Lanes::splat(T::default());
+                let mut xmm2 = T::Lanes::splat(T::default());
+                let mut xmm3 = T::Lanes::splat(T::default());
+                let mut xmm4 = T::Lanes::splat(T::default());
 
                 let mut batch = 0;
 
                 while batch + lanes - 1 < b {
-                    let l1 = Simd::<T, 8>::from_slice(&lhs_opp[r1 * b + batch..]);
-                    let l2 = Simd::<T, 8>::from_slice(&lhs_opp[r2 * b + batch..]);
+                    let l1 = unsafe { simd_load::<T, ALIGNED>(lhs_opp, r1 * b + batch) };
+                    let l2 = unsafe { simd_load::<T, ALIGNED>(lhs_opp, r2 * b + batch) };
 
-                    let r1 = Simd::<T, 8>::from_slice(&rhs_opp[c1 * b + batch..]);
-                    let r2 = Simd::<T, 8>::from_slice(&rhs_opp[c2 * b + batch..]);
+                    let r1 = unsafe { simd_load::<T, ALIGNED>(rhs_opp, c1 * b + batch) };
+                    let r2 = unsafe { simd_load::<T, ALIGNED>(rhs_opp, c2 * b + batch) };
 
                     xmm1 += l1 * r1;
                     xmm2 += l1 * r2;
@@ -320,28 +302,28 @@ where
         }
     }
 
-    fn compute_batch_outer_impl(&self, output: &mut Vec<T>) {
+    fn compute_batch_outer_impl(&self, output: &mut [T]) {
         if LeftExpr::DIMENSIONS == 2 && RightExpr::DIMENSIONS == 2 {
-            let m = self.lhs.value.columns();
-            let n = self.rhs.value.columns();
+            let m = self.lhs.value.dim(1);
+            let n = self.rhs.value.dim(1);
             let b = self.lhs.value.rows();
 
             if m * n <= 16384 {
                 let small_kernel = |out: &mut [T], lhs: &[T], rhs: &[T]| Self::small_kernel(m, n, b, out, lhs, rhs);
                 forward_data_binary(output, &self.lhs.value, &self.rhs.value, small_kernel);
             } else {
-                let mut rhs_opp = Vec::<T>::new();
-                let mut lhs_opp = Vec::<T>::new();
+                let mut rhs_opp = AlignedBuffer::<T>::new(0);
+                let mut lhs_opp = AlignedBuffer::<T>::new(0);
 
                 let mut transpose_first_kernel = |_out: &mut [T], lhs: &[T], rhs: &[T]| {
-                    lhs_opp = lhs.to_vec();
+                    lhs_opp = AlignedBuffer::from_workspace_slice(lhs);
                     for lhs_row in 0..b {
                         for (lhs_column, lhs_iter) in lhs[lhs_row * m..lhs_row * m + m].iter().enumerate() {
                             lhs_opp[lhs_column * b + lhs_row] = *lhs_iter;
                         }
                     }
 
-                    rhs_opp = rhs.to_vec();
+                    rhs_opp = AlignedBuffer::from_workspace_slice(rhs);
                     for rhs_row in 0..b {
                         for (rhs_column, rhs_iter) in rhs[rhs_row * n..rhs_row * n + n].iter().enumerate() {
                             rhs_opp[rhs_column * b + rhs_row] = *rhs_iter;
@@ -361,25 +343,35 @@ where
         }
     }
 
-    fn validate_batch_outer<OutputExpr: EtlExpr<T>>(&self, lhs: &OutputExpr) {
+    fn validate_batch_outer<OutputExpr: EtlExpr<T>>(&self, lhs: &OutputExpr) -> Result<(), EtlError> {
         if OutputExpr::DIMENSIONS != 2 {
-            panic!("The output of batch_outer must be a 2D Matrix");
+            return Err(EtlError::RankMismatch {
+                op: "assignment of batch_outer result",
+                expected: 2,
+                actual: OutputExpr::DIMENSIONS,
+            });
         }
 
         if LeftExpr::DIMENSIONS == 2 && RightExpr::DIMENSIONS == 2 {
-            if lhs.rows() != self.lhs.value.columns() || lhs.columns() != self.rhs.value.columns() {
-                panic!("Invalid dimensions for assignment of batch_outer result");
+            if lhs.rows() != self.lhs.value.dim(1) || lhs.dim(1) != self.rhs.value.dim(1) {
+                return Err(EtlError::ShapeMismatch {
+                    op: "assignment of batch_outer result",
+                    lhs: expr_dims(lhs),
+                    rhs: expr_dims(self),
+                });
             }
         } else {
             panic!("This code should be unreachable!");
         }
+
+        Ok(())
     }
 }
 
 // BatchOuterExpr is an EtlExpr
 impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> EtlExpr<T> for BatchOuterExpr<T, LeftExpr, RightExpr>
 where
-    Simd<T, 8>: SimdHelper,
+    T: Vectorizable,
 {
     const DIMENSIONS: usize = 2;
     const TYPE: EtlType = EtlType::Smart;
@@ -392,62 +384,69 @@ where
         Self: 'x;
 
     fn iter(&self) -> Self::Iter<'_> {
-        self.temp.iter().cloned()
+        self.temp().iter().cloned()
     }
 
     fn iter_range(&self, range: std::ops::Range<usize>) -> Self::Iter<'_> {
-        self.temp[range].iter().cloned()
+        self.temp()[range].iter().cloned()
     }
 
     fn size(&self) -> usize {
-        self.lhs.value.columns() * self.rhs.value.columns()
+        self.lhs.value.dim(1) * self.rhs.value.dim(1)
     }
 
     fn rows(&self) -> usize {
-        self.lhs.value.columns()
+        self.lhs.value.dim(1)
     }
 
-    fn columns(&self) -> usize {
-        self.rhs.value.columns()
+    fn dim(&self, i: usize) -> usize {
+        match i {
+            0 => self.rows(),
+            1 => self.rhs.value.dim(1),
+            _ => panic!("Invalid dimension access ({i} for 2D)"),
+        }
     }
 
-    fn validate_assign<OutputExpr: EtlExpr<T>>(&self, lhs: &OutputExpr) {
-        self.validate_batch_outer(lhs);
+    fn try_validate_assign<OutputExpr: EtlExpr<T>>(&self, lhs: &OutputExpr) -> Result<(), EtlError> {
+        self.validate_batch_outer(lhs)
     }
 
-    fn compute_into(&self, output: &mut Vec<T>) {
+    fn compute_into(&self, output: &mut [T]) {
         self.compute_batch_outer(output);
     }
 
-    fn compute_into_add(&self, output: &mut Vec<T>) {
-        self.compute_batch_outer_add(output);
+    fn compute_into_add(&self, output: &mut [T]) {
+        self.temp.compute_into_add(output, self.size(), |temp| self.compute_batch_outer_impl(temp));
     }
 
-    fn compute_into_sub(&self, output: &mut Vec<T>) {
-        self.compute_batch_outer_sub(output);
+    fn compute_into_sub(&self, output: &mut [T]) {
+        self.temp.compute_into_sub(output, self.size(), |temp| self.compute_batch_outer_impl(temp));
     }
 
-    fn compute_into_scale(&self, output: &mut Vec<T>) {
-        self.compute_batch_outer_scale(output);
+    fn compute_into_scale(&self, output: &mut [T]) {
+        self.temp.compute_into_scale(output, self.size(), |temp| self.compute_batch_outer_impl(temp));
     }
 
-    fn compute_into_div(&self, output: &mut Vec<T>) {
-        self.compute_batch_outer_div(output);
+    fn compute_into_div(&self, output: &mut [T]) {
+        self.temp.compute_into_div(output, self.size(), |temp| self.compute_batch_outer_impl(temp));
     }
 
     fn at(&self, i: usize) -> T {
-        self.temp[i]
+        self.temp()[i]
     }
 
     fn get_data(&self) -> &[T] {
-        &self.temp
+        self.temp()
     }
 }
 
+// BatchOuterExpr is an Etl2d
+impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> Etl2d<T> for BatchOuterExpr<T, LeftExpr, RightExpr> where T: Vectorizable {}
+
 // BatchOuterExpr is an EtlWrappable
 impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> EtlWrappable<T> for BatchOuterExpr<T, LeftExpr, RightExpr>
 where
-    Simd<T, 8>: SimdHelper,
+    T: Vectorizable,
 {
     type WrappedAs = BatchOuterExpr<T, LeftExpr, RightExpr>;
 
@@ -462,22 +461,19 @@ where
 // BatchOuterExpr computes as copy
 impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> EtlComputable<T> for BatchOuterExpr<T, LeftExpr, RightExpr>
 where
-    Simd<T, 8>: SimdHelper,
+    T: Vectorizable,
 {
     fn to_data(&self) -> Vec<T> {
-        self.temp.clone()
+        self.temp().to_vec()
     }
 }
 
 // Operations
 
-pub fn batch_outer<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>>(
+pub fn batch_outer<T: EtlValueType + Vectorizable, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>>(
     lhs: LeftExpr,
     rhs: RightExpr,
-) -> BatchOuterExpr<T, LeftExpr, RightExpr>
-where
-    Simd<T, 8>: SimdHelper,
-{
+) -> BatchOuterExpr<T, LeftExpr, RightExpr> {
     BatchOuterExpr::<T, LeftExpr, RightExpr>::new(lhs, rhs)
 }
 
@@ -492,7 +488,7 @@ crate::impl_scale_op_binary_expr_simd!(BatchOuterExpr<T, LeftExpr, RightExpr>);
 #[cfg(test)]
 mod tests {
     use crate::batch_outer_expr::batch_outer;
-    use crate::etl_expr::EtlExpr;
+    use crate::etl_expr::{Etl2d, EtlExpr};
     use crate::matrix_2d::Matrix2d;
 
     #[test]
@@ -609,33 +605,35 @@ mod tests {
     fn batch_outer_large_parallel() {
         let m = 171;
         let n = 111;
-        let b = 39;
 
-        let mut lhs = Matrix2d::<i64>::new(b, m);
-        let mut rhs = Matrix2d::<i64>::new(b, n);
+        // 40 takes the aligned path of the transposed kernel
+        for b in [39, 40] {
+            let mut lhs = Matrix2d::<i64>::new(b, m);
+            let mut rhs = Matrix2d::<i64>::new(b, n);
 
-        lhs.iota_fill(1);
-        rhs.iota_fill(2);
+            lhs.iota_fill(1);
+            rhs.iota_fill(2);
 
-        let mut c = Matrix2d::<i64>::new(m, n);
-        c |= batch_outer(&lhs, &rhs);
+            let mut c = Matrix2d::<i64>::new(m, n);
+            c |= batch_outer(&lhs, &rhs);
 
-        let mut c_ref = Matrix2d::<i64>::new(m, n);
+            let mut c_ref = Matrix2d::<i64>::new(m, n);
 
-        for row in 0..m {
-            for column in 0..n {
-                let mut v = 0;
+            for row in 0..m {
+                for column in 0..n {
+                    let mut v = 0;
 
-                for batch in 0..b {
-                    v += lhs.at2(batch, row) * rhs.at2(batch, column);
-                }
+                    for batch in 0..b {
+                        v += lhs.at2(batch, row) * rhs.at2(batch, column);
+                    }
 
-                *c_ref.at_mut(row, column) = v;
+                    *c_ref.at_mut(row, column) = v;
+                }
             }
-        }
 
-        for i in 0..(m * n) {
-            assert_eq!(c.at(i), c_ref.at(i), "Invalid value at index {i}");
+            for i in 0..(m * n) {
+                assert_eq!(c.at(i), c_ref.at(i), "Invalid value at index {i} for b={b}");
+            }
         }
     }
 }
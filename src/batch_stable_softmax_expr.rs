@@ -1,4 +1,6 @@
 use crate::base_traits::Float;
+use crate::deferred_temp::DeferredTemp;
+use crate::etl_error::EtlError;
 use crate::etl_expr::*;
 
 // The declaration of BatchStableSoftmaxExpr
@@ -7,7 +9,7 @@ use crate::etl_expr::*;
 #[derive(Clone)]
 pub struct BatchStableSoftmaxExpr<T: EtlValueType + Float, Expr: WrappableExpr<T>> {
     expr: EtlWrapper<T, Expr::WrappedAs>,
-    pub temp: Vec<T>,
+    temp: DeferredTemp<T>,
 }
 
 // The functions of BatchStableSoftmaxExpr
@@ -18,60 +20,24 @@ impl<T: EtlValueType + Float, Expr: WrappableExpr<T>> BatchStableSoftmaxExpr<T,
             panic!("Invalid batch_stable_softmax dimensions ({}D)", Expr::DIMENSIONS);
         }
 
-        let mut expr = Self {
+        Self {
             expr: expr.wrap(),
-            temp: Vec::<T>::new(),
-        };
-
-        let mut temp = vec![T::default(); padded_size(expr.size())];
-        expr.compute_batch_stable_softmax_impl(&mut temp);
-        expr.temp = temp;
-
-        expr
-    }
-
-    fn compute_batch_stable_softmax(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        output[..self.temp.len()].copy_from_slice(&self.temp[..]);
-    }
-
-    fn compute_batch_stable_softmax_add(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (lhs, rhs) in output.iter_mut().zip(self.temp.iter()) {
-            *lhs += *rhs;
+            temp: DeferredTemp::new(),
         }
     }
 
-    fn compute_batch_stable_softmax_sub(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (lhs, rhs) in output.iter_mut().zip(self.temp.iter()) {
-            *lhs -= *rhs;
-        }
+    fn temp(&self) -> &[T] {
+        self.temp.get_or_compute(self.size(), |temp| self.compute_batch_stable_softmax_impl(temp))
     }
 
-    fn compute_batch_stable_softmax_scale(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (lhs, rhs) in output.iter_mut().zip(self.temp.iter()) {
-            *lhs *= *rhs;
-        }
-    }
-
-    fn compute_batch_stable_softmax_div(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (lhs, rhs) in output.iter_mut().zip(self.temp.iter()) {
-            *lhs /= *rhs;
-        }
+    fn compute_batch_stable_softmax(&self, output: &mut [T]) {
+        self.temp.compute_into(output, |output| self.compute_batch_stable_softmax_impl(output));
     }
 
-    fn compute_batch_stable_softmax_impl(&self, output: &mut Vec<T>) {
+    fn compute_batch_stable_softmax_impl(&self, output: &mut [T]) {
         if Expr::DIMENSIONS == 2 {
             let b = self.expr.value.rows();
-            let m = self.expr.value.columns();
+            let m = self.expr.value.dim(1);
 
             let functor = |out: &mut [T], expr: &[T]| {
                 for batch in 0..b {
@@ -99,18 +65,28 @@ impl<T: EtlValueType + Float, Expr: WrappableExpr<T>> BatchStableSoftmaxExpr<T,
         }
     }
 
-    fn validate_batch_stable_softmax<OutputExpr: EtlExpr<T>>(&self, expr: &OutputExpr) {
+    fn validate_batch_stable_softmax<OutputExpr: EtlExpr<T>>(&self, expr: &OutputExpr) -> Result<(), EtlError> {
         if OutputExpr::DIMENSIONS != 2 {
-            panic!("The output of batch_stable_softmax must be a 2D Matrix");
+            return Err(EtlError::RankMismatch {
+                op: "assignment of batch_stable_softmax result",
+                expected: 2,
+                actual: OutputExpr::DIMENSIONS,
+            });
         }
 
         if Expr::DIMENSIONS == 2 {
             if expr.size() != self.expr.value.size() {
-                panic!("Invalid dimensions for assignment of batch_stable_softmax result");
+                return Err(EtlError::ShapeMismatch {
+                    op: "assignment of batch_stable_softmax result",
+                    lhs: expr_dims(expr),
+                    rhs: expr_dims(self),
+                });
             }
         } else {
             panic!("This code should be unreachable!");
         }
+
+        Ok(())
     }
 }
 
@@ -127,11 +103,11 @@ impl<T: EtlValueType + Float, Expr: WrappableExpr<T>> EtlExpr<T> for BatchStable
         Self: 'x;
 
     fn iter(&self) -> Self::Iter<'_> {
-        self.temp.iter().cloned()
+        self.temp().iter().cloned()
     }
 
     fn iter_range(&self, range: std::ops::Range<usize>) -> Self::Iter<'_> {
-        self.temp[range].iter().cloned()
+        self.temp()[range].iter().cloned()
     }
 
     fn size(&self) -> usize {
@@ -142,43 +118,54 @@ impl<T: EtlValueType + Float, Expr: WrappableExpr<T>> EtlExpr<T> for BatchStable
         self.expr.value.rows()
     }
 
-    fn columns(&self) -> usize {
-        self.expr.value.columns()
+    fn dim(&self, i: usize) -> usize {
+        match i {
+            0 => self.rows(),
+            1 => self.expr.value.dim(1),
+            _ => panic!("Invalid dimension access ({i} for 2D)"),
+        }
     }
 
-    fn validate_assign<OutputExpr: EtlExpr<T>>(&self, expr: &OutputExpr) {
-        self.validate_batch_stable_softmax(expr);
+    fn try_validate_assign<OutputExpr: EtlExpr<T>>(&self, expr: &OutputExpr) -> Result<(), EtlError> {
+        self.validate_batch_stable_softmax(expr)
     }
 
-    fn compute_into(&self, output: &mut Vec<T>) {
+    fn compute_into(&self, output: &mut [T]) {
         self.compute_batch_stable_softmax(output);
     }
 
-    fn compute_into_add(&self, output: &mut Vec<T>) {
-        self.compute_batch_stable_softmax_add(output);
+    fn compute_into_add(&self, output: &mut [T]) {
+        self.temp
+            .compute_into_add(output, self.size(), |temp| self.compute_batch_stable_softmax_impl(temp));
     }
 
-    fn compute_into_sub(&self, output: &mut Vec<T>) {
-        self.compute_batch_stable_softmax_sub(output);
+    fn compute_into_sub(&self, output: &mut [T]) {
+        self.temp
+            .compute_into_sub(output, self.size(), |temp| self.compute_batch_stable_softmax_impl(temp));
     }
 
-    fn compute_into_scale(&self, output: &mut Vec<T>) {
-        self.compute_batch_stable_softmax_scale(output);
+    fn compute_into_scale(&self, output: &mut [T]) {
+        self.temp
+            .compute_into_scale(output, self.size(), |temp| self.compute_batch_stable_softmax_impl(temp));
     }
 
-    fn compute_into_div(&self, output: &mut Vec<T>) {
-        self.compute_batch_stable_softmax_div(output);
+    fn compute_into_div(&self, output: &mut [T]) {
+        self.temp
+            .compute_into_div(output, self.size(), |temp| self.compute_batch_stable_softmax_impl(temp));
     }
 
     fn at(&self, i: usize) -> T {
-        self.temp[i]
+        self.temp()[i]
     }
 
     fn get_data(&self) -> &[T] {
-        &self.temp
+        self.temp()
     }
 }
 
+// BatchStableSoftmaxExpr is an Etl2d
+impl<T: EtlValueType + Float, Expr: WrappableExpr<T>> Etl2d<T> for BatchStableSoftmaxExpr<T, Expr> {}
+
 // BatchStableSoftmaxExpr is an EtlWrappable
 impl<T: EtlValueType + Float, Expr: WrappableExpr<T>> EtlWrappable<T> for BatchStableSoftmaxExpr<T, Expr> {
     type WrappedAs = BatchStableSoftmaxExpr<T, Expr>;
@@ -194,7 +181,7 @@ impl<T: EtlValueType + Float, Expr: WrappableExpr<T>> EtlWrappable<T> for BatchS
 // BatchStableSoftmaxExpr computes as copy
 impl<T: EtlValueType + Float, Expr: WrappableExpr<T>> EtlComputable<T> for BatchStableSoftmaxExpr<T, Expr> {
     fn to_data(&self) -> Vec<T> {
-        self.temp.clone()
+        self.temp().to_vec()
     }
 }
 
@@ -215,7 +202,7 @@ crate::impl_scale_op_unary_expr_trait!(Float, BatchStableSoftmaxExpr<T, Expr>);
 #[cfg(test)]
 mod tests {
     use crate::batch_stable_softmax_expr::batch_stable_softmax;
-    use crate::etl_expr::EtlExpr;
+    use crate::etl_expr::Etl2d;
     use crate::matrix_2d::Matrix2d;
     use approx::assert_relative_eq;
 
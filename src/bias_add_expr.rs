@@ -1,8 +1,8 @@
 use crate::base_traits::*;
+use crate::deferred_temp::DeferredTemp;
+use crate::etl_error::EtlError;
 use crate::etl_expr::*;
 
-use std::simd::*;
-
 // The declaration of BiasAddExpr
 
 /// Expression representing the batched addition of biases to a matrix
@@ -12,77 +12,41 @@ use std::simd::*;
 #[derive(Clone)]
 pub struct BiasAddExpr<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>>
 where
-    Simd<T, 8>: SimdHelper,
+    T: Vectorizable,
 {
     lhs: EtlWrapper<T, LeftExpr::WrappedAs>,
     rhs: EtlWrapper<T, RightExpr::WrappedAs>,
-    pub temp: Vec<T>,
+    temp: DeferredTemp<T>,
 }
 
 // The functions of BiasAddExpr
 
 impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> BiasAddExpr<T, LeftExpr, RightExpr>
 where
-    Simd<T, 8>: SimdHelper,
+    T: Vectorizable,
 {
     pub fn new(lhs: LeftExpr, rhs: RightExpr) -> Self {
         if LeftExpr::DIMENSIONS == 2 && RightExpr::DIMENSIONS == 1 {
-            if lhs.columns() != rhs.rows() {
-                panic!("Invalid bias_add dimensions ([{},{}]*[{}])", lhs.rows(), lhs.columns(), rhs.rows());
+            if lhs.dim(1) != rhs.rows() {
+                panic!("Invalid bias_add dimensions ([{},{}]*[{}])", lhs.rows(), lhs.dim(1), rhs.rows());
             }
         } else {
             panic!("Invalid bias_add dimensions ({}D*{}D)", LeftExpr::DIMENSIONS, RightExpr::DIMENSIONS);
         }
 
-        let mut expr = Self {
+        Self {
             lhs: lhs.wrap(),
             rhs: rhs.wrap(),
-            temp: Vec::<T>::new(),
-        };
-
-        let mut temp = vec![T::default(); padded_size(expr.size())];
-        expr.compute_bias_add_impl(&mut temp);
-        expr.temp = temp;
-
-        expr
-    }
-
-    fn compute_bias_add(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        output[..self.temp.len()].copy_from_slice(&self.temp[..]);
-    }
-
-    fn compute_bias_add_add(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (n, value) in output.iter_mut().enumerate() {
-            *value += self.temp[n];
-        }
-    }
-
-    fn compute_bias_add_sub(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (n, value) in output.iter_mut().enumerate() {
-            *value -= self.temp[n];
+            temp: DeferredTemp::new(),
         }
     }
 
-    fn compute_bias_add_scale(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (n, value) in output.iter_mut().enumerate() {
-            *value *= self.temp[n];
-        }
+    fn temp(&self) -> &[T] {
+        self.temp.get_or_compute(self.size(), |temp| self.compute_bias_add_impl(temp))
     }
 
-    fn compute_bias_add_div(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (n, value) in output.iter_mut().enumerate() {
-            *value /= self.temp[n];
-        }
+    fn compute_bias_add(&self, output: &mut [T]) {
+        self.temp.compute_into(output, |output| self.compute_bias_add_impl(output));
     }
 
     fn compute_kernel(m: usize, n: usize, out: &mut [T], lhs: &[T], rhs: &[T]) {
@@ -92,8 +56,8 @@ where
             let mut column = 0;
 
             while column + lanes - 1 < n {
-                let vec_x = Simd::<T, 8>::from_slice(&lhs[row * n + column..]);
-                let vec_y = Simd::<T, 8>::from_slice(&rhs[column..]);
+                let vec_x = T::Lanes::from_slice(&lhs[row * n + column..]);
+                let vec_y = T::Lanes::from_slice(&rhs[column..]);
 
                 let result = vec_x + vec_y;
 
@@ -110,10 +74,10 @@ where
         }
     }
 
-    fn compute_bias_add_impl(&self, output: &mut Vec<T>) {
+    fn compute_bias_add_impl(&self, output: &mut [T]) {
         if LeftExpr::DIMENSIONS == 2 && RightExpr::DIMENSIONS == 1 {
             let m = self.lhs.value.rows();
-            let n = self.lhs.value.columns();
+            let n = self.lhs.value.dim(1);
 
             let functor = |out: &mut [T], lhs: &[T], rhs: &[T]| {
                 Self::compute_kernel(m, n, out, lhs, rhs);
@@ -125,25 +89,35 @@ where
         }
     }
 
-    fn validate_bias_add<OutputExpr: EtlExpr<T>>(&self, lhs: &OutputExpr) {
+    fn validate_bias_add<OutputExpr: EtlExpr<T>>(&self, lhs: &OutputExpr) -> Result<(), EtlError> {
         if OutputExpr::DIMENSIONS != 2 {
-            panic!("The output of bias_add must be a 2D Matrix");
+            return Err(EtlError::RankMismatch {
+                op: "assignment of bias_add result",
+                expected: 2,
+                actual: OutputExpr::DIMENSIONS,
+            });
         }
 
         if LeftExpr::DIMENSIONS == 2 && RightExpr::DIMENSIONS == 1 {
-            if lhs.rows() != self.lhs.value.rows() || lhs.columns() != self.lhs.value.columns() {
-                panic!("Invalid dimensions for assignment of bias_add result");
+            if lhs.rows() != self.lhs.value.rows() || lhs.dim(1) != self.lhs.value.dim(1) {
+                return Err(EtlError::ShapeMismatch {
+                    op: "assignment of bias_add result",
+                    lhs: expr_dims(lhs),
+                    rhs: expr_dims(self),
+                });
             }
         } else {
             panic!("This code should be unreachable!");
         }
+
+        Ok(())
     }
 }
 
 // BiasAddExpr is an EtlExpr
 impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> EtlExpr<T> for BiasAddExpr<T, LeftExpr, RightExpr>
 where
-    Simd<T, 8>: SimdHelper,
+    T: Vectorizable,
 {
     const DIMENSIONS: usize = 2;
     const TYPE: EtlType = EtlType::Smart;
@@ -156,11 +130,11 @@ where
         Self: 'x;
 
     fn iter(&self) -> Self::Iter<'_> {
-        self.temp.iter().cloned()
+        self.temp().iter().cloned()
     }
 
     fn iter_range(&self, range: std::ops::Range<usize>) -> Self::Iter<'_> {
-        self.temp[range].iter().cloned()
+        self.temp()[range].iter().cloned()
     }
 
     fn size(&self) -> usize {
@@ -171,47 +145,54 @@ where
         self.lhs.value.rows()
     }
 
-    fn columns(&self) -> usize {
-        self.lhs.value.columns()
+    fn dim(&self, i: usize) -> usize {
+        match i {
+            0 => self.rows(),
+            1 => self.lhs.value.dim(1),
+            _ => panic!("Invalid dimension access ({i} for 2D)"),
+        }
     }
 
-    fn validate_assign<OutputExpr: EtlExpr<T>>(&self, lhs: &OutputExpr) {
-        self.validate_bias_add(lhs);
+    fn try_validate_assign<OutputExpr: EtlExpr<T>>(&self, lhs: &OutputExpr) -> Result<(), EtlError> {
+        self.validate_bias_add(lhs)
     }
 
-    fn compute_into(&self, output: &mut Vec<T>) {
+    fn compute_into(&self, output: &mut [T]) {
         self.compute_bias_add(output);
     }
 
-    fn compute_into_add(&self, output: &mut Vec<T>) {
-        self.compute_bias_add_add(output);
+    fn compute_into_add(&self, output: &mut [T]) {
+        self.temp.compute_into_add(output, self.size(), |temp| self.compute_bias_add_impl(temp));
     }
 
-    fn compute_into_sub(&self, output: &mut Vec<T>) {
-        self.compute_bias_add_sub(output);
+    fn compute_into_sub(&self, output: &mut [T]) {
+        self.temp.compute_into_sub(output, self.size(), |temp| self.compute_bias_add_impl(temp));
     }
 
-    fn compute_into_scale(&self, output: &mut Vec<T>) {
-        self.compute_bias_add_scale(output);
+    fn compute_into_scale(&self, output: &mut [T]) {
+        self.temp.compute_into_scale(output, self.size(), |temp| self.compute_bias_add_impl(temp));
     }
 
-    fn compute_into_div(&self, output: &mut Vec<T>) {
-        self.compute_bias_add_div(output);
+    fn compute_into_div(&self, output: &mut [T]) {
+        self.temp.compute_into_div(output, self.size(), |temp| self.compute_bias_add_impl(temp));
     }
 
     fn at(&self, i: usize) -> T {
-        self.temp[i]
+        self.temp()[i]
     }
 
     fn get_data(&self) -> &[T] {
-        &self.temp
+        self.temp()
     }
 }
 
+// BiasAddExpr is an Etl2d
+impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> Etl2d<T> for BiasAddExpr<T, LeftExpr, RightExpr> where T: Vectorizable {}
+
 // BiasAddExpr is an EtlWrappable
 impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> EtlWrappable<T> for BiasAddExpr<T, LeftExpr, RightExpr>
 where
-    Simd<T, 8>: SimdHelper,
+    T: Vectorizable,
 {
     type WrappedAs = BiasAddExpr<T, LeftExpr, RightExpr>;
 
@@ -226,19 +207,19 @@ where
 // BiasAddExpr computes as copy
 impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> EtlComputable<T> for BiasAddExpr<T, LeftExpr, RightExpr>
 where
-    Simd<T, 8>: SimdHelper,
+    T: Vectorizable,
 {
     fn to_data(&self) -> Vec<T> {
-        self.temp.clone()
+        self.temp().to_vec()
     }
 }
 
 // Operations
 
-pub fn bias_add<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>>(lhs: LeftExpr, rhs: RightExpr) -> BiasAddExpr<T, LeftExpr, RightExpr>
-where
-    Simd<T, 8>: SimdHelper,
-{
+pub fn bias_add<T: EtlValueType + Vectorizable, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>>(
+    lhs: LeftExpr,
+    rhs: RightExpr,
+) -> BiasAddExpr<T, LeftExpr, RightExpr> {
     BiasAddExpr::<T, LeftExpr, RightExpr>::new(lhs, rhs)
 }
 
@@ -253,7 +234,7 @@ crate::impl_scale_op_binary_expr_simd!(BiasAddExpr<T, LeftExpr, RightExpr>);
 #[cfg(test)]
 mod tests {
     use crate::bias_add_expr::bias_add;
-    use crate::etl_expr::EtlExpr;
+    use crate::etl_expr::Etl2d;
     use crate::matrix_2d::Matrix2d;
     use crate::vector::Vector;
 
@@ -1,3 +1,5 @@
+use crate::deferred_temp::DeferredTemp;
+use crate::etl_error::EtlError;
 use crate::etl_expr::*;
 
 // The declaration of BiasBatchSumExpr
@@ -6,7 +8,7 @@ use crate::etl_expr::*;
 #[derive(Clone)]
 pub struct BiasBatchSumExpr<T: EtlValueType, Expr: WrappableExpr<T>> {
     lhs: EtlWrapper<T, Expr::WrappedAs>,
-    pub temp: Vec<T>,
+    temp: DeferredTemp<T>,
 }
 
 // The functions of BiasBatchSumExpr
@@ -17,60 +19,28 @@ impl<T: EtlValueType, Expr: WrappableExpr<T>> BiasBatchSumExpr<T, Expr> {
             panic!("Invalid bias_batch_sum dimensions ({}D)", Expr::DIMENSIONS);
         }
 
-        let mut expr = Self {
+        Self {
             lhs: lhs.wrap(),
-            temp: Vec::<T>::new(),
-        };
-
-        let mut temp = vec![T::default(); padded_size(expr.size())];
-        expr.compute_bias_batch_sum_impl(&mut temp);
-        expr.temp = temp;
-
-        expr
-    }
-
-    fn compute_bias_batch_sum(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        output[..self.temp.len()].copy_from_slice(&self.temp[..]);
-    }
-
-    fn compute_bias_batch_sum_add(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (n, value) in output.iter_mut().enumerate() {
-            *value += self.temp[n];
+            temp: DeferredTemp::new(),
         }
     }
 
-    fn compute_bias_batch_sum_sub(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (n, value) in output.iter_mut().enumerate() {
-            *value -= self.temp[n];
-        }
-    }
-
-    fn compute_bias_batch_sum_scale(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (n, value) in output.iter_mut().enumerate() {
-            *value *= self.temp[n];
-        }
+    fn temp(&self) -> &[T] {
+        self.temp.get_or_compute(self.size(), |temp| self.compute_bias_batch_sum_impl(temp))
     }
 
-    fn compute_bias_batch_sum_div(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (n, value) in output.iter_mut().enumerate() {
-            *value /= self.temp[n];
-        }
+    fn compute_bias_batch_sum(&self, output: &mut [T]) {
+        self.temp.compute_into(output, |output| {
+            // The kernel accumulates into the output
+            output.fill(T::default());
+            self.compute_bias_batch_sum_impl(output);
+        });
     }
 
-    fn compute_bias_batch_sum_impl(&self, output: &mut Vec<T>) {
+    fn compute_bias_batch_sum_impl(&self, output: &mut [T]) {
         if Expr::DIMENSIONS == 2 {
             let b = self.lhs.value.rows();
-            let m = self.lhs.value.columns();
+            let m = self.lhs.value.dim(1);
 
             let functor = |out: &mut [T], lhs: &[T]| {
                 for batch in 0..b {
@@ -86,18 +56,28 @@ impl<T: EtlValueType, Expr: WrappableExpr<T>> BiasBatchSumExpr<T, Expr> {
         }
     }
 
-    fn validate_bias_batch_sum<OutputExpr: EtlExpr<T>>(&self, lhs: &OutputExpr) {
+    fn validate_bias_batch_sum<OutputExpr: EtlExpr<T>>(&self, lhs: &OutputExpr) -> Result<(), EtlError> {
         if OutputExpr::DIMENSIONS != 1 {
-            panic!("The output of bias_batch_sum must be a 1D Matrix");
+            return Err(EtlError::RankMismatch {
+                op: "assignment of bias_batch_sum result",
+                expected: 1,
+                actual: OutputExpr::DIMENSIONS,
+            });
         }
 
         if Expr::DIMENSIONS == 2 {
-            if lhs.rows() != self.lhs.value.columns() {
-                panic!("Invalid dimensions for assignment of bias_batch_sum result");
+            if lhs.rows() != self.lhs.value.dim(1) {
+                return Err(EtlError::ShapeMismatch {
+                    op: "assignment of bias_batch_sum result",
+                    lhs: expr_dims(lhs),
+                    rhs: expr_dims(self),
+                });
             }
         } else {
             panic!("This code should be unreachable!");
         }
+
+        Ok(())
     }
 }
 
@@ -114,51 +94,51 @@ impl<T: EtlValueType, Expr: WrappableExpr<T>> EtlExpr<T> for BiasBatchSumExpr<T,
         Self: 'x;
 
     fn iter(&self) -> Self::Iter<'_> {
-        self.temp.iter().cloned()
+        self.temp().iter().cloned()
     }
 
     fn iter_range(&self, range: std::ops::Range<usize>) -> Self::Iter<'_> {
-        self.temp[range].iter().cloned()
+        self.temp()[range].iter().cloned()
     }
 
     fn size(&self) -> usize {
-        self.lhs.value.columns()
+        self.lhs.value.dim(1)
     }
 
     fn rows(&self) -> usize {
-        self.lhs.value.columns()
+        self.lhs.value.dim(1)
     }
 
-    fn validate_assign<OutputExpr: EtlExpr<T>>(&self, lhs: &OutputExpr) {
-        self.validate_bias_batch_sum(lhs);
+    fn try_validate_assign<OutputExpr: EtlExpr<T>>(&self, lhs: &OutputExpr) -> Result<(), EtlError> {
+        self.validate_bias_batch_sum(lhs)
     }
 
-    fn compute_into(&self, output: &mut Vec<T>) {
+    fn compute_into(&self, output: &mut [T]) {
         self.compute_bias_batch_sum(output);
     }
 
-    fn compute_into_add(&self, output: &mut Vec<T>) {
-        self.compute_bias_batch_sum_add(output);
+    fn compute_into_add(&self, output: &mut [T]) {
+        self.temp.compute_into_add(output, self.size(), |temp| self.compute_bias_batch_sum_impl(temp));
     }
 
-    fn compute_into_sub(&self, output: &mut Vec<T>) {
-        self.compute_bias_batch_sum_sub(output);
+    fn compute_into_sub(&self, output: &mut [T]) {
+        self.temp.compute_into_sub(output, self.size(), |temp| self.compute_bias_batch_sum_impl(temp));
     }
 
-    fn compute_into_scale(&self, output: &mut Vec<T>) {
-        self.compute_bias_batch_sum_scale(output);
+    fn compute_into_scale(&self, output: &mut [T]) {
+        self.temp.compute_into_scale(output, self.size(), |temp| self.compute_bias_batch_sum_impl(temp));
     }
 
-    fn compute_into_div(&self, output: &mut Vec<T>) {
-        self.compute_bias_batch_sum_div(output);
+    fn compute_into_div(&self, output: &mut [T]) {
+        self.temp.compute_into_div(output, self.size(), |temp| self.compute_bias_batch_sum_impl(temp));
     }
 
     fn at(&self, i: usize) -> T {
-        self.temp[i]
+        self.temp()[i]
     }
 
     fn get_data(&self) -> &[T] {
-        &self.temp
+        self.temp()
     }
 }
 
@@ -177,7 +157,7 @@ impl<T: EtlValueType, Expr: WrappableExpr<T>> EtlWrappable<T> for BiasBatchSumEx
 // BiasBatchSumExpr computes as copy
 impl<T: EtlValueType, Expr: WrappableExpr<T>> EtlComputable<T> for BiasBatchSumExpr<T, Expr> {
     fn to_data(&self) -> Vec<T> {
-        self.temp.clone()
+        self.temp().to_vec()
     }
 }
 
@@ -219,4 +199,16 @@ mod tests {
         assert_eq!(c.at(0), 9);
         assert_eq!(c.at(1), 12);
     }
+
+    #[test]
+    fn bias_batch_sum_overwrite() {
+        let a = Matrix2d::<i64>::new_iota(3, 2, 1);
+        let mut c = Vector::<i64>::new(2);
+
+        c.fill(100);
+        c |= bias_batch_sum(&a);
+
+        assert_eq!(c.at(0), 9);
+        assert_eq!(c.at(1), 12);
+    }
 }
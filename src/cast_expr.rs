@@ -0,0 +1,222 @@
+use crate::base_traits::Cast;
+use crate::etl_expr::*;
+
+// The declaration of CastExpr
+
+/// Expression converting the values of its sub expression from T to U
+#[derive(Clone)]
+pub struct CastExpr<T: EtlValueType + Cast<U>, U: EtlValueType, Expr: WrappableExpr<T>> {
+    expr: EtlWrapper<T, Expr::WrappedAs>,
+    _marker: std::marker::PhantomData<U>,
+}
+
+// The functions of CastExpr
+
+impl<T: EtlValueType + Cast<U>, U: EtlValueType, Expr: WrappableExpr<T>> CastExpr<T, U, Expr> {
+    pub fn new(expr: Expr) -> Self {
+        Self {
+            expr: expr.wrap(),
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+pub struct CastExprIterator<'a, T: EtlValueType + Cast<U>, U: EtlValueType, Expr: EtlExpr<T> + 'a>
+where
+    T: 'a,
+{
+    sub_iter: Expr::Iter<'a>,
+    _marker: std::marker::PhantomData<U>,
+}
+
+impl<'a, T: EtlValueType + Cast<U>, U: EtlValueType, Expr: EtlExpr<T>> Iterator for CastExprIterator<'a, T, U, Expr> {
+    type Item = U;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.sub_iter.next().map(Cast::cast)
+    }
+}
+
+// CastExpr is an EtlExpr
+impl<T: EtlValueType + Cast<U>, U: EtlValueType, Expr: WrappableExpr<T>> EtlExpr<U> for CastExpr<T, U, Expr> {
+    const DIMENSIONS: usize = Expr::DIMENSIONS;
+    const TYPE: EtlType = simple_unary_type(Expr::TYPE);
+    const THREAD_SAFE: bool = Expr::THREAD_SAFE;
+
+    type Iter<'x>
+        = CastExprIterator<'x, T, U, Expr::WrappedAs>
+    where
+        U: 'x,
+        Self: 'x;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        Self::Iter {
+            sub_iter: self.expr.value.iter(),
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    fn iter_range(&self, range: std::ops::Range<usize>) -> Self::Iter<'_> {
+        Self::Iter {
+            sub_iter: self.expr.value.iter_range(range),
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.expr.value.size()
+    }
+
+    fn rows(&self) -> usize {
+        self.expr.value.rows()
+    }
+
+    fn dim(&self, i: usize) -> usize {
+        self.expr.value.dim(i)
+    }
+
+    fn at(&self, i: usize) -> U {
+        self.expr.value.at(i).cast()
+    }
+}
+
+// CastExpr has the rank of its sub expression
+impl<T: EtlValueType + Cast<U>, U: EtlValueType, Expr: WrappableExpr<T> + Etl2d<T>> Etl2d<U> for CastExpr<T, U, Expr> {}
+impl<T: EtlValueType + Cast<U>, U: EtlValueType, Expr: WrappableExpr<T> + Etl3d<T>> Etl3d<U> for CastExpr<T, U, Expr> {}
+impl<T: EtlValueType + Cast<U>, U: EtlValueType, Expr: WrappableExpr<T> + Etl4d<T>> Etl4d<U> for CastExpr<T, U, Expr> {}
+
+// CastExpr is an EtlWrappable
+// CastExpr wraps as value
+impl<T: EtlValueType + Cast<U>, U: EtlValueType, Expr: WrappableExpr<T>> EtlWrappable<U> for CastExpr<T, U, Expr> {
+    type WrappedAs = CastExpr<T, U, Expr>;
+
+    fn wrap(self) -> EtlWrapper<U, Self::WrappedAs> {
+        EtlWrapper {
+            value: self,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+// CastExpr computes as copy
+impl<T: EtlValueType + Cast<U>, U: EtlValueType, Expr: WrappableExpr<T>> EtlComputable<U> for CastExpr<T, U, Expr> {
+    fn to_data(&self) -> Vec<U> {
+        let mut vec = vec![U::default(); padded_size(self.size())];
+        assign_direct(&mut vec, self);
+        vec
+    }
+}
+
+// Operations
+
+/// Convert the values of an expression to U
+///
+/// U is generally inferred from the destination or from the other operand, otherwise it must be
+/// given explicitly, as in cast::<f32, _, _>(&a)
+pub fn cast<U: EtlValueType, T: EtlValueType + Cast<U>, Expr: WrappableExpr<T>>(expr: Expr) -> CastExpr<T, U, Expr> {
+    CastExpr::new(expr)
+}
+
+crate::impl_op_converting_expr!([T: EtlValueType + Cast<U>, U: EtlValueType, Expr: WrappableExpr<T>] CastExpr<T, U, Expr>, U);
+
+// The tests
+
+#[cfg(test)]
+mod tests {
+    use crate::cast_expr::cast;
+    use crate::complex::Complex;
+    use crate::etl_expr::{Etl2d, EtlExpr};
+    use crate::half_float::{bf16, f16};
+    use crate::matrix_2d::Matrix2d;
+    use crate::transpose_expr::transpose;
+    use crate::vector::Vector;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn basic() {
+        let a = Matrix2d::<i32>::new_iota(2, 3, -1);
+
+        let expr = cast::<f32, _, _>(&a);
+
+        assert_eq!(expr.size(), 6);
+        assert_eq!(expr.columns(), 3);
+        assert_eq!(expr.at(0), -1.0);
+
+        let mut b = Matrix2d::<f32>::new(2, 3);
+        b |= cast(&a);
+
+        assert_eq!(b.at2(1, 2), 4.0);
+
+        let mut c = Vector::<u8>::new(3);
+        c |= cast(&Vector::<f64>::new_iota(3, 1.75));
+
+        assert_eq!(c.at(0), 1);
+        assert_eq!(c.at(2), 3);
+    }
+
+    #[test]
+    fn deep() {
+        let a = Vector::<u8>::new_iota(5, 1);
+        let b = Vector::<f64>::new_iota(5, 0.5);
+
+        let mut c = Vector::<f64>::new(5);
+        c |= (cast(&a) + &b) >> cast(&a + &a);
+
+        assert_relative_eq!(c.at(0), (1.0 + 0.5) * 2.0, epsilon = 1e-12);
+        assert_relative_eq!(c.at(4), (5.0 + 4.5) * 10.0, epsilon = 1e-12);
+
+        // The rounding happens in the inner cast
+        let mut d = Vector::<i64>::new(5);
+        d |= cast::<i64, _, _>(cast::<i32, _, _>(&b) + cast(&a));
+
+        assert_eq!(d.at(0), 1);
+        assert_eq!(d.at(4), 9);
+    }
+
+    #[test]
+    fn gemm() {
+        let labels = Matrix2d::<i32>::new_iota(3, 4, 1);
+        let weights = Matrix2d::<f32>::new_iota(4, 2, 0.5);
+
+        let mut c = Matrix2d::<f32>::new(3, 2);
+        c |= cast(&labels) * &weights;
+
+        let mut d = Matrix2d::<f32>::new(2, 3);
+        d |= transpose(&weights) * cast(transpose(&labels));
+
+        for row in 0..3 {
+            for column in 0..2 {
+                let mut v = 0.0;
+                for inner in 0..4 {
+                    v += labels.at2(row, inner) as f32 * weights.at2(inner, column);
+                }
+                assert_eq!(c.at2(row, column), v);
+                assert_eq!(d.at2(column, row), v);
+            }
+        }
+    }
+
+    #[test]
+    fn half_and_complex() {
+        let a = Vector::<f64>::new_iota(4, 0.25);
+
+        let mut b = Vector::<f16>::new(4);
+        let mut c = Vector::<bf16>::new(4);
+        b |= cast(&a);
+        c |= cast(&b);
+
+        assert_eq!(b.at(3).to_f32(), 3.25);
+        assert_eq!(c.at(3).to_f32(), 3.25);
+
+        let mut d = Vector::<Complex<f32>>::new(4);
+        d |= cast(&c);
+
+        assert_eq!(d.at(1), Complex::new(1.25, 0.0));
+
+        let mut e = Vector::<Complex<f64>>::new(4);
+        e |= cast(&d);
+
+        assert_eq!(e.at(2), Complex::new(2.25, 0.0));
+    }
+}
@@ -0,0 +1,545 @@
+use crate::aligned_buffer::ALIGNMENT;
+use crate::etl_error::EtlError;
+use crate::etl_expr::*;
+use crate::half_float::{bf16, f16};
+use crate::matrix_2d::Matrix2d;
+use crate::matrix_3d::Matrix3d;
+use crate::matrix_4d::Matrix4d;
+use crate::tensor::Tensor;
+use crate::vector::Vector;
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+
+// The checkpoint archive format
+//
+// A checkpoint stores named containers in a single file, in little endian:
+//  * the magic string ETLCKPT\0, the version (u32) and the number of tensors (u32)
+//  * for each tensor: its name (u32 length and UTF-8 bytes), its dtype (u8), its rank (u8), its
+//    dimensions (u64 each), the offset of its data from the start of the file (u64) and the
+//    size of its data in bytes (u64)
+//  * the data of each tensor, starting on a multiple of ALIGNMENT bytes, the file is padded to
+//    a multiple of ALIGNMENT bytes as well
+
+const MAGIC: &[u8; 8] = b"ETLCKPT\0";
+const VERSION: u32 = 1;
+
+/// The types of the values stored in a checkpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DType {
+    F16,
+    BF16,
+    F32,
+    F64,
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+}
+
+impl DType {
+    const ALL: [DType; 12] = [
+        DType::F16,
+        DType::BF16,
+        DType::F32,
+        DType::F64,
+        DType::I8,
+        DType::I16,
+        DType::I32,
+        DType::I64,
+        DType::U8,
+        DType::U16,
+        DType::U32,
+        DType::U64,
+    ];
+
+    fn code(self) -> u8 {
+        DType::ALL.iter().position(|dtype| *dtype == self).unwrap() as u8
+    }
+
+    fn from_code(code: u8) -> Result<Self, EtlError> {
+        DType::ALL.get(code as usize).copied().ok_or_else(|| invalid(format!("unknown dtype {code}")))
+    }
+
+    /// The size in bytes of a value
+    pub fn size(self) -> usize {
+        match self {
+            DType::I8 | DType::U8 => 1,
+            DType::F16 | DType::BF16 | DType::I16 | DType::U16 => 2,
+            DType::F32 | DType::I32 | DType::U32 => 4,
+            DType::F64 | DType::I64 | DType::U64 => 8,
+        }
+    }
+}
+
+/// The value types that can be stored in a checkpoint
+pub trait CheckpointType: EtlValueType {
+    const DTYPE: DType;
+
+    fn from_le_slice(bytes: &[u8]) -> Self;
+    fn append_le_bytes(self, out: &mut Vec<u8>);
+}
+
+macro_rules! impl_checkpoint_type {
+    ($type:ty, $dtype:expr) => {
+        impl CheckpointType for $type {
+            const DTYPE: DType = $dtype;
+
+            fn from_le_slice(bytes: &[u8]) -> Self {
+                <$type>::from_le_bytes(bytes.try_into().unwrap())
+            }
+
+            fn append_le_bytes(self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+        }
+    };
+}
+
+impl_checkpoint_type!(f16, DType::F16);
+impl_checkpoint_type!(bf16, DType::BF16);
+impl_checkpoint_type!(f32, DType::F32);
+impl_checkpoint_type!(f64, DType::F64);
+impl_checkpoint_type!(i8, DType::I8);
+impl_checkpoint_type!(i16, DType::I16);
+impl_checkpoint_type!(i32, DType::I32);
+impl_checkpoint_type!(i64, DType::I64);
+impl_checkpoint_type!(u8, DType::U8);
+impl_checkpoint_type!(u16, DType::U16);
+impl_checkpoint_type!(u32, DType::U32);
+impl_checkpoint_type!(u64, DType::U64);
+
+/// The containers that can be stored in a checkpoint
+pub trait CheckpointTensor {
+    fn dtype(&self) -> DType;
+    fn shape(&self) -> Vec<usize>;
+
+    /// Append the values, in little endian
+    fn write_data(&self, out: &mut Vec<u8>);
+
+    /// Set the values from little endian bytes, the shape has already been validated
+    fn read_data(&mut self, bytes: &[u8]);
+
+    /// Create an empty container of the given shape
+    fn with_shape(shape: &[usize]) -> Result<Self, EtlError>
+    where
+        Self: Sized;
+}
+
+fn check_rank(shape: &[usize], rank: usize) -> Result<(), EtlError> {
+    if shape.len() != rank {
+        return Err(EtlError::RankMismatch {
+            op: "loading of checkpoint tensor",
+            expected: rank,
+            actual: shape.len(),
+        });
+    }
+
+    Ok(())
+}
+
+macro_rules! impl_checkpoint_tensor {
+    ([$($generics:tt)*] $type:ty, $rank:expr, $shape:ident => $new:expr) => {
+        impl<T: CheckpointType, $($generics)*> CheckpointTensor for $type {
+            fn dtype(&self) -> DType {
+                T::DTYPE
+            }
+
+            fn shape(&self) -> Vec<usize> {
+                expr_dims(self)
+            }
+
+            fn write_data(&self, out: &mut Vec<u8>) {
+                for value in &self.data[..self.size()] {
+                    value.append_le_bytes(out);
+                }
+            }
+
+            fn read_data(&mut self, bytes: &[u8]) {
+                for (value, bytes) in self.data.iter_mut().zip(bytes.chunks_exact(T::DTYPE.size())) {
+                    *value = T::from_le_slice(bytes);
+                }
+            }
+
+            fn with_shape($shape: &[usize]) -> Result<Self, EtlError> {
+                check_rank($shape, $rank)?;
+                Ok($new)
+            }
+        }
+    };
+}
+
+impl_checkpoint_tensor!([] Vector<T>, 1, shape => Vector::new(shape[0]));
+impl_checkpoint_tensor!([] Matrix2d<T>, 2, shape => Matrix2d::new(shape[0], shape[1]));
+impl_checkpoint_tensor!([] Matrix3d<T>, 3, shape => Matrix3d::new(shape[0], shape[1], shape[2]));
+impl_checkpoint_tensor!([] Matrix4d<T>, 4, shape => Matrix4d::new(shape[0], shape[1], shape[2], shape[3]));
+impl_checkpoint_tensor!([const D: usize] Tensor<T, D>, D, shape => Tensor::new(std::array::from_fn(|d| shape[d])));
+
+fn invalid(message: String) -> EtlError {
+    EtlError::InvalidFormat { format: "checkpoint", message }
+}
+
+// The declaration of Checkpoint
+
+/// The description of a tensor of a checkpoint
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckpointEntry {
+    pub name: String,
+    pub dtype: DType,
+    pub shape: Vec<usize>,
+    pub offset: usize,
+    pub len: usize,
+}
+
+/// A checkpoint loaded in memory, the tensors are converted to containers on demand
+pub struct Checkpoint {
+    bytes: Vec<u8>,
+    entries: Vec<CheckpointEntry>,
+    index: HashMap<String, usize>,
+}
+
+// The functions of Checkpoint
+
+impl Checkpoint {
+    /// Write the tensors to a stream
+    pub fn write<W: Write>(writer: &mut W, tensors: &[(&str, &dyn CheckpointTensor)]) -> Result<(), EtlError> {
+        let mut header = Vec::new();
+        header.extend_from_slice(MAGIC);
+        header.extend_from_slice(&VERSION.to_le_bytes());
+        header.extend_from_slice(&(tensors.len() as u32).to_le_bytes());
+
+        let mut header_len = header.len();
+        for (name, tensor) in tensors {
+            header_len += 4 + name.len() + 2 + 8 * tensor.shape().len() + 16;
+        }
+
+        let mut data = Vec::new();
+        let mut names = HashMap::new();
+
+        for (name, tensor) in tensors {
+            if names.insert(*name, ()).is_some() {
+                return Err(invalid(format!("the name {name} is used by several tensors")));
+            }
+
+            // The data of each tensor is aligned
+            let padding = (ALIGNMENT - (header_len + data.len()) % ALIGNMENT) % ALIGNMENT;
+            data.resize(data.len() + padding, 0);
+
+            let offset = header_len + data.len();
+            tensor.write_data(&mut data);
+            let len = header_len + data.len() - offset;
+
+            let shape = tensor.shape();
+            header.extend_from_slice(&(name.len() as u32).to_le_bytes());
+            header.extend_from_slice(name.as_bytes());
+            header.push(tensor.dtype().code());
+            header.push(shape.len() as u8);
+            for dim in shape {
+                header.extend_from_slice(&(dim as u64).to_le_bytes());
+            }
+            header.extend_from_slice(&(offset as u64).to_le_bytes());
+            header.extend_from_slice(&(len as u64).to_le_bytes());
+        }
+
+        // The last tensor is padded as well, so that all the tensors can be mapped as containers
+        let padding = (ALIGNMENT - (header_len + data.len()) % ALIGNMENT) % ALIGNMENT;
+        data.resize(data.len() + padding, 0);
+
+        debug_assert_eq!(header.len(), header_len);
+
+        writer.write_all(&header)?;
+        writer.write_all(&data)?;
+        Ok(())
+    }
+
+    /// Save the tensors to a file
+    pub fn save<P: AsRef<Path>>(path: P, tensors: &[(&str, &dyn CheckpointTensor)]) -> Result<(), EtlError> {
+        let mut bytes = Vec::new();
+        Self::write(&mut bytes, tensors)?;
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    /// Load a checkpoint from a file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, EtlError> {
+        Self::from_bytes(std::fs::read(path)?)
+    }
+
+    /// Load a checkpoint from its bytes
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, EtlError> {
+        let entries = parse_header(&bytes)?;
+
+        let mut index = HashMap::new();
+        for (i, entry) in entries.iter().enumerate() {
+            if index.insert(entry.name.clone(), i).is_some() {
+                return Err(invalid(format!("the name {} is used by several tensors", entry.name)));
+            }
+        }
+
+        Ok(Self { bytes, entries, index })
+    }
+
+    /// The descriptions of the tensors, in the order they were saved
+    pub fn entries(&self) -> &[CheckpointEntry] {
+        &self.entries
+    }
+
+    /// The description of a tensor
+    pub fn entry(&self, name: &str) -> Result<&CheckpointEntry, EtlError> {
+        match self.index.get(name) {
+            Some(i) => Ok(&self.entries[*i]),
+            None => Err(invalid(format!("there is no tensor named {name}"))),
+        }
+    }
+
+    /// Create a container from a tensor, its dtype and its rank must match
+    pub fn get<C: CheckpointTensor>(&self, name: &str) -> Result<C, EtlError> {
+        let entry = self.entry(name)?;
+
+        let mut container = C::with_shape(&entry.shape)?;
+        self.check_dtype(entry, &container)?;
+
+        container.read_data(&self.bytes[entry.offset..entry.offset + entry.len]);
+        Ok(container)
+    }
+
+    /// Load a tensor into an existing container, its dtype and its shape must match
+    pub fn load_into<C: CheckpointTensor + ?Sized>(&self, name: &str, container: &mut C) -> Result<(), EtlError> {
+        let entry = self.entry(name)?;
+
+        self.check_dtype(entry, container)?;
+
+        if container.shape() != entry.shape {
+            return Err(EtlError::ShapeMismatch {
+                op: "loading of checkpoint tensor",
+                lhs: container.shape(),
+                rhs: entry.shape.clone(),
+            });
+        }
+
+        container.read_data(&self.bytes[entry.offset..entry.offset + entry.len]);
+        Ok(())
+    }
+
+    fn check_dtype<C: CheckpointTensor + ?Sized>(&self, entry: &CheckpointEntry, container: &C) -> Result<(), EtlError> {
+        if container.dtype() != entry.dtype {
+            return Err(invalid(format!("{} is stored as {:?}, not as {:?}", entry.name, entry.dtype, container.dtype())));
+        }
+
+        Ok(())
+    }
+}
+
+// A cursor over the header that reports the truncated files
+struct HeaderReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl HeaderReader<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8], EtlError> {
+        let slice = self
+            .bytes
+            .get(self.position..self.position + len)
+            .ok_or_else(|| invalid("the header is truncated".to_string()))?;
+        self.position += len;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, EtlError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, EtlError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<usize, EtlError> {
+        let value = u64::from_le_bytes(self.take(8)?.try_into().unwrap());
+        usize::try_from(value).map_err(|_| invalid(format!("{value} is too large")))
+    }
+}
+
+/// Parse and validate the header of a checkpoint
+pub fn parse_header(bytes: &[u8]) -> Result<Vec<CheckpointEntry>, EtlError> {
+    let mut reader = HeaderReader { bytes, position: 0 };
+
+    if reader.take(MAGIC.len())? != MAGIC {
+        return Err(invalid("the magic string is missing".to_string()));
+    }
+
+    let version = reader.u32()?;
+    if version != VERSION {
+        return Err(invalid(format!("unsupported version {version}")));
+    }
+
+    let count = reader.u32()?;
+    let mut entries = Vec::new();
+
+    for _ in 0..count {
+        let name_len = reader.u32()? as usize;
+        let name = String::from_utf8(reader.take(name_len)?.to_vec()).map_err(|_| invalid("a name is not valid UTF-8".to_string()))?;
+
+        let dtype = DType::from_code(reader.u8()?)?;
+        let rank = reader.u8()?;
+
+        let mut shape = Vec::with_capacity(rank as usize);
+        for _ in 0..rank {
+            shape.push(reader.u64()?);
+        }
+
+        let offset = reader.u64()?;
+        let len = reader.u64()?;
+
+        // The shape comes from the file, its size must not overflow
+        match shape.iter().try_fold(dtype.size(), |size, &dim| size.checked_mul(dim)) {
+            None => return Err(invalid(format!("the size of {name} overflows"))),
+            Some(size) if size != len => return Err(invalid(format!("the size of {name} does not match its shape"))),
+            Some(_) => {}
+        }
+
+        if offset.checked_add(len).is_none_or(|end| end > bytes.len()) {
+            return Err(invalid(format!("the data of {name} is outside of the file")));
+        }
+
+        entries.push(CheckpointEntry {
+            name,
+            dtype,
+            shape,
+            offset,
+            len,
+        });
+    }
+
+    Ok(entries)
+}
+
+// The tests
+
+#[cfg(test)]
+mod tests {
+    use crate::checkpoint::*;
+    use crate::etl_expr::{Etl2d, Etl3d, EtlExpr};
+
+    fn sample() -> Vec<u8> {
+        let weights = Matrix2d::<f32>::new_iota(3, 5, 0.5);
+        let biases = Vector::<f64>::new_iota(5, -1.0);
+        let labels = Vector::<u8>::new_iota(7, 1);
+        let kernels = Matrix3d::<bf16>::new_iota(2, 2, 2, bf16::from_f32(1.0));
+
+        let mut bytes = Vec::new();
+        Checkpoint::write(&mut bytes, &[("dense/weights", &weights), ("dense/biases", &biases), ("labels", &labels), ("conv", &kernels)]).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn round_trip() {
+        let checkpoint = Checkpoint::from_bytes(sample()).unwrap();
+
+        assert_eq!(checkpoint.entries().len(), 4);
+        assert_eq!(checkpoint.entries()[0].name, "dense/weights");
+        assert_eq!(checkpoint.entry("conv").unwrap().dtype, DType::BF16);
+
+        for entry in checkpoint.entries() {
+            assert!(entry.offset.is_multiple_of(ALIGNMENT));
+        }
+
+        let weights = checkpoint.get::<Matrix2d<f32>>("dense/weights").unwrap();
+        assert_eq!(weights.rows(), 3);
+        assert_eq!(weights.columns(), 5);
+        assert_eq!(weights.at2(2, 4), 14.5);
+
+        let biases = checkpoint.get::<Vector<f64>>("dense/biases").unwrap();
+        assert_eq!(biases.at(4), 3.0);
+
+        let labels = checkpoint.get::<Vector<u8>>("labels").unwrap();
+        assert_eq!(labels.at(6), 7);
+
+        let kernels = checkpoint.get::<Tensor<bf16, 3>>("conv").unwrap();
+        assert_eq!(kernels.at(7).to_f32(), 8.0);
+
+        let mut conv = Matrix3d::<bf16>::new(2, 2, 2);
+        checkpoint.load_into("conv", &mut conv).unwrap();
+        assert_eq!(conv.at3(1, 1, 0).to_f32(), 7.0);
+    }
+
+    #[test]
+    fn file() {
+        let path = std::env::temp_dir().join(format!("etl_checkpoint_{}", std::process::id()));
+
+        let a = Matrix4d::<i64>::new_iota(2, 1, 2, 3, 1);
+        Checkpoint::save(&path, &[("a", &a)]).unwrap();
+
+        let b = Checkpoint::load(&path).unwrap().get::<Matrix4d<i64>>("a").unwrap();
+        assert_eq!(b.size(), 12);
+        assert_eq!(b.at(11), 12);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn validation() {
+        let checkpoint = Checkpoint::from_bytes(sample()).unwrap();
+
+        let error = checkpoint.get::<Matrix2d<f64>>("dense/weights").err().unwrap();
+        assert_eq!(error.to_string(), "Invalid checkpoint data: dense/weights is stored as F32, not as F64");
+
+        let error = checkpoint.get::<Vector<f32>>("dense/weights").err().unwrap();
+        assert_eq!(
+            error,
+            EtlError::RankMismatch {
+                op: "loading of checkpoint tensor",
+                expected: 1,
+                actual: 2,
+            }
+        );
+
+        let mut weights = Matrix2d::<f32>::new(5, 3);
+        let error = checkpoint.load_into("dense/weights", &mut weights).err().unwrap();
+        assert_eq!(
+            error,
+            EtlError::ShapeMismatch {
+                op: "loading of checkpoint tensor",
+                lhs: vec![5, 3],
+                rhs: vec![3, 5],
+            }
+        );
+
+        let error = checkpoint.get::<Vector<f32>>("missing").err().unwrap();
+        assert_eq!(error.to_string(), "Invalid checkpoint data: there is no tensor named missing");
+
+        let a = Vector::<f32>::new(3);
+        let error = Checkpoint::write(&mut Vec::new(), &[("a", &a), ("a", &a)]).err().unwrap();
+        assert_eq!(error.to_string(), "Invalid checkpoint data: the name a is used by several tensors");
+    }
+
+    #[test]
+    fn corrupted() {
+        let bytes = sample();
+        assert!(bytes.len().is_multiple_of(ALIGNMENT));
+
+        // The data of the last tensor is cut
+        let conv = Checkpoint::from_bytes(bytes.clone()).unwrap().entry("conv").unwrap().clone();
+        let error = Checkpoint::from_bytes(bytes[..conv.offset + conv.len - 1].to_vec()).err().unwrap();
+        assert_eq!(error.to_string(), "Invalid checkpoint data: the data of conv is outside of the file");
+
+        let error = Checkpoint::from_bytes(bytes[..30].to_vec()).err().unwrap();
+        assert_eq!(error.to_string(), "Invalid checkpoint data: the header is truncated");
+
+        // The first dimension of dense/weights is so large that its size overflows
+        let mut wrong = bytes.clone();
+        let dim = MAGIC.len() + 12 + "dense/weights".len() + 2;
+        wrong[dim..dim + 8].copy_from_slice(&(1u64 << 62).to_le_bytes());
+        let error = Checkpoint::from_bytes(wrong).err().unwrap();
+        assert_eq!(error.to_string(), "Invalid checkpoint data: the size of dense/weights overflows");
+
+        let mut wrong = bytes.clone();
+        wrong[0] = b'X';
+        assert!(matches!(Checkpoint::from_bytes(wrong), Err(EtlError::InvalidFormat { format: "checkpoint", .. })));
+    }
+}
@@ -0,0 +1,353 @@
+use crate::etl_expr::*;
+use crate::matrix_2d::Matrix2d;
+
+// The declaration of ColumnView
+
+/// A strided view of the j-th column of a matrix
+#[derive(Clone)]
+pub struct ColumnView<T: EtlValueType, Expr: WrappableExpr<T>> {
+    expr: EtlWrapper<T, Expr::WrappedAs>,
+    column: usize,
+}
+
+// The declaration of ColumnViewMut<'a, T>
+
+/// A strided mutable view of the j-th column of a matrix
+pub struct ColumnViewMut<'a, T: EtlValueType> {
+    data: &'a mut [T],
+    column: usize,
+    rows: usize,
+    columns: usize,
+}
+
+// The functions of ColumnView
+
+impl<T: EtlValueType, Expr: WrappableExpr<T>> ColumnView<T, Expr> {
+    pub fn new(expr: Expr, column: usize) -> Self {
+        Self { expr: expr.wrap(), column }
+    }
+}
+
+// The functions of ColumnViewMut<'a, T>
+
+impl<'a, T: EtlValueType> ColumnViewMut<'a, T> {
+    pub fn new(matrix: &'a mut Matrix2d<T>, column: usize) -> Self {
+        let rows = matrix.rows();
+        let columns = matrix.columns();
+
+        Self {
+            data: &mut matrix.data,
+            column,
+            rows,
+            columns,
+        }
+    }
+
+    pub fn at_mut(&mut self, i: usize) -> &mut T {
+        &mut self.data[i * self.columns + self.column]
+    }
+
+    pub fn fill(&mut self, value: T) {
+        for lhs in self.data.iter_mut().skip(self.column).step_by(self.columns).take(self.rows) {
+            *lhs = value;
+        }
+    }
+
+    pub fn inplace_axpy<RightExpr: EtlExpr<T>>(&mut self, alpha: T, beta: T, y: RightExpr) {
+        validate_assign(&&*self, &y);
+        self.apply_strided(&y, |lhs, rhs| *lhs = alpha * *lhs + beta * rhs);
+    }
+
+    // The destination is strided, so the assignment is done element by element, which works
+    // for every type of expression
+    fn apply_strided<RightExpr: EtlExpr<T>>(&mut self, rhs: &RightExpr, functor: impl Fn(&mut T, T)) {
+        for (lhs, rhs) in self.data.iter_mut().skip(self.column).step_by(self.columns).take(self.rows).zip(rhs.iter()) {
+            functor(lhs, rhs);
+        }
+    }
+}
+
+// Both views iterate with a stride over the storage
+type StridedIter<'x, T> = std::iter::Cloned<std::iter::StepBy<std::slice::Iter<'x, T>>>;
+
+fn strided_range<T>(data: &[T], column: usize, columns: usize, range: std::ops::Range<usize>) -> StridedIter<'_, T>
+where
+    T: Clone,
+{
+    let start = column + range.start * columns;
+    let end = if range.end > range.start { column + (range.end - 1) * columns + 1 } else { start };
+
+    data[start..end].iter().step_by(columns).cloned()
+}
+
+// ColumnView is an EtlExpr
+impl<T: EtlValueType, Expr: WrappableExpr<T>> EtlExpr<T> for ColumnView<T, Expr> {
+    const DIMENSIONS: usize = 1;
+    const TYPE: EtlType = EtlType::Unaligned;
+    const THREAD_SAFE: bool = Expr::THREAD_SAFE;
+
+    type Iter<'x>
+        = StridedIter<'x, T>
+    where
+        T: 'x,
+        Self: 'x;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.iter_range(0..self.size())
+    }
+
+    fn iter_range(&self, range: std::ops::Range<usize>) -> Self::Iter<'_> {
+        strided_range(self.expr.value.get_data(), self.column, self.expr.value.dim(1), range)
+    }
+
+    fn size(&self) -> usize {
+        self.expr.value.rows()
+    }
+
+    fn rows(&self) -> usize {
+        self.expr.value.rows()
+    }
+
+    fn at(&self, i: usize) -> T {
+        self.expr.value.at(i * self.expr.value.dim(1) + self.column)
+    }
+}
+
+// ColumnViewMut is an EtlExpr (when borrowed)
+impl<T: EtlValueType> EtlExpr<T> for &ColumnViewMut<'_, T> {
+    const DIMENSIONS: usize = 1;
+    const TYPE: EtlType = EtlType::Unaligned;
+    const THREAD_SAFE: bool = true;
+
+    type Iter<'x>
+        = StridedIter<'x, T>
+    where
+        T: 'x,
+        Self: 'x;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.iter_range(0..self.size())
+    }
+
+    fn iter_range(&self, range: std::ops::Range<usize>) -> Self::Iter<'_> {
+        strided_range(self.data, self.column, self.columns, range)
+    }
+
+    fn size(&self) -> usize {
+        self.rows
+    }
+
+    fn rows(&self) -> usize {
+        self.rows
+    }
+
+    fn at(&self, i: usize) -> T {
+        self.data[i * self.columns + self.column]
+    }
+}
+
+// ColumnView is an EtlWrappable
+// ColumnView wraps as value
+impl<T: EtlValueType, Expr: WrappableExpr<T>> EtlWrappable<T> for ColumnView<T, Expr> {
+    type WrappedAs = ColumnView<T, Expr>;
+
+    fn wrap(self) -> EtlWrapper<T, Self::WrappedAs> {
+        EtlWrapper {
+            value: self,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+// ColumnViewMut wraps as reference
+impl<'a, T: EtlValueType> EtlWrappable<T> for &'a ColumnViewMut<'_, T> {
+    type WrappedAs = &'a ColumnViewMut<'a, T>;
+
+    fn wrap(self) -> EtlWrapper<T, Self::WrappedAs> {
+        EtlWrapper {
+            value: self,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+// ColumnView computes as copy
+impl<T: EtlValueType, Expr: WrappableExpr<T>> EtlComputable<T> for ColumnView<T, Expr> {
+    fn to_data(&self) -> Vec<T> {
+        let mut vec = vec![T::default(); padded_size(self.size())];
+        assign_direct(&mut vec, self);
+        vec
+    }
+}
+
+// ColumnViewMut computes as copy
+impl<T: EtlValueType> EtlComputable<T> for &ColumnViewMut<'_, T> {
+    fn to_data(&self) -> Vec<T> {
+        let mut vec = vec![T::default(); padded_size(self.size())];
+        assign_direct(&mut vec, self);
+        vec
+    }
+}
+
+// Operator overloading for ColumnViewMut<'a, T>
+
+// Since we can't overload Assign, we settle for BitOrAssign
+impl<T: EtlValueType, RightExpr: EtlExpr<T>> std::ops::BitOrAssign<RightExpr> for ColumnViewMut<'_, T> {
+    fn bitor_assign(&mut self, rhs: RightExpr) {
+        validate_assign(&&*self, &rhs);
+        self.apply_strided(&rhs, |lhs, rhs| *lhs = rhs);
+    }
+}
+
+impl<T: EtlValueType, RightExpr: EtlExpr<T>> std::ops::AddAssign<RightExpr> for ColumnViewMut<'_, T> {
+    fn add_assign(&mut self, rhs: RightExpr) {
+        validate_assign(&&*self, &rhs);
+        self.apply_strided(&rhs, |lhs, rhs| *lhs += rhs);
+    }
+}
+
+impl<T: EtlValueType, RightExpr: EtlExpr<T>> std::ops::SubAssign<RightExpr> for ColumnViewMut<'_, T> {
+    fn sub_assign(&mut self, rhs: RightExpr) {
+        validate_assign(&&*self, &rhs);
+        self.apply_strided(&rhs, |lhs, rhs| *lhs -= rhs);
+    }
+}
+
+impl<T: EtlValueType, RightExpr: EtlExpr<T>> std::ops::ShrAssign<RightExpr> for ColumnViewMut<'_, T> {
+    fn shr_assign(&mut self, rhs: RightExpr) {
+        validate_assign(&&*self, &rhs);
+        self.apply_strided(&rhs, |lhs, rhs| *lhs *= rhs);
+    }
+}
+
+impl<T: EtlValueType, RightExpr: EtlExpr<T>> std::ops::DivAssign<RightExpr> for ColumnViewMut<'_, T> {
+    fn div_assign(&mut self, rhs: RightExpr) {
+        validate_assign(&&*self, &rhs);
+        self.apply_strided(&rhs, |lhs, rhs| *lhs /= rhs);
+    }
+}
+
+// Operations
+
+pub fn col<T: EtlValueType, Expr: WrappableExpr<T> + Etl2d<T>>(expr: Expr, j: usize) -> ColumnView<T, Expr> {
+    assert!(Expr::TYPE.direct(), "col can only work on direct expressions");
+    assert!(j < expr.columns());
+
+    ColumnView::<T, Expr>::new(expr, j)
+}
+
+pub fn col_mut<T: EtlValueType>(matrix: &mut Matrix2d<T>, j: usize) -> ColumnViewMut<'_, T> {
+    assert!(j < matrix.columns());
+
+    ColumnViewMut::new(matrix, j)
+}
+
+crate::impl_add_op_unary_expr!(ColumnView<T, Expr>);
+crate::impl_sub_op_unary_expr!(ColumnView<T, Expr>);
+crate::impl_mul_op_unary_expr!(ColumnView<T, Expr>);
+crate::impl_div_op_unary_expr!(ColumnView<T, Expr>);
+crate::impl_scale_op_unary_expr!(ColumnView<T, Expr>);
+
+// The tests
+
+#[cfg(test)]
+mod tests {
+    use crate::column_view::{col, col_mut};
+    use crate::constant::cst;
+    use crate::etl_expr::{Etl2d, EtlExpr};
+    use crate::matrix_2d::Matrix2d;
+    use crate::vector::Vector;
+
+    #[test]
+    fn basic_col() {
+        let a = Matrix2d::<i64>::new_iota(3, 4, 1);
+
+        let expr = col(&a, 2);
+
+        assert_eq!(expr.size(), 3);
+        assert_eq!(expr.rows(), 3);
+        assert_eq!(expr.at(0), 3);
+        assert_eq!(expr.at(2), 11);
+
+        let values: Vec<i64> = expr.iter().collect();
+        assert_eq!(values, vec![3, 7, 11]);
+
+        let values: Vec<i64> = expr.iter_range(1..3).collect();
+        assert_eq!(values, vec![7, 11]);
+    }
+
+    #[test]
+    fn col_expr() {
+        let a = Matrix2d::<i64>::new_iota(3, 4, 1);
+        let b = Vector::<i64>::new_iota(3, 1);
+
+        let mut c = Vector::<i64>::new(3);
+        c |= col(&a, 1) + &b;
+
+        assert_eq!(c.at(0), 3);
+        assert_eq!(c.at(1), 8);
+        assert_eq!(c.at(2), 13);
+
+        c |= (col(&a, 0) >> col(&a, 3)) - cst(1);
+
+        assert_eq!(c.at(0), 3);
+        assert_eq!(c.at(1), 39);
+        assert_eq!(c.at(2), 107);
+    }
+
+    #[test]
+    fn col_gemv() {
+        let a = Matrix2d::<i64>::new_iota(3, 4, 1);
+        let b = Matrix2d::<i64>::new_iota(2, 3, 1);
+
+        let mut c = Vector::<i64>::new(2);
+        c |= &b * col(&a, 0);
+
+        assert_eq!(c.at(0), 38);
+        assert_eq!(c.at(1), 83);
+    }
+
+    #[test]
+    fn col_mut_assign() {
+        let mut a = Matrix2d::<i64>::new_iota(3, 4, 1);
+        let b = Vector::<i64>::new_iota(3, 1);
+
+        let mut column = col_mut(&mut a, 1);
+        column |= &b;
+        column += cst(2);
+        column >>= &b;
+        column -= &b;
+        column /= cst(2);
+
+        assert_eq!(a.at2(0, 0), 1);
+        assert_eq!(a.at2(0, 1), 1);
+        assert_eq!(a.at2(1, 1), 3);
+        assert_eq!(a.at2(2, 1), 6);
+        assert_eq!(a.at2(2, 2), 11);
+    }
+
+    #[test]
+    fn col_mut_from_col() {
+        let mut a = Matrix2d::<i64>::new_iota(3, 4, 1);
+        let b = Matrix2d::<i64>::new_iota(3, 4, 1);
+
+        col_mut(&mut a, 0).inplace_axpy(1, 2, col(&b, 3));
+        col_mut(&mut a, 3).fill(0);
+
+        assert_eq!(a.at2(0, 0), 9);
+        assert_eq!(a.at2(1, 0), 21);
+        assert_eq!(a.at2(2, 0), 33);
+        assert_eq!(a.at2(2, 3), 0);
+        assert_eq!(a.at2(2, 2), 11);
+    }
+
+    #[test]
+    #[should_panic]
+    fn col_mut_invalid_assign() {
+        let mut a = Matrix2d::<i64>::new_iota(3, 4, 1);
+        let b = Vector::<i64>::new_iota(4, 1);
+
+        let mut column = col_mut(&mut a, 0);
+        column |= &b;
+    }
+}
@@ -0,0 +1,217 @@
+use crate::base_traits::{Cast, Constants, Float, ScalarLanes, Vectorizable};
+use crate::half_float::{bf16, f16};
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+// The declaration of Complex<T>
+
+/// A complex number, stored as its real part followed by its imaginary part
+///
+/// Complex numbers are ordered lexicographically (real part first), like numpy does, so that
+/// argmax, min and max are defined for complex containers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[repr(C)]
+pub struct Complex<T> {
+    pub re: T,
+    pub im: T,
+}
+
+// The functions of Complex<T>
+
+impl<T> Complex<T> {
+    pub const fn new(re: T, im: T) -> Self {
+        Self { re, im }
+    }
+}
+
+impl<T: Copy + Neg<Output = T>> Complex<T> {
+    /// The complex conjugate
+    pub fn conj(self) -> Self {
+        Self::new(self.re, -self.im)
+    }
+}
+
+impl<T: Copy + Add<Output = T> + Mul<Output = T> + Float> Complex<T> {
+    /// The magnitude
+    pub fn abs(self) -> T {
+        (self.re * self.re + self.im * self.im).sqrt()
+    }
+}
+
+// Complex<T> is a value type
+
+impl<T: Constants> Constants for Complex<T> {
+    fn one() -> Self {
+        Self::new(T::one(), T::zero())
+    }
+
+    fn zero() -> Self {
+        Self::new(T::zero(), T::zero())
+    }
+}
+
+// std::simd only supports primitive types, complex kernels go through the lanes one at a time
+// The bounds are the ones of EtlValueType, which cannot be used here since it requires Vectorizable
+impl<T> Vectorizable for Complex<T>
+where
+    T: Constants + Default + Copy + PartialOrd + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T> + Sync + Send + fmt::Display,
+{
+    type Lanes = ScalarLanes<Complex<T>>;
+    type Compute = Complex<T>;
+
+    fn to_compute(self) -> Self {
+        self
+    }
+
+    fn from_compute(value: Self) -> Self {
+        value
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Complex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(precision) = f.precision() {
+            write!(f, "({:.*},{:.*})", precision, self.re, precision, self.im)
+        } else {
+            write!(f, "({},{})", self.re, self.im)
+        }
+    }
+}
+
+impl<T: Cast<U>, U> Cast<Complex<U>> for Complex<T> {
+    fn cast(self) -> Complex<U> {
+        Complex::new(self.re.cast(), self.im.cast())
+    }
+}
+
+// The real values become complex values without imaginary part
+macro_rules! impl_real_to_complex_cast {
+    ($($type:ty),*) => {
+        $(
+            impl<U: Constants> Cast<Complex<U>> for $type
+            where
+                $type: Cast<U>,
+            {
+                fn cast(self) -> Complex<U> {
+                    Complex::new(self.cast(), U::zero())
+                }
+            }
+        )*
+    };
+}
+
+impl_real_to_complex_cast!(i64, i32, i16, i8, u64, u32, u16, u8, usize, f32, f64, f16, bf16);
+
+// Operations
+
+impl<T: Neg<Output = T>> Neg for Complex<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.re, -self.im)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Complex<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Complex<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl<T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T>> Mul for Complex<T> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::new(self.re * other.re - self.im * other.im, self.re * other.im + self.im * other.re)
+    }
+}
+
+impl<T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T>> Div for Complex<T> {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        let norm = other.re * other.re + other.im * other.im;
+        Self::new((self.re * other.re + self.im * other.im) / norm, (self.im * other.re - self.re * other.im) / norm)
+    }
+}
+
+impl<T: Add<Output = T> + Copy> AddAssign for Complex<T> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Sub<Output = T> + Copy> SubAssign for Complex<T> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T>> MulAssign for Complex<T> {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+impl<T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Div<Output = T>> DivAssign for Complex<T> {
+    fn div_assign(&mut self, other: Self) {
+        *self = *self / other;
+    }
+}
+
+// The tests
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::Complex;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn arithmetic() {
+        let a = Complex::new(1.0, 2.0);
+        let b = Complex::new(3.0, -1.0);
+
+        assert_eq!(a + b, Complex::new(4.0, 1.0));
+        assert_eq!(a - b, Complex::new(-2.0, 3.0));
+        assert_eq!(a * b, Complex::new(5.0, 5.0));
+        assert_eq!(-a, Complex::new(-1.0, -2.0));
+
+        let c = (a * b) / b;
+        assert_relative_eq!(c.re, 1.0, epsilon = 1e-12);
+        assert_relative_eq!(c.im, 2.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn conj_abs() {
+        let a = Complex::new(3.0f32, -4.0);
+
+        assert_eq!(a.conj(), Complex::new(3.0, 4.0));
+        assert_relative_eq!(a.abs(), 5.0, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn ordering() {
+        assert!(Complex::new(1.0, 5.0) < Complex::new(2.0, 0.0));
+        assert!(Complex::new(1.0, 1.0) < Complex::new(1.0, 2.0));
+    }
+
+    #[test]
+    fn display() {
+        let a = Complex::new(1.5f64, -2.0);
+
+        assert_eq!(format!("{a}"), "(1.5,-2)");
+        assert_eq!(format!("{a:.2}"), "(1.50,-2.00)");
+    }
+}
@@ -0,0 +1,307 @@
+use crate::base_traits::Float;
+use crate::complex::Complex;
+use crate::etl_expr::*;
+
+use std::ops::Neg;
+
+// The declaration of ComplexExpr
+
+/// An element-wise function of complex values
+pub trait ComplexFunction<T, Out>: Clone + Sync + Send {
+    fn apply(value: Complex<T>) -> Out;
+}
+
+/// The complex conjugate
+#[derive(Clone)]
+pub struct Conj;
+
+/// The real part
+#[derive(Clone)]
+pub struct Real;
+
+/// The imaginary part
+#[derive(Clone)]
+pub struct Imag;
+
+/// The magnitude
+#[derive(Clone)]
+pub struct Magnitude;
+
+impl<T: Copy + Neg<Output = T>> ComplexFunction<T, Complex<T>> for Conj {
+    fn apply(value: Complex<T>) -> Complex<T> {
+        value.conj()
+    }
+}
+
+impl<T> ComplexFunction<T, T> for Real {
+    fn apply(value: Complex<T>) -> T {
+        value.re
+    }
+}
+
+impl<T> ComplexFunction<T, T> for Imag {
+    fn apply(value: Complex<T>) -> T {
+        value.im
+    }
+}
+
+impl<T: EtlValueType + Float> ComplexFunction<T, T> for Magnitude {
+    fn apply(value: Complex<T>) -> T {
+        value.abs()
+    }
+}
+
+/// Expression applying an element-wise function to a complex expression, the result can be real
+/// (real, imag, abs) or complex (conj)
+#[derive(Clone)]
+pub struct ComplexExpr<T: EtlValueType, Out: EtlValueType, Expr: WrappableExpr<Complex<T>>, F: ComplexFunction<T, Out>>
+where
+    Complex<T>: EtlValueType,
+{
+    expr: EtlWrapper<Complex<T>, Expr::WrappedAs>,
+    _marker: std::marker::PhantomData<(Out, F)>,
+}
+
+// The functions of ComplexExpr
+
+impl<T: EtlValueType, Out: EtlValueType, Expr: WrappableExpr<Complex<T>>, F: ComplexFunction<T, Out>> ComplexExpr<T, Out, Expr, F>
+where
+    Complex<T>: EtlValueType,
+{
+    pub fn new(expr: Expr) -> Self {
+        Self {
+            expr: expr.wrap(),
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+pub struct ComplexExprIterator<'a, T: EtlValueType, Out: EtlValueType, Expr: EtlExpr<Complex<T>> + 'a, F: ComplexFunction<T, Out>>
+where
+    Complex<T>: EtlValueType,
+    T: 'a,
+{
+    sub_iter: Expr::Iter<'a>,
+    _marker: std::marker::PhantomData<(Out, F)>,
+}
+
+impl<'a, T: EtlValueType, Out: EtlValueType, Expr: EtlExpr<Complex<T>>, F: ComplexFunction<T, Out>> Iterator for ComplexExprIterator<'a, T, Out, Expr, F>
+where
+    Complex<T>: EtlValueType,
+{
+    type Item = Out;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.sub_iter.next().map(F::apply)
+    }
+}
+
+// ComplexExpr is an EtlExpr
+impl<T: EtlValueType, Out: EtlValueType, Expr: WrappableExpr<Complex<T>>, F: ComplexFunction<T, Out>> EtlExpr<Out> for ComplexExpr<T, Out, Expr, F>
+where
+    Complex<T>: EtlValueType,
+{
+    const DIMENSIONS: usize = Expr::DIMENSIONS;
+    const TYPE: EtlType = simple_unary_type(Expr::TYPE);
+    const THREAD_SAFE: bool = Expr::THREAD_SAFE;
+
+    type Iter<'x>
+        = ComplexExprIterator<'x, T, Out, Expr::WrappedAs, F>
+    where
+        Out: 'x,
+        Self: 'x;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        Self::Iter {
+            sub_iter: self.expr.value.iter(),
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    fn iter_range(&self, range: std::ops::Range<usize>) -> Self::Iter<'_> {
+        Self::Iter {
+            sub_iter: self.expr.value.iter_range(range),
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.expr.value.size()
+    }
+
+    fn rows(&self) -> usize {
+        self.expr.value.rows()
+    }
+
+    fn dim(&self, i: usize) -> usize {
+        self.expr.value.dim(i)
+    }
+
+    fn at(&self, i: usize) -> Out {
+        F::apply(self.expr.value.at(i))
+    }
+}
+
+// ComplexExpr has the rank of its sub expression
+impl<T: EtlValueType, Out: EtlValueType, Expr: WrappableExpr<Complex<T>> + Etl2d<Complex<T>>, F: ComplexFunction<T, Out>> Etl2d<Out>
+    for ComplexExpr<T, Out, Expr, F>
+where
+    Complex<T>: EtlValueType,
+{
+}
+impl<T: EtlValueType, Out: EtlValueType, Expr: WrappableExpr<Complex<T>> + Etl3d<Complex<T>>, F: ComplexFunction<T, Out>> Etl3d<Out>
+    for ComplexExpr<T, Out, Expr, F>
+where
+    Complex<T>: EtlValueType,
+{
+}
+impl<T: EtlValueType, Out: EtlValueType, Expr: WrappableExpr<Complex<T>> + Etl4d<Complex<T>>, F: ComplexFunction<T, Out>> Etl4d<Out>
+    for ComplexExpr<T, Out, Expr, F>
+where
+    Complex<T>: EtlValueType,
+{
+}
+
+// ComplexExpr is an EtlWrappable
+// ComplexExpr wraps as value
+impl<T: EtlValueType, Out: EtlValueType, Expr: WrappableExpr<Complex<T>>, F: ComplexFunction<T, Out>> EtlWrappable<Out> for ComplexExpr<T, Out, Expr, F>
+where
+    Complex<T>: EtlValueType,
+{
+    type WrappedAs = ComplexExpr<T, Out, Expr, F>;
+
+    fn wrap(self) -> EtlWrapper<Out, Self::WrappedAs> {
+        EtlWrapper {
+            value: self,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+// ComplexExpr computes as copy
+impl<T: EtlValueType, Out: EtlValueType, Expr: WrappableExpr<Complex<T>>, F: ComplexFunction<T, Out>> EtlComputable<Out> for ComplexExpr<T, Out, Expr, F>
+where
+    Complex<T>: EtlValueType,
+{
+    fn to_data(&self) -> Vec<Out> {
+        let mut vec = vec![Out::default(); padded_size(self.size())];
+        assign_direct(&mut vec, self);
+        vec
+    }
+}
+
+// Operations
+
+pub fn conj<T: EtlValueType + Neg<Output = T>, Expr: WrappableExpr<Complex<T>>>(expr: Expr) -> ComplexExpr<T, Complex<T>, Expr, Conj>
+where
+    Complex<T>: EtlValueType,
+{
+    ComplexExpr::new(expr)
+}
+
+pub fn real<T: EtlValueType, Expr: WrappableExpr<Complex<T>>>(expr: Expr) -> ComplexExpr<T, T, Expr, Real>
+where
+    Complex<T>: EtlValueType,
+{
+    ComplexExpr::new(expr)
+}
+
+pub fn imag<T: EtlValueType, Expr: WrappableExpr<Complex<T>>>(expr: Expr) -> ComplexExpr<T, T, Expr, Imag>
+where
+    Complex<T>: EtlValueType,
+{
+    ComplexExpr::new(expr)
+}
+
+pub fn abs<T: EtlValueType + Float, Expr: WrappableExpr<Complex<T>>>(expr: Expr) -> ComplexExpr<T, T, Expr, Magnitude>
+where
+    Complex<T>: EtlValueType,
+{
+    ComplexExpr::new(expr)
+}
+
+crate::impl_op_converting_expr!(
+    [T: EtlValueType, Out: EtlValueType, Expr: WrappableExpr<Complex<T>>, F: ComplexFunction<T, Out>] ComplexExpr<T, Out, Expr, F>, Out
+    where Complex<T>: EtlValueType
+);
+
+// The tests
+
+#[cfg(test)]
+mod tests {
+    use crate::complex::Complex;
+    use crate::complex_expr::{abs, conj, imag, real};
+    use crate::etl_expr::{Etl2d, EtlExpr};
+    use crate::matrix_2d::Matrix2d;
+    use crate::vector::Vector;
+
+    use approx::assert_relative_eq;
+
+    fn sample() -> Vector<Complex<f64>> {
+        let mut a = Vector::<Complex<f64>>::new(3);
+        a[0] = Complex::new(3.0, 4.0);
+        a[1] = Complex::new(-1.0, 0.5);
+        a[2] = Complex::new(0.0, -2.0);
+        a
+    }
+
+    #[test]
+    fn conj_real_imag() {
+        let a = sample();
+
+        let mut b = Vector::<Complex<f64>>::new(3);
+        b |= conj(&a);
+
+        assert_eq!(b.at(0), Complex::new(3.0, -4.0));
+        assert_eq!(b.at(2), Complex::new(0.0, 2.0));
+
+        let mut re = Vector::<f64>::new(3);
+        let mut im = Vector::<f64>::new(3);
+        re |= real(&a);
+        im |= imag(&a);
+
+        assert_eq!(re.at(1), -1.0);
+        assert_eq!(im.at(1), 0.5);
+        assert_eq!(im.at(2), -2.0);
+    }
+
+    #[test]
+    fn magnitude() {
+        let a = sample();
+
+        let expr = abs(&a);
+
+        assert_eq!(expr.size(), 3);
+        assert_relative_eq!(expr.at(0), 5.0, epsilon = 1e-12);
+        assert_relative_eq!(expr.at(2), 2.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn deep() {
+        let a = sample();
+        let b = Vector::<f64>::new_iota(3, 1.0);
+
+        let mut c = Vector::<f64>::new(3);
+        c |= real(&a) + (imag(&a) >> &b);
+
+        assert_relative_eq!(c.at(0), 3.0 + 4.0 * 1.0, epsilon = 1e-12);
+        assert_relative_eq!(c.at(1), -1.0 + 0.5 * 2.0, epsilon = 1e-12);
+        assert_relative_eq!(c.at(2), 0.0 - 2.0 * 3.0, epsilon = 1e-12);
+
+        let mut d = Vector::<Complex<f64>>::new(3);
+        d |= conj(&a) + &a;
+
+        assert_eq!(d.at(0), Complex::new(6.0, 0.0));
+    }
+
+    #[test]
+    fn matrix() {
+        let mut a = Matrix2d::<Complex<f32>>::new(2, 2);
+        *a.at_mut(0, 1) = Complex::new(1.0, -1.0);
+
+        let b = real(&a);
+
+        assert_eq!(b.dim(1), 2);
+        assert_eq!(b.at2(0, 1), 1.0);
+    }
+}
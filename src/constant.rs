@@ -57,10 +57,6 @@ impl<T: EtlValueType> EtlExpr<T> for Constant<T> {
         0
     }
 
-    fn columns(&self) -> usize {
-        0
-    }
-
     fn at(&self, _i: usize) -> T {
         self.value
     }
@@ -85,6 +81,9 @@ impl<T: EtlValueType> EtlWrappable<T> for Constant<T> {
     }
 }
 
+// Constant<T> has every fixed shape
+impl<T: EtlValueType, S> FixedShape<S> for Constant<T> {}
+
 // Operations
 
 crate::impl_add_op_constant!(Constant<T>);
@@ -0,0 +1,342 @@
+use crate::etl_error::EtlError;
+use crate::etl_expr::*;
+use crate::matrix_2d::Matrix2d;
+use crate::vector::Vector;
+
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::str::FromStr;
+
+// Reading and writing of CSV files
+//
+// Only numbers are stored, so there is no quoting: a line is a row of the matrix and its values
+// are separated by the delimiter. Empty lines are ignored.
+
+/// How a Vector is stored in a CSV file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CsvLayout {
+    /// One value per line
+    #[default]
+    Column,
+    /// All the values on a single line
+    Row,
+}
+
+/// The options of the CSV reading and writing
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvOptions {
+    /// The separator of the values of a line
+    pub delimiter: char,
+    /// Indicates that the first line is a header, it is skipped when reading
+    pub header: bool,
+    /// The names written in the header, the indices of the columns are written when it is empty
+    pub column_names: Vec<String>,
+    /// The number of decimals written for each value, all the digits when None
+    pub precision: Option<usize>,
+    /// How a Vector is stored
+    pub layout: CsvLayout,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            header: false,
+            column_names: Vec::new(),
+            precision: None,
+            layout: CsvLayout::Column,
+        }
+    }
+}
+
+// The functions of CsvOptions
+
+impl CsvOptions {
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    pub fn with_column_names<S: ToString>(mut self, names: &[S]) -> Self {
+        self.header = true;
+        self.column_names = names.iter().map(|name| name.to_string()).collect();
+        self
+    }
+
+    pub fn with_precision(mut self, precision: usize) -> Self {
+        self.precision = Some(precision);
+        self
+    }
+
+    pub fn with_layout(mut self, layout: CsvLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+}
+
+// The values read from a CSV file, in row-major order
+struct CsvData<T> {
+    rows: usize,
+    columns: usize,
+    values: Vec<T>,
+}
+
+// Read the values, all the lines must have the same number of values (or columns when it is set)
+fn read_csv<T: FromStr, R: Read>(reader: R, options: &CsvOptions, columns: Option<usize>, single_row: bool) -> Result<CsvData<T>, EtlError> {
+    let mut data = CsvData {
+        rows: 0,
+        columns: columns.unwrap_or(0),
+        values: Vec::new(),
+    };
+
+    for (index, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line?;
+        let number = index + 1;
+
+        if (options.header && index == 0) || line.trim().is_empty() {
+            continue;
+        }
+
+        if single_row && data.rows == 1 {
+            return Err(EtlError::Parse {
+                line: number,
+                column: 1,
+                message: "the values must be on a single line".to_string(),
+            });
+        }
+
+        let fields = line.split(options.delimiter).map(str::trim).collect::<Vec<_>>();
+
+        if data.rows == 0 && columns.is_none() {
+            data.columns = fields.len();
+        }
+
+        if fields.len() != data.columns {
+            return Err(EtlError::Parse {
+                line: number,
+                column: fields.len().min(data.columns) + 1,
+                message: format!("found {} values instead of {}", fields.len(), data.columns),
+            });
+        }
+
+        for (column, field) in fields.iter().enumerate() {
+            let value = field.parse().map_err(|_| EtlError::Parse {
+                line: number,
+                column: column + 1,
+                message: format!("invalid value '{field}'"),
+            })?;
+            data.values.push(value);
+        }
+
+        data.rows += 1;
+    }
+
+    Ok(data)
+}
+
+fn write_value<T: EtlValueType, W: Write>(writer: &mut W, value: T, options: &CsvOptions) -> std::io::Result<()> {
+    match options.precision {
+        Some(precision) => write!(writer, "{value:.precision$}"),
+        None => write!(writer, "{value}"),
+    }
+}
+
+fn write_header<W: Write>(writer: &mut W, columns: usize, options: &CsvOptions) -> std::io::Result<()> {
+    if !options.header {
+        return Ok(());
+    }
+
+    for column in 0..columns {
+        if column > 0 {
+            write!(writer, "{}", options.delimiter)?;
+        }
+
+        match options.column_names.get(column) {
+            Some(name) => write!(writer, "{name}")?,
+            None => write!(writer, "{column}")?,
+        }
+    }
+
+    writeln!(writer)
+}
+
+fn write_csv<T: EtlValueType, W: Write>(writer: W, rows: usize, columns: usize, values: &[T], options: &CsvOptions) -> Result<(), EtlError> {
+    let mut writer = BufWriter::new(writer);
+
+    write_header(&mut writer, columns, options)?;
+
+    for row in 0..rows {
+        for column in 0..columns {
+            if column > 0 {
+                write!(writer, "{}", options.delimiter)?;
+            }
+            write_value(&mut writer, values[row * columns + column], options)?;
+        }
+        writeln!(writer)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+// The containers can be read from and written to CSV files
+
+impl<T: EtlValueType + FromStr> Matrix2d<T> {
+    /// Read a matrix from CSV, each line is a row
+    pub fn from_csv<R: Read>(reader: R, options: &CsvOptions) -> Result<Self, EtlError> {
+        let data = read_csv(reader, options, None, false)?;
+
+        let mut matrix = Matrix2d::new(data.rows, data.columns);
+        matrix.data[..data.values.len()].copy_from_slice(&data.values);
+        Ok(matrix)
+    }
+}
+
+impl<T: EtlValueType> Matrix2d<T> {
+    /// Write the matrix as CSV, each row on a line
+    pub fn to_csv<W: Write>(&self, writer: W, options: &CsvOptions) -> Result<(), EtlError> {
+        write_csv(writer, self.rows(), self.dim(1), &self.data, options)
+    }
+}
+
+impl<T: EtlValueType + FromStr> Vector<T> {
+    /// Read a vector from CSV, as a single column or a single row depending on the layout
+    pub fn from_csv<R: Read>(reader: R, options: &CsvOptions) -> Result<Self, EtlError> {
+        let data = match options.layout {
+            CsvLayout::Column => read_csv(reader, options, Some(1), false)?,
+            CsvLayout::Row => read_csv(reader, options, None, true)?,
+        };
+
+        let mut vector = Vector::new(data.values.len());
+        vector.data[..data.values.len()].copy_from_slice(&data.values);
+        Ok(vector)
+    }
+}
+
+impl<T: EtlValueType> Vector<T> {
+    /// Write the vector as CSV, as a single column or a single row depending on the layout
+    pub fn to_csv<W: Write>(&self, writer: W, options: &CsvOptions) -> Result<(), EtlError> {
+        match options.layout {
+            CsvLayout::Column => write_csv(writer, self.size(), 1, &self.data, options),
+            CsvLayout::Row => write_csv(writer, 1, self.size(), &self.data, options),
+        }
+    }
+}
+
+// The tests
+
+#[cfg(test)]
+mod tests {
+    use crate::csv::*;
+    use crate::etl_expr::{Etl2d, EtlExpr};
+    use crate::matrix_2d::Matrix2d;
+    use crate::vector::Vector;
+
+    fn to_string<F: FnOnce(&mut Vec<u8>)>(f: F) -> String {
+        let mut out = Vec::new();
+        f(&mut out);
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn matrix() {
+        let input = "1.5, 2, 3\n\n4,5,-6.25\r\n";
+
+        let a = Matrix2d::<f64>::from_csv(input.as_bytes(), &CsvOptions::default()).unwrap();
+
+        assert_eq!(a.rows(), 2);
+        assert_eq!(a.columns(), 3);
+        assert_eq!(a.at2(0, 0), 1.5);
+        assert_eq!(a.at2(1, 2), -6.25);
+
+        let output = to_string(|out| a.to_csv(out, &CsvOptions::default()).unwrap());
+        assert_eq!(output, "1.5,2,3\n4,5,-6.25\n");
+
+        let b = Matrix2d::<f64>::from_csv(output.as_bytes(), &CsvOptions::default()).unwrap();
+        assert_eq!(b.at2(1, 0), 4.0);
+    }
+
+    #[test]
+    fn options() {
+        let a = Matrix2d::<f32>::new_iota(2, 2, 0.5);
+
+        let options = CsvOptions::default().with_delimiter(';').with_column_names(&["x", "y"]).with_precision(2);
+        let output = to_string(|out| a.to_csv(out, &options).unwrap());
+        assert_eq!(output, "x;y\n0.50;1.50\n2.50;3.50\n");
+
+        let b = Matrix2d::<f32>::from_csv(output.as_bytes(), &options).unwrap();
+        assert_eq!(b.rows(), 2);
+        assert_eq!(b.at2(1, 1), 3.5);
+
+        let options = CsvOptions::default().with_delimiter('\t').with_header(true);
+        let output = to_string(|out| a.to_csv(out, &options).unwrap());
+        assert_eq!(output, "0\t1\n0.5\t1.5\n2.5\t3.5\n");
+    }
+
+    #[test]
+    fn vector() {
+        let a = Vector::<i32>::new_iota(3, 7);
+
+        let column = to_string(|out| a.to_csv(out, &CsvOptions::default()).unwrap());
+        assert_eq!(column, "7\n8\n9\n");
+
+        let options = CsvOptions::default().with_layout(CsvLayout::Row);
+        let row = to_string(|out| a.to_csv(out, &options).unwrap());
+        assert_eq!(row, "7,8,9\n");
+
+        assert_eq!(Vector::<i32>::from_csv(column.as_bytes(), &CsvOptions::default()).unwrap().at(2), 9);
+        assert_eq!(Vector::<i32>::from_csv(row.as_bytes(), &options).unwrap().at(1), 8);
+
+        let error = Vector::<i32>::from_csv(row.as_bytes(), &CsvOptions::default()).err();
+        assert_eq!(
+            error,
+            Some(EtlError::Parse {
+                line: 1,
+                column: 2,
+                message: "found 3 values instead of 1".to_string(),
+            })
+        );
+
+        let error = Vector::<i32>::from_csv(column.as_bytes(), &options).err();
+        assert_eq!(
+            error,
+            Some(EtlError::Parse {
+                line: 2,
+                column: 1,
+                message: "the values must be on a single line".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let options = CsvOptions::default().with_header(true);
+
+        let error = Matrix2d::<f32>::from_csv("a,b\n1,2\n3,x\n".as_bytes(), &options).err();
+        assert_eq!(
+            error,
+            Some(EtlError::Parse {
+                line: 3,
+                column: 2,
+                message: "invalid value 'x'".to_string(),
+            })
+        );
+
+        let error = Matrix2d::<f32>::from_csv("1,2\n3\n".as_bytes(), &CsvOptions::default()).err();
+        assert_eq!(
+            error,
+            Some(EtlError::Parse {
+                line: 2,
+                column: 2,
+                message: "found 1 values instead of 2".to_string(),
+            })
+        );
+
+        let error = Matrix2d::<u8>::from_csv("1,-2\n".as_bytes(), &CsvOptions::default()).err();
+        assert!(matches!(error, Some(EtlError::Parse { line: 1, column: 2, .. })));
+    }
+}
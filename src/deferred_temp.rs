@@ -0,0 +1,106 @@
+use crate::aligned_buffer::AlignedBuffer;
+use crate::etl_expr::{EtlValueType, padded_size};
+
+use std::sync::OnceLock;
+
+// The declaration of DeferredTemp<T>
+
+/// The result of a smart expression, computed only when it is needed
+///
+/// The result is only kept in a temporary when the expression is read element by element (or
+/// accumulated into an output), otherwise it is computed directly into the output.
+#[derive(Clone, Default)]
+pub struct DeferredTemp<T: EtlValueType> {
+    temp: OnceLock<AlignedBuffer<T>>,
+}
+
+// The functions of DeferredTemp<T>
+
+impl<T: EtlValueType> DeferredTemp<T> {
+    pub fn new() -> Self {
+        Self { temp: OnceLock::new() }
+    }
+
+    /// The temporary, if it has already been computed
+    pub fn get(&self) -> Option<&[T]> {
+        self.temp.get().map(|temp| &temp[..])
+    }
+
+    /// The temporary, computed by compute into a zeroed scratch buffer of (at least) size values the
+    /// first time
+    pub fn get_or_compute(&self, size: usize, compute: impl FnOnce(&mut [T])) -> &[T] {
+        self.temp.get_or_init(|| {
+            let mut temp = AlignedBuffer::from_workspace(padded_size(size));
+            compute(&mut temp);
+            temp
+        })
+    }
+
+    /// Assign the result to output, compute writes it directly into output when the temporary has
+    /// not been computed
+    pub fn compute_into(&self, output: &mut [T], compute: impl FnOnce(&mut [T])) {
+        if let Some(temp) = self.temp.get() {
+            let n = output.len().min(temp.len());
+            output[..n].copy_from_slice(&temp[..n]);
+        } else {
+            compute(output);
+        }
+    }
+
+    pub fn compute_into_add(&self, output: &mut [T], size: usize, compute: impl FnOnce(&mut [T])) {
+        self.accumulate(output, size, compute, |lhs, rhs| *lhs += rhs);
+    }
+
+    pub fn compute_into_sub(&self, output: &mut [T], size: usize, compute: impl FnOnce(&mut [T])) {
+        self.accumulate(output, size, compute, |lhs, rhs| *lhs -= rhs);
+    }
+
+    pub fn compute_into_scale(&self, output: &mut [T], size: usize, compute: impl FnOnce(&mut [T])) {
+        self.accumulate(output, size, compute, |lhs, rhs| *lhs *= rhs);
+    }
+
+    pub fn compute_into_div(&self, output: &mut [T], size: usize, compute: impl FnOnce(&mut [T])) {
+        self.accumulate(output, size, compute, |lhs, rhs| *lhs /= rhs);
+    }
+
+    fn accumulate(&self, output: &mut [T], size: usize, compute: impl FnOnce(&mut [T]), op: impl Fn(&mut T, T)) {
+        let temp = self.get_or_compute(size, compute);
+
+        for (lhs, rhs) in output.iter_mut().zip(temp.iter()) {
+            op(lhs, *rhs);
+        }
+    }
+}
+
+// The tests
+
+#[cfg(test)]
+mod tests {
+    use crate::deferred_temp::*;
+
+    #[test]
+    fn deferred() {
+        let temp = DeferredTemp::<i64>::new();
+        let compute = |output: &mut [i64]| output[..3].copy_from_slice(&[1, 2, 3]);
+
+        // Assigning the result does not keep it
+        let mut output = [0; 3];
+        temp.compute_into(&mut output, compute);
+        assert_eq!(output, [1, 2, 3]);
+        assert!(temp.get().is_none());
+
+        // Accumulating the result keeps it
+        temp.compute_into_add(&mut output, 3, compute);
+        assert_eq!(output, [2, 4, 6]);
+        assert_eq!(&temp.get().unwrap()[..3], &[1, 2, 3]);
+
+        temp.compute_into_scale(&mut output, 3, |_| unreachable!());
+        temp.compute_into_sub(&mut output, 3, |_| unreachable!());
+        temp.compute_into_div(&mut output, 3, |_| unreachable!());
+        assert_eq!(output, [1, 3, 5]);
+
+        let mut output = [0; 3];
+        temp.compute_into(&mut output, |_| unreachable!());
+        assert_eq!(output, [1, 2, 3]);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::etl_error::EtlError;
 use crate::etl_expr::*;
 
 // The declaration of DivExpr
@@ -12,14 +13,25 @@ pub struct DivExpr<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: Wrapp
 
 impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> DivExpr<T, LeftExpr, RightExpr> {
     pub fn new(lhs: LeftExpr, rhs: RightExpr) -> Self {
+        match Self::try_new(lhs, rhs) {
+            Ok(expr) => expr,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    pub fn try_new(lhs: LeftExpr, rhs: RightExpr) -> Result<Self, EtlError> {
         if LeftExpr::DIMENSIONS > 0 && RightExpr::DIMENSIONS > 0 && lhs.size() != rhs.size() {
-            panic!("Cannot divide expressions of different sizes ({} + {})", lhs.size(), rhs.size());
+            return Err(EtlError::ShapeMismatch {
+                op: "division",
+                lhs: expr_dims(&lhs),
+                rhs: expr_dims(&rhs),
+            });
         }
 
-        Self {
+        Ok(Self {
             lhs: lhs.wrap(),
             rhs: rhs.wrap(),
-        }
+        })
     }
 }
 
@@ -81,11 +93,11 @@ impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> E
         }
     }
 
-    fn columns(&self) -> usize {
+    fn dim(&self, i: usize) -> usize {
         if LeftExpr::DIMENSIONS > 0 {
-            self.lhs.value.columns()
+            self.lhs.value.dim(i)
         } else {
-            self.rhs.value.columns()
+            self.rhs.value.dim(i)
         }
     }
 
@@ -93,6 +105,11 @@ impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> E
     fn at(&self, i: usize) -> T {
         self.lhs.value.at(i) / self.rhs.value.at(i)
     }
+
+    #[inline(always)]
+    fn compute_at(&self, i: usize) -> T::Compute {
+        self.lhs.value.compute_at(i) / self.rhs.value.compute_at(i)
+    }
 }
 
 // DivExpr is an EtlWrappable
@@ -125,8 +142,8 @@ impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> E
 
 #[macro_export]
 macro_rules! impl_div_op_value {
-    ($type:ty) => {
-        impl<'a, T: EtlValueType, RightExpr: WrappableExpr<T>> std::ops::Div<RightExpr> for &'a $type {
+    ($type:ty $(, const $dim:ident)*) => {
+        impl<'a, T: EtlValueType, $(const $dim: usize,)* RightExpr: WrappableExpr<T>> std::ops::Div<RightExpr> for &'a $type {
             type Output = $crate::div_expr::DivExpr<T, &'a $type, RightExpr>;
 
             fn div(self, other: RightExpr) -> Self::Output {
@@ -134,10 +151,11 @@ macro_rules! impl_div_op_value {
             }
         }
 
-        impl<T: EtlValueType, RightExpr: EtlExpr<T>> std::ops::DivAssign<RightExpr> for $type {
+        impl<T: EtlValueType, $(const $dim: usize,)* RightExpr: EtlExpr<T>> std::ops::DivAssign<RightExpr> for $type {
             fn div_assign(&mut self, other: RightExpr) {
                 validate_assign(self, &other);
-                div_assign_direct(&mut self.data, &other);
+                let size = self.size();
+                div_assign_direct(&mut self.data[..size], &other);
             }
         }
     };
@@ -189,7 +207,7 @@ macro_rules! impl_div_op_binary_expr_simd {
     ($type:ty) => {
         impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>, OuterRightExpr: WrappableExpr<T>> std::ops::Div<OuterRightExpr> for $type
         where
-            Simd<T, 8>: SimdHelper,
+            T: $crate::base_traits::Vectorizable,
         {
             type Output = $crate::div_expr::DivExpr<T, $type, OuterRightExpr>;
 
@@ -226,6 +244,9 @@ macro_rules! impl_div_op_unary_expr_trait {
     };
 }
 
+crate::impl_rank_binary_expr!(DivExpr<T, LeftExpr, RightExpr>);
+crate::impl_fixed_shape_binary_expr!(DivExpr<T, LeftExpr, RightExpr>);
+
 crate::impl_add_op_binary_expr!(DivExpr<T, LeftExpr, RightExpr>);
 crate::impl_div_op_binary_expr!(DivExpr<T, LeftExpr, RightExpr>);
 crate::impl_sub_op_binary_expr!(DivExpr<T, LeftExpr, RightExpr>);
@@ -369,4 +390,18 @@ mod tests {
         assert_eq!(c.at(0), 3);
         assert_eq!(c.at(1), 9);
     }
+
+    #[test]
+    fn unsigned() {
+        // The padding must not be divided by zero
+        let a = Vector::<u8>::new_iota(5, 10);
+        let b = Vector::<u8>::new_iota(5, 1);
+
+        let mut c = Vector::<u8>::new(5);
+        c |= &a / &b;
+
+        assert_eq!(c.at(0), 10);
+        assert_eq!(c.at(1), 5);
+        assert_eq!(c.at(4), 2);
+    }
 }
@@ -0,0 +1,117 @@
+use std::fmt;
+
+// The declaration of EtlError
+
+/// The errors reported by the fallible (try_) versions of the operations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EtlError {
+    /// The dimensions of the two sides of an operation are not compatible
+    ShapeMismatch { op: &'static str, lhs: Vec<usize>, rhs: Vec<usize> },
+    /// An expression does not have the number of dimensions required by the operation
+    RankMismatch { op: &'static str, expected: usize, actual: usize },
+    /// An index is outside of the dimensions of an expression
+    OutOfBounds { index: Vec<usize>, dims: Vec<usize> },
+    /// The operation cannot be computed on an empty expression
+    EmptyInput { op: &'static str },
+    /// A file or a stream could not be read or written
+    Io { message: String },
+    /// The content of a file does not follow its format, or uses an unsupported feature of it
+    InvalidFormat { format: &'static str, message: String },
+    /// A value of a text input cannot be parsed, line and column start at 1
+    Parse { line: usize, column: usize, message: String },
+}
+
+fn fmt_dims(f: &mut fmt::Formatter<'_>, dims: &[usize]) -> fmt::Result {
+    write!(f, "[")?;
+
+    for (i, dim) in dims.iter().enumerate() {
+        if i > 0 {
+            write!(f, ",")?;
+        }
+        write!(f, "{dim}")?;
+    }
+
+    write!(f, "]")
+}
+
+impl fmt::Display for EtlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EtlError::ShapeMismatch { op, lhs, rhs } => {
+                write!(f, "Invalid dimensions for {op} (")?;
+                fmt_dims(f, lhs)?;
+                write!(f, " and ")?;
+                fmt_dims(f, rhs)?;
+                write!(f, ")")
+            }
+            EtlError::RankMismatch { op, expected, actual } => write!(f, "Invalid number of dimensions for {op} ({actual}D instead of {expected}D)"),
+            EtlError::OutOfBounds { index, dims } => {
+                write!(f, "Index ")?;
+                fmt_dims(f, index)?;
+                write!(f, " is out of bounds of ")?;
+                fmt_dims(f, dims)
+            }
+            EtlError::EmptyInput { op } => write!(f, "Cannot compute {op} of empty collection"),
+            EtlError::Io { message } => write!(f, "I/O error: {message}"),
+            EtlError::InvalidFormat { format, message } => write!(f, "Invalid {format} data: {message}"),
+            EtlError::Parse { line, column, message } => write!(f, "Parse error at line {line}, column {column}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for EtlError {}
+
+impl From<std::io::Error> for EtlError {
+    fn from(error: std::io::Error) -> Self {
+        EtlError::Io { message: error.to_string() }
+    }
+}
+
+// The tests
+
+#[cfg(test)]
+mod tests {
+    use crate::etl_error::EtlError;
+
+    #[test]
+    fn display() {
+        let error = EtlError::ShapeMismatch {
+            op: "assignment",
+            lhs: vec![2, 3],
+            rhs: vec![4],
+        };
+        assert_eq!(format!("{error}"), "Invalid dimensions for assignment ([2,3] and [4])");
+
+        let error = EtlError::RankMismatch {
+            op: "argmax",
+            expected: 2,
+            actual: 1,
+        };
+        assert_eq!(format!("{error}"), "Invalid number of dimensions for argmax (1D instead of 2D)");
+
+        let error = EtlError::OutOfBounds {
+            index: vec![2, 0],
+            dims: vec![2, 2],
+        };
+        assert_eq!(format!("{error}"), "Index [2,0] is out of bounds of [2,2]");
+
+        let error = EtlError::EmptyInput { op: "mean" };
+        assert_eq!(format!("{error}"), "Cannot compute mean of empty collection");
+
+        let error = EtlError::from(std::io::Error::new(std::io::ErrorKind::NotFound, "no such file"));
+        assert_eq!(format!("{error}"), "I/O error: no such file");
+
+        let error = EtlError::InvalidFormat {
+            format: "npy",
+            message: "bad magic".to_string(),
+        };
+        assert_eq!(format!("{error}"), "Invalid npy data: bad magic");
+
+        let error = EtlError::Parse {
+            line: 3,
+            column: 2,
+            message: "invalid value x".to_string(),
+        };
+        assert_eq!(format!("{error}"), "Parse error at line 3, column 2: invalid value x");
+    }
+}
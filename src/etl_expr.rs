@@ -1,7 +1,9 @@
 use rayon;
 use std::ops::*;
 
-use crate::base_traits::Constants;
+use crate::aligned_buffer::AlignedBuffer;
+use crate::base_traits::{Constants, Vectorizable};
+use crate::etl_error::EtlError;
 
 pub fn padded_size(size: usize) -> usize {
     (size + 7) & !7
@@ -13,7 +15,6 @@ pub trait EtlValueType:
     + Clone
     + Copy
     + PartialOrd
-    + Neg<Output = Self>
     + Add<Output = Self>
     + AddAssign
     + Sub<Output = Self>
@@ -25,7 +26,7 @@ pub trait EtlValueType:
     + std::marker::Sync
     + std::marker::Send
     + std::fmt::Display
-    + std::simd::SimdElement
+    + Vectorizable
 {
 }
 
@@ -35,7 +36,6 @@ impl<
             + Clone
             + Copy
             + PartialOrd
-            + Neg<Output = T>
             + Add<Output = T>
             + AddAssign<T>
             + Sub<Output = T>
@@ -47,7 +47,7 @@ impl<
             + std::marker::Sync
             + std::marker::Send
             + std::fmt::Display
-            + std::simd::SimdElement,
+            + Vectorizable,
     > EtlValueType for T
 {
 }
@@ -62,13 +62,10 @@ pub enum EtlType {
 
 // Since PartialEq is not const fn (yet), we must declare a const fn comparison
 pub const fn is_same_type(lhs_type: EtlType, rhs_type: EtlType) -> bool {
-    match (lhs_type, rhs_type) {
-        (EtlType::Simple, EtlType::Simple) => true,
-        (EtlType::Unaligned, EtlType::Unaligned) => true,
-        (EtlType::Smart, EtlType::Smart) => true,
-        (EtlType::Value, EtlType::Value) => true,
-        _ => false,
-    }
+    matches!(
+        (lhs_type, rhs_type),
+        (EtlType::Simple, EtlType::Simple) | (EtlType::Unaligned, EtlType::Unaligned) | (EtlType::Smart, EtlType::Smart) | (EtlType::Value, EtlType::Value)
+    )
 }
 
 pub const fn simple_unary_type(etl_type: EtlType) -> EtlType {
@@ -98,6 +95,10 @@ pub trait EtlExpr<T: EtlValueType>: std::marker::Sync + Clone {
     const TYPE: EtlType;
     const THREAD_SAFE: bool;
 
+    /// Indicates that the expression is a transposed view of a 2D expression, whose data (not
+    /// transposed) is available with get_transposed_data
+    const TRANSPOSED: bool = false;
+
     type Iter<'x>: Iterator<Item = T>
     where
         T: 'x,
@@ -116,69 +117,177 @@ pub trait EtlExpr<T: EtlValueType>: std::marker::Sync + Clone {
     /// This works for all dimensions and consider a flat structure
     fn at(&self, i: usize) -> T;
 
+    /// Return the element at position `i` in the compute type of T (see Vectorizable::Compute)
+    ///
+    /// The element-wise expressions compute their operands in this type, so that the reduced
+    /// precision values are only rounded once, when the result is stored
+    fn compute_at(&self, i: usize) -> T::Compute {
+        self.at(i).to_compute()
+    }
+
+    /// Return the i-th dimension of the expression
+    ///
+    /// Only the first dimension is implicitly defined, expressions with more than one dimension
+    /// must implement it
     fn dim(&self, i: usize) -> usize {
-        match i {
-            0 => {
-                assert!(Self::DIMENSIONS >= 1 && Self::DIMENSIONS <= 2, "dim is only implicitly defined for 1D/2D EtlExpr");
-                self.rows()
-            }
-            1 => {
-                assert!(Self::DIMENSIONS == 2, "dim is only implicitly defined for 1D/2D EtlExpr");
-                self.columns()
-            }
-            _ => panic!("dim is only implicitly define for dim(0) and dim(1)"),
-        }
+        assert!(i == 0, "dim is only implicitly defined for dim(0)");
+        self.rows()
     }
 
     fn rows(&self) -> usize;
 
-    // TODO I should find a solution to prevent this at compile-time (without combinatorial explosion)
-
-    fn columns(&self) -> usize {
-        panic!("This function is only implemented for 2D containers");
+    fn compute_into(&self, _lhs: &mut [T]) {
+        panic!("This function is only implemented for smart expression");
     }
 
-    fn at2(&self, _row: usize, _column: usize) -> T {
-        panic!("This function is only implemented for 2D containers");
+    fn compute_into_add(&self, _lhs: &mut [T]) {
+        panic!("This function is only implemented for smart expression");
     }
 
-    fn at3(&self, _i1: usize, _i2: usize, _i3: usize) -> T {
-        panic!("This function is only implemented for 3D containers");
+    fn compute_into_sub(&self, _lhs: &mut [T]) {
+        panic!("This function is only implemented for smart expression");
     }
 
-    fn at4(&self, _i1: usize, _i2: usize, _i3: usize, _i4: usize) -> T {
-        panic!("This function is only implemented for 4D containers");
+    fn compute_into_scale(&self, _lhs: &mut [T]) {
+        panic!("This function is only implemented for smart expression");
     }
 
-    fn compute_into(&self, _lhs: &mut Vec<T>) {
+    fn compute_into_div(&self, _lhs: &mut [T]) {
         panic!("This function is only implemented for smart expression");
     }
 
-    fn compute_into_add(&self, _lhs: &mut Vec<T>) {
-        panic!("This function is only implemented for smart expression");
+    fn validate_assign<LeftExpr: EtlExpr<T>>(&self, lhs: &LeftExpr) {
+        if let Err(error) = self.try_validate_assign(lhs) {
+            panic!("{error}");
+        }
     }
 
-    fn compute_into_sub(&self, _lhs: &mut Vec<T>) {
+    fn try_validate_assign<LeftExpr: EtlExpr<T>>(&self, _lhs: &LeftExpr) -> Result<(), EtlError> {
         panic!("This function is only implemented for smart expression");
     }
 
-    fn compute_into_scale(&self, _lhs: &mut Vec<T>) {
-        panic!("This function is only implemented for smart expression");
+    fn get_data(&self) -> &[T] {
+        panic!("This function is only implemented for direct expression");
     }
 
-    fn compute_into_div(&self, _lhs: &mut Vec<T>) {
-        panic!("This function is only implemented for smart expression");
+    fn get_transposed_data(&self) -> &[T] {
+        panic!("This function is only implemented for transposed expression");
     }
+}
 
-    fn validate_assign<LeftExpr: EtlExpr<T>>(&self, _lhs: &LeftExpr) {
-        panic!("This function is only implemented for smart expression");
+// The accessors that only make sense for some number of dimensions are in separate traits, only
+// implemented by the expressions of the matching rank, so that using them on an expression of
+// another rank does not compile
+
+/// The accessors of 2D expressions
+pub trait Etl2d<T: EtlValueType>: EtlExpr<T> {
+    fn columns(&self) -> usize {
+        self.dim(1)
     }
 
-    fn get_data(&self) -> &[T] {
-        panic!("This function is only implemented for direct expression");
+    fn at2(&self, row: usize, column: usize) -> T {
+        self.at(row * self.columns() + column)
+    }
+}
+
+/// The accessors of 3D expressions
+pub trait Etl3d<T: EtlValueType>: EtlExpr<T> {
+    fn at3(&self, i1: usize, i2: usize, i3: usize) -> T {
+        self.at((i1 * self.dim(1) + i2) * self.dim(2) + i3)
+    }
+}
+
+/// The accessors of 4D expressions
+pub trait Etl4d<T: EtlValueType>: EtlExpr<T> {
+    fn at4(&self, i1: usize, i2: usize, i3: usize, i4: usize) -> T {
+        self.at(((i1 * self.dim(1) + i2) * self.dim(2) + i3) * self.dim(3) + i4)
     }
 }
 
+// Unfortunately, the rank of an expression cannot be matched on DIMENSIONS (associated const
+// equality is not stable), so expressions forward the rank traits of their operands
+
+#[macro_export]
+macro_rules! impl_rank_unary_expr {
+    ($type:ty) => {
+        impl<T: EtlValueType, Expr: WrappableExpr<T> + $crate::etl_expr::Etl2d<T>> $crate::etl_expr::Etl2d<T> for $type {}
+        impl<T: EtlValueType, Expr: WrappableExpr<T> + $crate::etl_expr::Etl3d<T>> $crate::etl_expr::Etl3d<T> for $type {}
+        impl<T: EtlValueType, Expr: WrappableExpr<T> + $crate::etl_expr::Etl4d<T>> $crate::etl_expr::Etl4d<T> for $type {}
+    };
+}
+
+#[macro_export]
+macro_rules! impl_rank_unary_expr_trait {
+    ($trait:tt, $type:ty) => {
+        impl<T: EtlValueType + $trait, Expr: WrappableExpr<T> + $crate::etl_expr::Etl2d<T>> $crate::etl_expr::Etl2d<T> for $type {}
+        impl<T: EtlValueType + $trait, Expr: WrappableExpr<T> + $crate::etl_expr::Etl3d<T>> $crate::etl_expr::Etl3d<T> for $type {}
+        impl<T: EtlValueType + $trait, Expr: WrappableExpr<T> + $crate::etl_expr::Etl4d<T>> $crate::etl_expr::Etl4d<T> for $type {}
+    };
+}
+
+// Binary element-wise expressions take the rank of their left operand (a second implementation for
+// a scalar left operand would conflict with this one)
+
+#[macro_export]
+macro_rules! impl_rank_binary_expr {
+    ($type:ty) => {
+        impl<T: EtlValueType, LeftExpr: WrappableExpr<T> + $crate::etl_expr::Etl2d<T>, RightExpr: WrappableExpr<T>> $crate::etl_expr::Etl2d<T> for $type {}
+        impl<T: EtlValueType, LeftExpr: WrappableExpr<T> + $crate::etl_expr::Etl3d<T>, RightExpr: WrappableExpr<T>> $crate::etl_expr::Etl3d<T> for $type {}
+        impl<T: EtlValueType, LeftExpr: WrappableExpr<T> + $crate::etl_expr::Etl4d<T>, RightExpr: WrappableExpr<T>> $crate::etl_expr::Etl4d<T> for $type {}
+    };
+}
+
+/// Marks the expressions whose shape S is known at compile-time
+///
+/// S is [(); N] for vectors and [[(); C]; R] for matrices. Only the expressions of the same fixed
+/// shape can be assigned to a fixed container. Scalars have every shape.
+pub trait FixedShape<S> {}
+
+// Element-wise expressions have the fixed shape of their operands
+
+#[macro_export]
+macro_rules! impl_fixed_shape_unary_expr_trait {
+    ($trait:tt, $type:ty) => {
+        impl<T: EtlValueType + $trait, S, Expr: WrappableExpr<T> + $crate::etl_expr::FixedShape<S>> $crate::etl_expr::FixedShape<S> for $type {}
+    };
+}
+
+#[macro_export]
+macro_rules! impl_fixed_shape_binary_expr {
+    ($type:ty) => {
+        impl<T: EtlValueType, S, LeftExpr: WrappableExpr<T> + $crate::etl_expr::FixedShape<S>, RightExpr: WrappableExpr<T> + $crate::etl_expr::FixedShape<S>>
+            $crate::etl_expr::FixedShape<S> for $type
+        {
+        }
+    };
+}
+
+// The operator macros of each operation take the value type of the sub expression, which is not
+// the value type of the expressions that convert it (e.g. cast or real), so their operators are
+// implemented with this macro, given the generics of the expression and its value type
+
+#[macro_export]
+macro_rules! impl_op_converting_expr {
+    ([$($generics:tt)*] $type:ty, $value:ty $(where $($bounds:tt)*)?) => {
+        $crate::impl_op_converting_expr!(@op Add, add, add_expr::AddExpr, [$($generics)*] $type, $value $(where $($bounds)*)?);
+        $crate::impl_op_converting_expr!(@op Sub, sub, sub_expr::SubExpr, [$($generics)*] $type, $value $(where $($bounds)*)?);
+        $crate::impl_op_converting_expr!(@op Mul, mul, mul_expr::MulExpr, [$($generics)*] $type, $value $(where $($bounds)*)?);
+        $crate::impl_op_converting_expr!(@op Div, div, div_expr::DivExpr, [$($generics)*] $type, $value $(where $($bounds)*)?);
+        $crate::impl_op_converting_expr!(@op Shr, shr, scale_expr::ScaleExpr, [$($generics)*] $type, $value $(where $($bounds)*)?);
+    };
+    (@op $trait:ident, $func:ident, $module:ident::$expr:ident, [$($generics:tt)*] $type:ty, $value:ty $(where $($bounds:tt)*)?) => {
+        impl<$($generics)*, OuterRightExpr: WrappableExpr<$value>> std::ops::$trait<OuterRightExpr> for $type
+        $(where $($bounds)*)?
+        {
+            type Output = $crate::$module::$expr<$value, $type, OuterRightExpr>;
+
+            fn $func(self, other: OuterRightExpr) -> Self::Output {
+                Self::Output::new(self, other)
+            }
+        }
+    };
+}
+
 // It does not seem like I can force Index trait because it must return a reference which
 // expressions cannot do. Therefore, I settled on at instead, which should work fine
 // TODO: See if there is any way to remove the phantom data here
@@ -202,17 +311,34 @@ impl<T: EtlValueType, TT: EtlExpr<T> + EtlWrappable<T> + EtlComputable<T>> Wrapp
 
 // Assignment functions, probably should be moved elsewhere
 
+/// The dimensions of an expression, used to report errors
+pub fn expr_dims<T: EtlValueType, Expr: EtlExpr<T>>(expr: &Expr) -> Vec<usize> {
+    (0..Expr::DIMENSIONS).map(|i| expr.dim(i)).collect()
+}
+
 pub fn validate_assign<T: EtlValueType, LeftExpr: EtlExpr<T>, RightExpr: EtlExpr<T>>(lhs: &LeftExpr, rhs: &RightExpr) {
+    if let Err(error) = try_validate_assign(lhs, rhs) {
+        panic!("{error}");
+    }
+}
+
+pub fn try_validate_assign<T: EtlValueType, LeftExpr: EtlExpr<T>, RightExpr: EtlExpr<T>>(lhs: &LeftExpr, rhs: &RightExpr) -> Result<(), EtlError> {
     if RightExpr::DIMENSIONS == 0 {
-        return;
+        return Ok(());
     }
 
     if RightExpr::TYPE == EtlType::Value || RightExpr::TYPE == EtlType::Simple || RightExpr::TYPE == EtlType::Unaligned {
         if lhs.size() != rhs.size() {
-            panic!("Incompatible assignment ([{}] = [{}])", lhs.size(), rhs.size());
+            return Err(EtlError::ShapeMismatch {
+                op: "assignment",
+                lhs: expr_dims(lhs),
+                rhs: expr_dims(rhs),
+            });
         }
+
+        Ok(())
     } else if RightExpr::TYPE == EtlType::Smart {
-        rhs.validate_assign(lhs);
+        rhs.try_validate_assign(lhs)
     } else {
         panic!("Unhandled EtlType");
     }
@@ -228,21 +354,71 @@ pub fn validate_assign<T: EtlValueType, LeftExpr: EtlExpr<T>, RightExpr: EtlExpr
 // So, we must use a rather high threshold
 const PARALLEL_THRESHOLD: usize = 256 * 1024;
 
-pub fn assign_direct<T: EtlValueType, RightExpr: EtlExpr<T>>(data: &mut Vec<T>, rhs: &RightExpr) {
+// The reduced precision types evaluate the expression in their compute type, each value is only
+// rounded once, when op stores it into data
+fn converted_direct<T: EtlValueType, RightExpr: EtlExpr<T>, F: Fn(T::Compute, T::Compute) -> T::Compute + Copy + Send + Sync>(
+    data: &mut [T],
+    rhs: &RightExpr,
+    op: F,
+) {
+    let size = data.len();
+
+    if RightExpr::THREAD_SAFE && size > PARALLEL_THRESHOLD {
+        rayon::scope(|s| {
+            let n = rayon::current_num_threads();
+            let block_size = size / n;
+
+            let ptr = data.as_mut_ptr();
+
+            for t in 0..n {
+                let start = t * block_size;
+                let end = if t < n - 1 { (t + 1) * block_size } else { size };
+
+                let slice = unsafe { std::slice::from_raw_parts_mut(ptr.add(start), end - start) };
+
+                s.spawn(move |_| {
+                    for (i, x) in slice.iter_mut().enumerate() {
+                        *x = T::from_compute(op(x.to_compute(), rhs.compute_at(start + i)));
+                    }
+                });
+            }
+        });
+    } else {
+        for (i, x) in data.iter_mut().enumerate() {
+            *x = T::from_compute(op(x.to_compute(), rhs.compute_at(i)));
+        }
+    }
+}
+
+// The number of values computed by the direct assignments. The padding of the containers is never
+// computed, it could overflow (e.g. unsigned subtraction)
+fn assigned_size<T: EtlValueType, RightExpr: EtlExpr<T>>(data: &[T], rhs: &RightExpr) -> usize {
+    if RightExpr::DIMENSIONS == 0 { data.len() } else { rhs.size().min(data.len()) }
+}
+
+/// Fill data with value, value + 1, value + 2, ...
+pub fn iota_direct<T: EtlValueType>(data: &mut [T], value: T) {
+    let mut acc = value;
+    for (i, value) in data.iter_mut().enumerate() {
+        // Only step between the values, the step after the last one could overflow
+        if i > 0 {
+            acc += T::one();
+        }
+        *value = acc;
+    }
+}
+
+pub fn assign_direct<T: EtlValueType, RightExpr: EtlExpr<T>>(data: &mut [T], rhs: &RightExpr) {
     // TODO Ideally, a RightExpr::TYPE = Value should be a simple memcpy
 
     if RightExpr::TYPE == EtlType::Smart {
         rhs.compute_into(data);
     } else {
-        let size = if RightExpr::DIMENSIONS == 0 {
-            data.len()
-        } else if RightExpr::TYPE != EtlType::Unaligned {
-            padded_size(rhs.size())
-        } else {
-            rhs.size()
-        };
+        let size = assigned_size(data, rhs);
 
-        if RightExpr::THREAD_SAFE && size > PARALLEL_THRESHOLD {
+        if T::CONVERTED {
+            converted_direct(&mut data[..size], rhs, |_, rhs| rhs);
+        } else if RightExpr::THREAD_SAFE && size > PARALLEL_THRESHOLD {
             rayon::scope(|s| {
                 let n = rayon::current_num_threads();
                 let block_size = size / n;
@@ -274,19 +450,15 @@ pub fn assign_direct<T: EtlValueType, RightExpr: EtlExpr<T>>(data: &mut Vec<T>,
     }
 }
 
-pub fn add_assign_direct<T: EtlValueType, RightExpr: EtlExpr<T>>(data: &mut Vec<T>, rhs: &RightExpr) {
+pub fn add_assign_direct<T: EtlValueType, RightExpr: EtlExpr<T>>(data: &mut [T], rhs: &RightExpr) {
     if RightExpr::TYPE == EtlType::Smart {
         rhs.compute_into_add(data);
     } else {
-        let size = if RightExpr::DIMENSIONS == 0 {
-            data.len()
-        } else if RightExpr::TYPE != EtlType::Unaligned {
-            padded_size(rhs.size())
-        } else {
-            rhs.size()
-        };
+        let size = assigned_size(data, rhs);
 
-        if RightExpr::THREAD_SAFE && size > PARALLEL_THRESHOLD {
+        if T::CONVERTED {
+            converted_direct(&mut data[..size], rhs, |lhs, rhs| lhs + rhs);
+        } else if RightExpr::THREAD_SAFE && size > PARALLEL_THRESHOLD {
             rayon::scope(|s| {
                 let n = rayon::current_num_threads();
                 let block_size = size / n;
@@ -314,19 +486,15 @@ pub fn add_assign_direct<T: EtlValueType, RightExpr: EtlExpr<T>>(data: &mut Vec<
     }
 }
 
-pub fn sub_assign_direct<T: EtlValueType, RightExpr: EtlExpr<T>>(data: &mut Vec<T>, rhs: &RightExpr) {
+pub fn sub_assign_direct<T: EtlValueType, RightExpr: EtlExpr<T>>(data: &mut [T], rhs: &RightExpr) {
     if RightExpr::TYPE == EtlType::Smart {
         rhs.compute_into_sub(data);
     } else {
-        let size = if RightExpr::DIMENSIONS == 0 {
-            data.len()
-        } else if RightExpr::TYPE != EtlType::Unaligned {
-            padded_size(rhs.size())
-        } else {
-            rhs.size()
-        };
+        let size = assigned_size(data, rhs);
 
-        if RightExpr::THREAD_SAFE && size > PARALLEL_THRESHOLD {
+        if T::CONVERTED {
+            converted_direct(&mut data[..size], rhs, |lhs, rhs| lhs - rhs);
+        } else if RightExpr::THREAD_SAFE && size > PARALLEL_THRESHOLD {
             rayon::scope(|s| {
                 let n = rayon::current_num_threads();
                 let block_size = size / n;
@@ -354,19 +522,15 @@ pub fn sub_assign_direct<T: EtlValueType, RightExpr: EtlExpr<T>>(data: &mut Vec<
     }
 }
 
-pub fn div_assign_direct<T: EtlValueType, RightExpr: EtlExpr<T>>(data: &mut Vec<T>, rhs: &RightExpr) {
+pub fn div_assign_direct<T: EtlValueType, RightExpr: EtlExpr<T>>(data: &mut [T], rhs: &RightExpr) {
     if RightExpr::TYPE == EtlType::Smart {
         rhs.compute_into_div(data);
     } else {
-        let size = if RightExpr::DIMENSIONS == 0 {
-            data.len()
-        } else if RightExpr::TYPE != EtlType::Unaligned {
-            padded_size(rhs.size())
-        } else {
-            rhs.size()
-        };
+        let size = assigned_size(data, rhs);
 
-        if RightExpr::THREAD_SAFE && size > PARALLEL_THRESHOLD {
+        if T::CONVERTED {
+            converted_direct(&mut data[..size], rhs, |lhs, rhs| lhs / rhs);
+        } else if RightExpr::THREAD_SAFE && size > PARALLEL_THRESHOLD {
             rayon::scope(|s| {
                 let n = rayon::current_num_threads();
                 let block_size = size / n;
@@ -394,19 +558,15 @@ pub fn div_assign_direct<T: EtlValueType, RightExpr: EtlExpr<T>>(data: &mut Vec<
     }
 }
 
-pub fn scale_assign_direct<T: EtlValueType, RightExpr: EtlExpr<T>>(data: &mut Vec<T>, rhs: &RightExpr) {
+pub fn scale_assign_direct<T: EtlValueType, RightExpr: EtlExpr<T>>(data: &mut [T], rhs: &RightExpr) {
     if RightExpr::TYPE == EtlType::Smart {
         rhs.compute_into_scale(data);
     } else {
-        let size = if RightExpr::DIMENSIONS == 0 {
-            data.len()
-        } else if RightExpr::TYPE != EtlType::Unaligned {
-            padded_size(rhs.size())
-        } else {
-            rhs.size()
-        };
+        let size = assigned_size(data, rhs);
 
-        if RightExpr::THREAD_SAFE && size > PARALLEL_THRESHOLD {
+        if T::CONVERTED {
+            converted_direct(&mut data[..size], rhs, |lhs, rhs| lhs * rhs);
+        } else if RightExpr::THREAD_SAFE && size > PARALLEL_THRESHOLD {
             rayon::scope(|s| {
                 let n = rayon::current_num_threads();
                 let block_size = size / n;
@@ -434,16 +594,13 @@ pub fn scale_assign_direct<T: EtlValueType, RightExpr: EtlExpr<T>>(data: &mut Ve
     }
 }
 
-pub fn axpy_direct<T: EtlValueType, RightExpr: EtlExpr<T>>(x: &mut Vec<T>, alpha: T, beta: T, y: &RightExpr) {
-    let size = if RightExpr::DIMENSIONS == 0 {
-        x.len()
-    } else if RightExpr::TYPE != EtlType::Unaligned {
-        padded_size(y.size())
-    } else {
-        y.size()
-    };
+pub fn axpy_direct<T: EtlValueType, RightExpr: EtlExpr<T>>(x: &mut [T], alpha: T, beta: T, y: &RightExpr) {
+    let size = assigned_size(x, y);
 
-    if RightExpr::THREAD_SAFE && size > PARALLEL_THRESHOLD {
+    if T::CONVERTED {
+        let (alpha, beta) = (alpha.to_compute(), beta.to_compute());
+        converted_direct(&mut x[..size], y, move |x, y| alpha * x + beta * y);
+    } else if RightExpr::THREAD_SAFE && size > PARALLEL_THRESHOLD {
         rayon::scope(|s| {
             let n = rayon::current_num_threads();
             let block_size = size / n;
@@ -477,7 +634,7 @@ pub fn axpy_direct<T: EtlValueType, RightExpr: EtlExpr<T>>(x: &mut Vec<T>, alpha
 // Parallel dispatchers
 
 pub fn dispatch_parallel_2d<T: EtlValueType, F: Fn(&mut [T], usize, usize) + Sync + Send + Clone>(
-    data: &mut Vec<T>,
+    data: &mut [T],
     size: usize,
     helper: bool,
     mul: usize,
@@ -541,6 +698,69 @@ pub fn dispatch_parallel_block<T: EtlValueType, F: Fn(&mut [T], usize, usize) +
 
 // Helpers
 
+/// Evaluate an expression into a scratch buffer borrowed from the workspace
+pub fn workspace_data<T: EtlValueType, Expr: EtlExpr<T>>(expr: &Expr) -> AlignedBuffer<T> {
+    let mut data = AlignedBuffer::from_workspace(padded_size(expr.size()));
+    assign_direct(&mut data, expr);
+    data
+}
+
+/// The values of an evaluated expression, which does not borrow its operands anymore
+pub struct Evaluated<T: EtlValueType> {
+    dims: Vec<usize>,
+    data: AlignedBuffer<T>,
+}
+
+/// Evaluate an expression into a scratch buffer borrowed from the workspace
+///
+/// This is how an expression that reads its destination is assigned, e.g. for `x = b * x + z`:
+///
+/// ```
+/// use etl::etl_expr::{EtlExpr, evaluate};
+/// use etl::vector::Vector;
+///
+/// let mut x = Vector::<i64>::new_iota(3, 1);
+/// let b = Vector::<i64>::new_iota(3, 2);
+/// let z = Vector::<i64>::new_iota(3, 3);
+/// x.assign_with(|x| evaluate((&b >> x) + &z));
+/// assert_eq!(x.at(2), 17);
+/// ```
+///
+/// The container is only borrowed by the closure, it cannot be kept while it is written:
+///
+/// ```compile_fail
+/// use etl::etl_expr::evaluate;
+/// use etl::vector::Vector;
+///
+/// let mut x = Vector::<i64>::new(3);
+/// let mut leak = None;
+/// x.assign_with(|a| {
+///     leak = Some(a);
+///     evaluate(a + a)
+/// });
+/// ```
+pub fn evaluate<T: EtlValueType, Expr: EtlExpr<T>>(expr: Expr) -> Evaluated<T> {
+    Evaluated {
+        dims: expr_dims(&expr),
+        data: workspace_data(&expr),
+    }
+}
+
+// The assign_with of the containers: the closure only borrows the container while it evaluates the
+// expression, so the container can be written once it returns
+pub(crate) fn assign_evaluated<T: EtlValueType>(dims: Vec<usize>, data: &mut [T], rhs: Evaluated<T>) {
+    if data.len() != rhs.dims.iter().product::<usize>() {
+        let error = EtlError::ShapeMismatch {
+            op: "assignment",
+            lhs: dims,
+            rhs: rhs.dims,
+        };
+        panic!("{error}");
+    }
+
+    data.copy_from_slice(&rhs.data[..data.len()]);
+}
+
 pub fn forward_data_binary<T: EtlValueType, F: Fn(&mut [T], &[T], &[T]), LeftExpr: EtlComputable<T> + EtlExpr<T>, RightExpr: EtlComputable<T> + EtlExpr<T>>(
     output: &mut [T],
     lhs: &LeftExpr,
@@ -550,16 +770,16 @@ pub fn forward_data_binary<T: EtlValueType, F: Fn(&mut [T], &[T], &[T]), LeftExp
     if LeftExpr::TYPE.direct() && RightExpr::TYPE.direct() {
         functor(output, lhs.get_data(), rhs.get_data());
     } else if LeftExpr::TYPE.direct() && !RightExpr::TYPE.direct() {
-        let rhs_data = rhs.to_data();
+        let rhs_data = workspace_data(rhs);
 
         functor(output, lhs.get_data(), &rhs_data);
     } else if !LeftExpr::TYPE.direct() && RightExpr::TYPE.direct() {
-        let lhs_data = lhs.to_data();
+        let lhs_data = workspace_data(lhs);
 
         functor(output, &lhs_data, rhs.get_data());
     } else {
-        let lhs_data = lhs.to_data();
-        let rhs_data = rhs.to_data();
+        let lhs_data = workspace_data(lhs);
+        let rhs_data = workspace_data(rhs);
 
         functor(output, &lhs_data, &rhs_data);
     }
@@ -579,16 +799,16 @@ pub fn forward_data_binary_mut<
     if LeftExpr::TYPE.direct() && RightExpr::TYPE.direct() {
         functor(output, lhs.get_data(), rhs.get_data());
     } else if LeftExpr::TYPE.direct() && !RightExpr::TYPE.direct() {
-        let rhs_data = rhs.to_data();
+        let rhs_data = workspace_data(rhs);
 
         functor(output, lhs.get_data(), &rhs_data);
     } else if !LeftExpr::TYPE.direct() && RightExpr::TYPE.direct() {
-        let lhs_data = lhs.to_data();
+        let lhs_data = workspace_data(lhs);
 
         functor(output, &lhs_data, rhs.get_data());
     } else {
-        let lhs_data = lhs.to_data();
-        let rhs_data = rhs.to_data();
+        let lhs_data = workspace_data(lhs);
+        let rhs_data = workspace_data(rhs);
 
         functor(output, &lhs_data, &rhs_data);
     }
@@ -598,7 +818,7 @@ pub fn forward_data_unary<T: EtlValueType, F: Fn(&mut [T], &[T]), Expr: EtlCompu
     if Expr::TYPE.direct() {
         functor(output, expr.get_data());
     } else {
-        let data = expr.to_data();
+        let data = workspace_data(expr);
         functor(output, &data);
     }
 }
@@ -607,7 +827,7 @@ pub fn forward_data_unary_mut<T: EtlValueType, F: FnMut(&mut [T], &[T]), Expr: E
     if Expr::TYPE.direct() {
         functor(output, expr.get_data());
     } else {
-        let data = expr.to_data();
+        let data = workspace_data(expr);
         functor(output, &data);
     }
 }
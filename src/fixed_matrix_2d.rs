@@ -0,0 +1,441 @@
+use crate::base_traits::Vectorizable;
+use crate::etl_expr::*;
+use crate::fixed_vector::FixedVector;
+use crate::print::{fmt_container, fmt_container_debug};
+
+use std::{fmt, ops::BitOrAssign};
+
+use rand::Rng;
+
+// The declaration of FixedMatrix2d<T, R, C>
+
+/// A row-major matrix whose dimensions are known at compile-time
+///
+/// The storage is an array, so the matrix lives on the stack and is not padded. Like the other
+/// containers, it is aligned to 64 bytes. Element-wise operations between fixed containers only
+/// compile when the dimensions match, and so does the multiplication. Only expressions of the same
+/// dimensions can be assigned.
+///
+/// ```
+/// use etl::fixed_matrix_2d::FixedMatrix2d;
+///
+/// let a = FixedMatrix2d::<f32, 2, 3>::new_iota(1.0);
+/// let b = FixedMatrix2d::<f32, 3, 2>::new_iota(1.0);
+/// let mut c = FixedMatrix2d::<f32, 2, 2>::new();
+/// c |= &a * &b;
+/// c -= &a * &b;
+/// assert_eq!(c[3], 0.0);
+/// ```
+///
+/// ```compile_fail
+/// use etl::fixed_matrix_2d::FixedMatrix2d;
+///
+/// let a = FixedMatrix2d::<f32, 2, 3>::new();
+/// let mut b = FixedMatrix2d::<f32, 3, 2>::new();
+/// b |= &a;
+/// ```
+///
+/// ```compile_fail
+/// use etl::fixed_matrix_2d::FixedMatrix2d;
+///
+/// let a = FixedMatrix2d::<f32, 2, 3>::new();
+/// let b = FixedMatrix2d::<f32, 3, 2>::new();
+/// let mut c = FixedMatrix2d::<f32, 3, 3>::new();
+/// c += &a * &b;
+/// ```
+#[derive(Clone, Copy)]
+#[repr(C, align(64))]
+pub struct FixedMatrix2d<T: EtlValueType, const R: usize, const C: usize> {
+    pub data: [[T; C]; R],
+}
+
+// The functions of FixedMatrix2d<T, R, C>
+
+impl<T: EtlValueType, const R: usize, const C: usize> FixedMatrix2d<T, R, C> {
+    pub fn new() -> Self {
+        Self { data: [[T::default(); C]; R] }
+    }
+
+    pub fn from_rows(data: [[T; C]; R]) -> Self {
+        Self { data }
+    }
+
+    pub fn new_from_expr<Expr: EtlExpr<T>>(expr: Expr) -> Self {
+        assert_eq!(Expr::DIMENSIONS, 2);
+        assert_eq!(expr.rows(), R);
+        assert_eq!(expr.dim(1), C);
+
+        let mut mat = Self::new();
+
+        assign_direct(mat.data.as_flattened_mut(), &expr);
+
+        mat
+    }
+
+    pub fn new_iota(value: T) -> Self {
+        let mut mat = Self::new();
+        mat.iota_fill(value);
+        mat
+    }
+
+    pub fn at_mut(&mut self, row: usize, column: usize) -> &mut T {
+        &mut self.data[row][column]
+    }
+
+    pub fn clear(&mut self) {
+        self.data.as_flattened_mut().fill(T::default());
+    }
+
+    pub fn fill(&mut self, value: T) {
+        self.data.as_flattened_mut().fill(value);
+    }
+
+    pub fn iota_fill(&mut self, value: T) {
+        iota_direct(self.data.as_flattened_mut(), value);
+    }
+
+    pub fn rand_fill(&mut self)
+    where
+        rand::distr::StandardUniform: rand::distr::Distribution<T>,
+    {
+        let mut rng = rand::rng();
+
+        for value in self.data.as_flattened_mut().iter_mut() {
+            *value = rng.random::<T>();
+        }
+    }
+
+    pub fn inplace_axpy<RightExpr: EtlExpr<T>>(&mut self, alpha: T, beta: T, y: RightExpr) {
+        validate_assign(self, &y);
+        axpy_direct(self.data.as_flattened_mut(), alpha, beta, &y);
+    }
+
+    fn data_mut(&mut self) -> &mut [T] {
+        self.data.as_flattened_mut()
+    }
+}
+
+impl<T: EtlValueType, const R: usize, const C: usize> Default for FixedMatrix2d<T, R, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: EtlValueType, const R: usize, const C: usize> EtlExpr<T> for FixedMatrix2d<T, R, C> {
+    const DIMENSIONS: usize = 2;
+    const TYPE: EtlType = EtlType::Value;
+    const THREAD_SAFE: bool = true;
+
+    type Iter<'x>
+        = std::iter::Cloned<std::slice::Iter<'x, T>>
+    where
+        T: 'x,
+        Self: 'x;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.data.as_flattened().iter().cloned()
+    }
+
+    fn iter_range(&self, range: std::ops::Range<usize>) -> Self::Iter<'_> {
+        self.data.as_flattened()[range].iter().cloned()
+    }
+
+    fn size(&self) -> usize {
+        R * C
+    }
+
+    fn rows(&self) -> usize {
+        R
+    }
+
+    fn dim(&self, i: usize) -> usize {
+        match i {
+            0 => R,
+            1 => C,
+            _ => panic!("Invalid dimension access"),
+        }
+    }
+
+    #[inline(always)]
+    fn at(&self, i: usize) -> T {
+        self.data.as_flattened()[i]
+    }
+
+    fn get_data(&self) -> &[T] {
+        self.data.as_flattened()
+    }
+}
+
+impl<T: EtlValueType, const R: usize, const C: usize> Etl2d<T> for FixedMatrix2d<T, R, C> {
+    fn columns(&self) -> usize {
+        C
+    }
+
+    fn at2(&self, row: usize, column: usize) -> T {
+        self.data[row][column]
+    }
+}
+
+impl<T: EtlValueType, const R: usize, const C: usize> EtlExpr<T> for &FixedMatrix2d<T, R, C> {
+    const DIMENSIONS: usize = 2;
+    const TYPE: EtlType = EtlType::Value;
+    const THREAD_SAFE: bool = true;
+
+    type Iter<'x>
+        = std::iter::Cloned<std::slice::Iter<'x, T>>
+    where
+        T: 'x,
+        Self: 'x;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.data.as_flattened().iter().cloned()
+    }
+
+    fn iter_range(&self, range: std::ops::Range<usize>) -> Self::Iter<'_> {
+        self.data.as_flattened()[range].iter().cloned()
+    }
+
+    fn size(&self) -> usize {
+        R * C
+    }
+
+    fn rows(&self) -> usize {
+        R
+    }
+
+    fn dim(&self, i: usize) -> usize {
+        match i {
+            0 => R,
+            1 => C,
+            _ => panic!("Invalid dimension access"),
+        }
+    }
+
+    #[inline(always)]
+    fn at(&self, i: usize) -> T {
+        self.data.as_flattened()[i]
+    }
+
+    fn get_data(&self) -> &[T] {
+        self.data.as_flattened()
+    }
+}
+
+impl<T: EtlValueType, const R: usize, const C: usize> Etl2d<T> for &FixedMatrix2d<T, R, C> {
+    fn columns(&self) -> usize {
+        C
+    }
+
+    fn at2(&self, row: usize, column: usize) -> T {
+        self.data[row][column]
+    }
+}
+
+// FixedMatrix2d<T, R, C> has the fixed shape [[(); C]; R]
+impl<T: EtlValueType, const R: usize, const C: usize> FixedShape<[[(); C]; R]> for FixedMatrix2d<T, R, C> {}
+impl<T: EtlValueType, const R: usize, const C: usize> FixedShape<[[(); C]; R]> for &FixedMatrix2d<T, R, C> {}
+
+// FixedMatrix2d<T, R, C> wraps as reference
+impl<'a, T: EtlValueType, const R: usize, const C: usize> EtlWrappable<T> for &'a FixedMatrix2d<T, R, C> {
+    type WrappedAs = &'a FixedMatrix2d<T, R, C>;
+
+    fn wrap(self) -> EtlWrapper<T, Self::WrappedAs> {
+        EtlWrapper {
+            value: self,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+// FixedMatrix2d<T, R, C> computes as a padded copy
+impl<T: EtlValueType, const R: usize, const C: usize> EtlComputable<T> for &FixedMatrix2d<T, R, C> {
+    fn to_data(&self) -> Vec<T> {
+        let mut vec = vec![T::default(); padded_size(R * C)];
+        vec[..R * C].copy_from_slice(self.data.as_flattened());
+        vec
+    }
+}
+
+impl<T: EtlValueType, const R: usize, const C: usize> fmt::Display for FixedMatrix2d<T, R, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_container(f, self.data.as_flattened(), &[R, C])
+    }
+}
+
+impl<T: EtlValueType, const R: usize, const C: usize> fmt::Debug for FixedMatrix2d<T, R, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_container_debug(f, "FixedMatrix2d", self.data.as_flattened(), &[R, C])
+    }
+}
+
+// Operator overloading for FixedMatrix2d<T, R, C>
+
+impl<T: EtlValueType, const R: usize, const C: usize> std::ops::Index<usize> for FixedMatrix2d<T, R, C> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.data.as_flattened()[index]
+    }
+}
+
+impl<T: EtlValueType, const R: usize, const C: usize> std::ops::IndexMut<usize> for FixedMatrix2d<T, R, C> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        &mut self.data.as_flattened_mut()[index]
+    }
+}
+
+// Since we can't overload Assign, we settle for BitOrAssign
+impl<T: EtlValueType, const R: usize, const C: usize, RightExpr: EtlExpr<T> + FixedShape<[[(); C]; R]>> BitOrAssign<RightExpr> for FixedMatrix2d<T, R, C> {
+    fn bitor_assign(&mut self, rhs: RightExpr) {
+        validate_assign(self, &rhs);
+        assign_direct(self.data.as_flattened_mut(), &rhs);
+    }
+}
+
+// Operations
+
+crate::impl_fixed_op!(Add, add, add_expr::AddExpr, AddAssign, add_assign, add_assign_direct, FixedMatrix2d<T, R, C>, [[(); C]; R], const R, const C);
+crate::impl_fixed_op!(Sub, sub, sub_expr::SubExpr, SubAssign, sub_assign, sub_assign_direct, FixedMatrix2d<T, R, C>, [[(); C]; R], const R, const C);
+crate::impl_fixed_op!(Div, div, div_expr::DivExpr, DivAssign, div_assign, div_assign_direct, FixedMatrix2d<T, R, C>, [[(); C]; R], const R, const C);
+crate::impl_fixed_op!(Shr, shr, scale_expr::ScaleExpr, ShrAssign, shr_assign, scale_assign_direct, FixedMatrix2d<T, R, C>, [[(); C]; R], const R, const C);
+
+// Matrix matrix multiplication, the inner dimensions must match
+impl<'a, 'b, T: EtlValueType, const R: usize, const C: usize, const K: usize> std::ops::Mul<&'b FixedMatrix2d<T, C, K>> for &'a FixedMatrix2d<T, R, C>
+where
+    T: Vectorizable,
+{
+    type Output = crate::mul_expr::MulExpr<T, &'a FixedMatrix2d<T, R, C>, &'b FixedMatrix2d<T, C, K>>;
+
+    fn mul(self, other: &'b FixedMatrix2d<T, C, K>) -> Self::Output {
+        Self::Output::new(self, other)
+    }
+}
+
+impl<T: EtlValueType, const R: usize, const C: usize, const K: usize> FixedShape<[[(); K]; R]>
+    for crate::mul_expr::MulExpr<T, &FixedMatrix2d<T, R, C>, &FixedMatrix2d<T, C, K>>
+where
+    T: Vectorizable,
+{
+}
+
+// Matrix vector multiplication, the inner dimensions must match
+impl<'a, 'b, T: EtlValueType, const R: usize, const C: usize> std::ops::Mul<&'b FixedVector<T, C>> for &'a FixedMatrix2d<T, R, C>
+where
+    T: Vectorizable,
+{
+    type Output = crate::mul_expr::MulExpr<T, &'a FixedMatrix2d<T, R, C>, &'b FixedVector<T, C>>;
+
+    fn mul(self, other: &'b FixedVector<T, C>) -> Self::Output {
+        Self::Output::new(self, other)
+    }
+}
+
+impl<T: EtlValueType, const R: usize, const C: usize> FixedShape<[(); R]> for crate::mul_expr::MulExpr<T, &FixedMatrix2d<T, R, C>, &FixedVector<T, C>> where
+    T: Vectorizable
+{
+}
+
+impl<T: EtlValueType, const R: usize, const C: usize> From<[[T; C]; R]> for FixedMatrix2d<T, R, C> {
+    fn from(data: [[T; C]; R]) -> Self {
+        Self::from_rows(data)
+    }
+}
+
+// Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::constant::cst;
+    use crate::matrix_2d::Matrix2d;
+    use crate::sigmoid_expr::sigmoid;
+
+    #[test]
+    fn construct() {
+        let mat = FixedMatrix2d::<i64, 3, 2>::new();
+        assert_eq!(mat.size(), 6);
+        assert_eq!(mat.rows(), 3);
+        assert_eq!(mat.columns(), 2);
+        assert_eq!(mat.dim(0), 3);
+        assert_eq!(mat.dim(1), 2);
+    }
+
+    #[test]
+    fn row_major() {
+        let mat = FixedMatrix2d::<i64, 2, 2>::from_rows([[1, 2], [3, 4]]);
+
+        assert_eq!(mat.at(0), 1);
+        assert_eq!(mat.at(1), 2);
+        assert_eq!(mat.at(2), 3);
+        assert_eq!(mat.at2(1, 1), 4);
+    }
+
+    #[test]
+    fn print() {
+        let mat = FixedMatrix2d::<i32, 3, 2>::new_iota(1);
+        assert_eq!(format!("{mat}"), "[[1,2]\n [3,4]\n [5,6]]");
+    }
+
+    #[test]
+    fn elementwise() {
+        let a = FixedMatrix2d::<f64, 2, 2>::from_rows([[1.0, 2.0], [3.0, 4.0]]);
+        let b = FixedMatrix2d::<f64, 2, 2>::from_rows([[2.0, 2.0], [2.0, 2.0]]);
+        let mut c = FixedMatrix2d::<f64, 2, 2>::new();
+
+        c |= (&a - &b) / cst(2.0);
+
+        assert_eq!(c.at2(0, 0), -0.5);
+        assert_eq!(c.at2(1, 1), 1.0);
+
+        c |= sigmoid(&a >> &b);
+
+        approx::assert_relative_eq!(c.at2(0, 0), 1.0 / (1.0 + (-2.0_f64).exp()), epsilon = 1e-6);
+    }
+
+    #[test]
+    fn gemm() {
+        let a = FixedMatrix2d::<i64, 2, 3>::new_iota(1);
+        let b = FixedMatrix2d::<i64, 3, 2>::new_iota(7);
+        let mut c = FixedMatrix2d::<i64, 2, 2>::new();
+
+        c |= &a * &b;
+
+        assert_eq!(c.at2(0, 0), 58);
+        assert_eq!(c.at2(0, 1), 64);
+        assert_eq!(c.at2(1, 0), 139);
+        assert_eq!(c.at2(1, 1), 154);
+
+        c += &a * &b;
+
+        assert_eq!(c.at2(0, 0), 116);
+        assert_eq!(c.at2(1, 1), 308);
+    }
+
+    #[test]
+    fn gemv() {
+        let a = FixedMatrix2d::<i64, 2, 3>::new_iota(1);
+        let b = FixedVector::<i64, 3>::from_array([1, 2, 3]);
+        let mut c = FixedVector::<i64, 2>::new();
+
+        c |= &a * &b;
+
+        assert_eq!(c.at(0), 14);
+        assert_eq!(c.at(1), 32);
+    }
+
+    #[test]
+    fn mixed() {
+        let a = FixedMatrix2d::<i64, 2, 3>::new_iota(1);
+        let b = Matrix2d::<i64>::new_iota(2, 3, 1);
+        let mut c = Matrix2d::<i64>::new(2, 3);
+
+        c |= &b + &a;
+
+        assert_eq!(c.at2(0, 0), 2);
+        assert_eq!(c.at2(1, 2), 12);
+
+        let d = FixedMatrix2d::<i64, 2, 3>::new_from_expr(&c);
+        assert_eq!(d.at2(1, 2), 12);
+    }
+}
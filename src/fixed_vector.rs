@@ -0,0 +1,412 @@
+use crate::base_traits::Vectorizable;
+use crate::etl_expr::*;
+use crate::fixed_matrix_2d::FixedMatrix2d;
+use crate::print::{fmt_container, fmt_container_debug};
+
+use std::{fmt, ops::BitOrAssign};
+
+use rand::Rng;
+
+// The declaration of FixedVector<T, N>
+
+/// A vector whose size is known at compile-time
+///
+/// The storage is an array, so the vector lives on the stack and is not padded. Like the other
+/// containers, it is aligned to 64 bytes. Element-wise operations between fixed containers only
+/// compile when the sizes match, and only expressions of the same size can be assigned.
+///
+/// ```
+/// use etl::constant::cst;
+/// use etl::fixed_vector::FixedVector;
+///
+/// let a = FixedVector::<f32, 3>::from_array([1.0, 2.0, 3.0]);
+/// let mut b = FixedVector::<f32, 3>::new();
+/// b |= (&a + &a) - cst(1.0);
+/// b += &a;
+/// assert_eq!(b[2], 8.0);
+/// ```
+///
+/// ```compile_fail
+/// use etl::fixed_vector::FixedVector;
+///
+/// let a = FixedVector::<f32, 3>::new();
+/// let mut b = FixedVector::<f32, 4>::new();
+/// b += &a;
+/// ```
+///
+/// ```compile_fail
+/// use etl::fixed_vector::FixedVector;
+///
+/// let a = FixedVector::<f32, 3>::new();
+/// let b = FixedVector::<f32, 4>::new();
+/// let c = &a - &b;
+/// ```
+///
+/// Dynamic containers have no fixed size, they cannot be assigned to a fixed container (see
+/// new_from_expr instead):
+///
+/// ```compile_fail
+/// use etl::fixed_vector::FixedVector;
+/// use etl::vector::Vector;
+///
+/// let a = Vector::<f32>::new(3);
+/// let mut b = FixedVector::<f32, 3>::new();
+/// b |= &a;
+/// ```
+#[derive(Clone, Copy)]
+#[repr(C, align(64))]
+pub struct FixedVector<T: EtlValueType, const N: usize> {
+    pub data: [T; N],
+}
+
+// The functions of FixedVector<T, N>
+
+impl<T: EtlValueType, const N: usize> FixedVector<T, N> {
+    pub fn new() -> Self {
+        Self { data: [T::default(); N] }
+    }
+
+    pub fn from_array(data: [T; N]) -> Self {
+        Self { data }
+    }
+
+    pub fn new_from_expr<Expr: EtlExpr<T>>(expr: Expr) -> Self {
+        assert_eq!(Expr::DIMENSIONS, 1);
+        assert_eq!(expr.size(), N);
+
+        let mut vec = Self::new();
+
+        assign_direct(&mut vec.data, &expr);
+
+        vec
+    }
+
+    pub fn new_iota(value: T) -> Self {
+        let mut vec = Self::new();
+        vec.iota_fill(value);
+        vec
+    }
+
+    pub fn at_mut(&mut self, i: usize) -> &mut T {
+        &mut self.data[i]
+    }
+
+    pub fn clear(&mut self) {
+        self.data.fill(T::default());
+    }
+
+    pub fn fill(&mut self, value: T) {
+        self.data.fill(value);
+    }
+
+    pub fn iota_fill(&mut self, value: T) {
+        iota_direct(&mut self.data, value);
+    }
+
+    pub fn rand_fill(&mut self)
+    where
+        rand::distr::StandardUniform: rand::distr::Distribution<T>,
+    {
+        let mut rng = rand::rng();
+
+        for value in self.data.iter_mut() {
+            *value = rng.random::<T>();
+        }
+    }
+
+    pub fn inplace_axpy<RightExpr: EtlExpr<T>>(&mut self, alpha: T, beta: T, y: RightExpr) {
+        validate_assign(self, &y);
+        axpy_direct(&mut self.data, alpha, beta, &y);
+    }
+
+    fn data_mut(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+}
+
+impl<T: EtlValueType, const N: usize> Default for FixedVector<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: EtlValueType, const N: usize> EtlExpr<T> for FixedVector<T, N> {
+    const DIMENSIONS: usize = 1;
+    const TYPE: EtlType = EtlType::Value;
+    const THREAD_SAFE: bool = true;
+
+    type Iter<'x>
+        = std::iter::Cloned<std::slice::Iter<'x, T>>
+    where
+        T: 'x,
+        Self: 'x;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.data.iter().cloned()
+    }
+
+    fn iter_range(&self, range: std::ops::Range<usize>) -> Self::Iter<'_> {
+        self.data[range].iter().cloned()
+    }
+
+    fn size(&self) -> usize {
+        N
+    }
+
+    fn rows(&self) -> usize {
+        N
+    }
+
+    #[inline(always)]
+    fn at(&self, i: usize) -> T {
+        self.data[i]
+    }
+
+    fn get_data(&self) -> &[T] {
+        &self.data
+    }
+}
+
+impl<T: EtlValueType, const N: usize> EtlExpr<T> for &FixedVector<T, N> {
+    const DIMENSIONS: usize = 1;
+    const TYPE: EtlType = EtlType::Value;
+    const THREAD_SAFE: bool = true;
+
+    type Iter<'x>
+        = std::iter::Cloned<std::slice::Iter<'x, T>>
+    where
+        T: 'x,
+        Self: 'x;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.data.iter().cloned()
+    }
+
+    fn iter_range(&self, range: std::ops::Range<usize>) -> Self::Iter<'_> {
+        self.data[range].iter().cloned()
+    }
+
+    fn size(&self) -> usize {
+        N
+    }
+
+    fn rows(&self) -> usize {
+        N
+    }
+
+    #[inline(always)]
+    fn at(&self, i: usize) -> T {
+        self.data[i]
+    }
+
+    fn get_data(&self) -> &[T] {
+        &self.data
+    }
+}
+
+// FixedVector<T, N> has the fixed shape [(); N]
+impl<T: EtlValueType, const N: usize> FixedShape<[(); N]> for FixedVector<T, N> {}
+impl<T: EtlValueType, const N: usize> FixedShape<[(); N]> for &FixedVector<T, N> {}
+
+// FixedVector<T, N> wraps as reference
+impl<'a, T: EtlValueType, const N: usize> EtlWrappable<T> for &'a FixedVector<T, N> {
+    type WrappedAs = &'a FixedVector<T, N>;
+
+    fn wrap(self) -> EtlWrapper<T, Self::WrappedAs> {
+        EtlWrapper {
+            value: self,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+// FixedVector<T, N> computes as a padded copy
+impl<T: EtlValueType, const N: usize> EtlComputable<T> for &FixedVector<T, N> {
+    fn to_data(&self) -> Vec<T> {
+        let mut vec = vec![T::default(); padded_size(N)];
+        vec[..N].copy_from_slice(&self.data);
+        vec
+    }
+}
+
+impl<T: EtlValueType, const N: usize> fmt::Display for FixedVector<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_container(f, &self.data, &[N])
+    }
+}
+
+impl<T: EtlValueType, const N: usize> fmt::Debug for FixedVector<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_container_debug(f, "FixedVector", &self.data, &[N])
+    }
+}
+
+// Operator overloading for FixedVector<T, N>
+
+impl<T: EtlValueType, const N: usize> std::ops::Index<usize> for FixedVector<T, N> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.data[index]
+    }
+}
+
+impl<T: EtlValueType, const N: usize> std::ops::IndexMut<usize> for FixedVector<T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        &mut self.data[index]
+    }
+}
+
+// Since we can't overload Assign, we settle for BitOrAssign
+impl<T: EtlValueType, const N: usize, RightExpr: EtlExpr<T> + FixedShape<[(); N]>> BitOrAssign<RightExpr> for FixedVector<T, N> {
+    fn bitor_assign(&mut self, rhs: RightExpr) {
+        validate_assign(self, &rhs);
+        assign_direct(&mut self.data, &rhs);
+    }
+}
+
+// Operations
+
+// Contrary to the other containers, the operators are only implemented between fixed containers
+// of the same size (and constants), and only expressions of the same fixed shape can be assigned,
+// so that a mismatch does not compile
+
+#[macro_export]
+macro_rules! impl_fixed_op {
+    ($trait:ident, $fn:ident, $module:ident::$expr:ident, $assign_trait:ident, $assign_fn:ident, $assign_direct:ident, $type:ty, $shape:ty $(, const $dim:ident)*) => {
+        impl<'a, 'b, T: EtlValueType, $(const $dim: usize,)*> std::ops::$trait<&'b $type> for &'a $type {
+            type Output = $crate::$module::$expr<T, &'a $type, &'b $type>;
+
+            fn $fn(self, other: &'b $type) -> Self::Output {
+                Self::Output::new(self, other)
+            }
+        }
+
+        impl<'a, T: EtlValueType, $(const $dim: usize,)*> std::ops::$trait<$crate::constant::Constant<T>> for &'a $type {
+            type Output = $crate::$module::$expr<T, &'a $type, $crate::constant::Constant<T>>;
+
+            fn $fn(self, other: $crate::constant::Constant<T>) -> Self::Output {
+                Self::Output::new(self, other)
+            }
+        }
+
+        impl<T: EtlValueType, $(const $dim: usize,)* RightExpr: EtlExpr<T> + FixedShape<$shape>> std::ops::$assign_trait<RightExpr> for $type {
+            fn $assign_fn(&mut self, other: RightExpr) {
+                validate_assign(self, &other);
+                $assign_direct(self.data_mut(), &other);
+            }
+        }
+    };
+}
+
+crate::impl_fixed_op!(Add, add, add_expr::AddExpr, AddAssign, add_assign, add_assign_direct, FixedVector<T, N>, [(); N], const N);
+crate::impl_fixed_op!(Sub, sub, sub_expr::SubExpr, SubAssign, sub_assign, sub_assign_direct, FixedVector<T, N>, [(); N], const N);
+crate::impl_fixed_op!(Div, div, div_expr::DivExpr, DivAssign, div_assign, div_assign_direct, FixedVector<T, N>, [(); N], const N);
+crate::impl_fixed_op!(Shr, shr, scale_expr::ScaleExpr, ShrAssign, shr_assign, scale_assign_direct, FixedVector<T, N>, [(); N], const N);
+
+// Vector matrix multiplication, the inner dimensions must match
+impl<'a, 'b, T: EtlValueType, const R: usize, const C: usize> std::ops::Mul<&'b FixedMatrix2d<T, R, C>> for &'a FixedVector<T, R>
+where
+    T: Vectorizable,
+{
+    type Output = crate::mul_expr::MulExpr<T, &'a FixedVector<T, R>, &'b FixedMatrix2d<T, R, C>>;
+
+    fn mul(self, other: &'b FixedMatrix2d<T, R, C>) -> Self::Output {
+        Self::Output::new(self, other)
+    }
+}
+
+impl<T: EtlValueType, const R: usize, const C: usize> FixedShape<[(); C]> for crate::mul_expr::MulExpr<T, &FixedVector<T, R>, &FixedMatrix2d<T, R, C>> where
+    T: Vectorizable
+{
+}
+
+impl<T: EtlValueType, const N: usize> From<[T; N]> for FixedVector<T, N> {
+    fn from(data: [T; N]) -> Self {
+        Self::from_array(data)
+    }
+}
+
+// Tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::constant::cst;
+    use crate::vector::Vector;
+
+    #[test]
+    fn construct() {
+        let vec = FixedVector::<i64, 3>::new();
+        assert_eq!(vec.size(), 3);
+        assert_eq!(vec.rows(), 3);
+        assert_eq!(vec.at(2), 0);
+    }
+
+    #[test]
+    fn print() {
+        let vec = FixedVector::<i32, 3>::from_array([3, 2, 1]);
+        assert_eq!(format!("{vec}"), "[3,2,1]");
+    }
+
+    #[test]
+    fn add() {
+        let a = FixedVector::<i64, 3>::from_array([1, 2, 3]);
+        let b = FixedVector::<i64, 3>::from_array([4, 5, 6]);
+        let mut c = FixedVector::<i64, 3>::new();
+
+        c |= &a + &b;
+
+        assert_eq!(c.at(0), 5);
+        assert_eq!(c.at(1), 7);
+        assert_eq!(c.at(2), 9);
+
+        c |= (&a >> &b) - cst(1);
+
+        assert_eq!(c.at(0), 3);
+        assert_eq!(c.at(1), 9);
+        assert_eq!(c.at(2), 17);
+    }
+
+    #[test]
+    fn compound() {
+        let mut a = FixedVector::<i64, 3>::from_array([1, 2, 3]);
+        let b = FixedVector::<i64, 3>::from_array([4, 5, 6]);
+
+        a += &b;
+        a -= cst(1);
+        a >>= &b;
+
+        assert_eq!(a.at(0), 16);
+        assert_eq!(a.at(1), 30);
+        assert_eq!(a.at(2), 48);
+    }
+
+    #[test]
+    fn mixed() {
+        let a = FixedVector::<i64, 3>::from_array([1, 2, 3]);
+        let b = Vector::<i64>::new_iota(3, 1);
+        let mut c = Vector::<i64>::new(3);
+
+        c |= &b + &a;
+        let d = FixedVector::<i64, 3>::new_from_expr(&c);
+
+        assert_eq!(d.at(0), 2);
+        assert_eq!(d.at(1), 4);
+        assert_eq!(d.at(2), 6);
+    }
+
+    #[test]
+    fn gevm() {
+        let a = FixedVector::<i64, 2>::from_array([1, 2]);
+        let b = FixedMatrix2d::<i64, 2, 3>::new_iota(1);
+        let mut c = FixedVector::<i64, 3>::new();
+
+        c |= &a * &b;
+
+        assert_eq!(c.at(0), 9);
+        assert_eq!(c.at(1), 12);
+        assert_eq!(c.at(2), 15);
+    }
+}
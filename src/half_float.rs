@@ -0,0 +1,196 @@
+use crate::base_traits::{Abs, Cast, Constants, Float, ScalarLanes, Vectorizable};
+
+pub use half::{bf16, f16};
+
+// The half-precision value types
+//
+// f16 and bf16 are storage types: every operation converts to f32, computes and rounds back. The
+// kernels that accumulate (GEMM) and the element-wise expressions assigned to a container work on
+// f32 values and only round the result (see Vectorizable::Compute).
+
+macro_rules! impl_half_float {
+    ($type:ty) => {
+        impl Constants for $type {
+            fn one() -> Self {
+                <$type>::ONE
+            }
+            fn zero() -> Self {
+                <$type>::ZERO
+            }
+        }
+
+        impl Float for $type {
+            fn exp(self) -> Self {
+                <$type>::from_f32(self.to_f32().exp())
+            }
+
+            fn sqrt(self) -> Self {
+                <$type>::from_f32(self.to_f32().sqrt())
+            }
+
+            fn ln(self) -> Self {
+                <$type>::from_f32(self.to_f32().ln())
+            }
+        }
+
+        impl Abs for $type {
+            fn abs(self) -> Self {
+                <$type>::from_bits(self.to_bits() & 0x7FFF)
+            }
+        }
+
+        // std::simd has no half-precision lanes, the kernels go through the lanes one at a time
+        impl Vectorizable for $type {
+            type Lanes = ScalarLanes<$type>;
+            type Compute = f32;
+
+            const CONVERTED: bool = true;
+
+            fn to_compute(self) -> f32 {
+                self.to_f32()
+            }
+
+            fn from_compute(value: f32) -> Self {
+                <$type>::from_f32(value)
+            }
+        }
+    };
+}
+
+impl_half_float!(f16);
+impl_half_float!(bf16);
+
+// f64 holds all the values of both types exactly, the conversions round only once
+macro_rules! impl_half_cast {
+    ($type:ty => $($to:ty),*) => {
+        $(
+            impl Cast<$to> for $type {
+                fn cast(self) -> $to {
+                    self.to_f64() as $to
+                }
+            }
+
+            impl Cast<$type> for $to {
+                fn cast(self) -> $type {
+                    <$type>::from_f64(self as f64)
+                }
+            }
+        )*
+
+        impl Cast<$type> for $type {
+            fn cast(self) -> $type {
+                self
+            }
+        }
+    };
+}
+
+impl_half_cast!(f16 => i64, i32, i16, i8, u64, u32, u16, u8, usize, f32, f64);
+impl_half_cast!(bf16 => i64, i32, i16, i8, u64, u32, u16, u8, usize, f32, f64);
+
+impl Cast<bf16> for f16 {
+    fn cast(self) -> bf16 {
+        bf16::from_f32(self.to_f32())
+    }
+}
+
+impl Cast<f16> for bf16 {
+    fn cast(self) -> f16 {
+        f16::from_f32(self.to_f32())
+    }
+}
+
+// The tests
+
+#[cfg(test)]
+mod tests {
+    use crate::base_traits::{Abs, Float};
+    use crate::etl_expr::EtlExpr;
+    use crate::exp_expr::exp;
+    use crate::half_float::{bf16, f16};
+    use crate::matrix_2d::Matrix2d;
+    use crate::vector::Vector;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn storage() {
+        let mut a = Vector::<f16>::new(5);
+        a[1] = f16::from_f32(1.5);
+
+        assert_eq!(a.at(0), f16::ZERO);
+        assert_eq!(a.at(1).to_f32(), 1.5);
+
+        let b = Matrix2d::<bf16>::new_iota(3, 2, bf16::from_f32(1.0));
+
+        assert_eq!(b.size(), 6);
+        assert_eq!(b.at(5).to_f32(), 6.0);
+    }
+
+    #[test]
+    fn functions() {
+        assert_eq!(f16::from_f32(-2.5).abs().to_f32(), 2.5);
+        assert_eq!(bf16::from_f32(-2.0).abs().to_f32(), 2.0);
+        assert_eq!(f16::from_f32(4.0).sqrt().to_f32(), 2.0);
+        assert_relative_eq!(bf16::from_f32(1.0).exp().to_f32(), 1.0f32.exp(), epsilon = 1e-2);
+    }
+
+    #[test]
+    fn expressions() {
+        let a = Vector::<f16>::new_iota(16, f16::from_f32(1.0));
+        let b = Vector::<f16>::new_iota(16, f16::from_f32(2.0));
+
+        let mut c = Vector::<f16>::new(16);
+        c |= (&a + &b) >> &a;
+
+        for i in 0..16 {
+            let (x, y) = ((i + 1) as f32, (i + 2) as f32);
+            assert_eq!(c.at(i).to_f32(), (x + y) * x);
+        }
+
+        let e = Vector::<bf16>::new_iota(3, bf16::from_f32(0.0));
+
+        let mut d = Vector::<bf16>::new(3);
+        d |= exp(&e);
+
+        assert_relative_eq!(d.at(2).to_f32(), 2.0f32.exp(), epsilon = 5e-2);
+    }
+
+    #[test]
+    fn single_rounding() {
+        // 2049 is not an f16 value, 2048 + 1 rounds back to 2048 at each step in f16
+        let a = Vector::<f16>::new_iota(3, f16::from_f32(2048.0));
+        let mut one = Vector::<f16>::new(3);
+        one.fill(f16::ONE);
+
+        let mut c = Vector::<f16>::new(3);
+        c |= &a + &one + &one;
+        assert_eq!(c.at(0).to_f32(), 2050.0);
+
+        c += &one + &one - &a;
+        assert_eq!(c.at(0).to_f32(), 4.0);
+
+        let d = Vector::<f16>::new_from_expr((&a + &one + &one) / &one);
+        assert_eq!(d.at(0).to_f32(), 2050.0);
+
+        let mut e = Vector::<f16>::new_iota(3, f16::from_f32(2048.0));
+        e.inplace_axpy(f16::ONE, f16::ONE, &one + &one);
+        assert_eq!(e.at(0).to_f32(), 2050.0);
+
+        let b = Vector::<bf16>::new_iota(2, bf16::from_f32(256.0));
+        let mut ones = Vector::<bf16>::new(2);
+        ones.fill(bf16::ONE);
+
+        let mut f = Vector::<bf16>::new(2);
+        f |= &b + &ones + &ones;
+        assert_eq!(f.at(0).to_f32(), 258.0);
+    }
+
+    #[test]
+    fn display() {
+        let a = f16::from_f32(1.5);
+
+        assert_eq!(format!("{a}"), "1.5");
+        assert_eq!(format!("{a:.2}"), "1.50");
+    }
+}
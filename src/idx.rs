@@ -0,0 +1,326 @@
+use crate::base_traits::Cast;
+use crate::etl_error::EtlError;
+use crate::etl_expr::*;
+use crate::matrix_2d::Matrix2d;
+use crate::matrix_4d::Matrix4d;
+use crate::vector::Vector;
+
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+// Reading of the IDX format, the format of the MNIST dataset
+//
+// A file starts with two zero bytes, the type of the values, the number of dimensions and each
+// dimension on 4 bytes, followed by the values. Everything is stored in big endian. The files
+// must be decompressed first.
+
+/// The types of the values of an IDX file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdxType {
+    U8,
+    I8,
+    I16,
+    I32,
+    F32,
+    F64,
+}
+
+impl IdxType {
+    fn from_code(code: u8) -> Result<Self, EtlError> {
+        match code {
+            0x08 => Ok(IdxType::U8),
+            0x09 => Ok(IdxType::I8),
+            0x0B => Ok(IdxType::I16),
+            0x0C => Ok(IdxType::I32),
+            0x0D => Ok(IdxType::F32),
+            0x0E => Ok(IdxType::F64),
+            _ => Err(invalid(format!("unknown type 0x{code:02x}"))),
+        }
+    }
+
+    fn size(self) -> usize {
+        match self {
+            IdxType::U8 | IdxType::I8 => 1,
+            IdxType::I16 => 2,
+            IdxType::I32 | IdxType::F32 => 4,
+            IdxType::F64 => 8,
+        }
+    }
+
+    // The value of the big endian bytes, f64 holds all the values of all the types exactly
+    fn value(self, bytes: &[u8]) -> f64 {
+        match self {
+            IdxType::U8 => bytes[0] as f64,
+            IdxType::I8 => bytes[0] as i8 as f64,
+            IdxType::I16 => i16::from_be_bytes(bytes.try_into().unwrap()) as f64,
+            IdxType::I32 => i32::from_be_bytes(bytes.try_into().unwrap()) as f64,
+            IdxType::F32 => f32::from_be_bytes(bytes.try_into().unwrap()) as f64,
+            IdxType::F64 => f64::from_be_bytes(bytes.try_into().unwrap()),
+        }
+    }
+}
+
+/// The content of an IDX file
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdxArray {
+    pub value_type: IdxType,
+    pub dims: Vec<usize>,
+    /// The values, as stored in the file
+    pub bytes: Vec<u8>,
+}
+
+impl IdxArray {
+    /// The values, decoded from the bytes one at a time
+    pub fn values(&self) -> impl Iterator<Item = f64> + '_ {
+        self.bytes.chunks_exact(self.value_type.size()).map(|bytes| self.value_type.value(bytes))
+    }
+}
+
+fn invalid(message: String) -> EtlError {
+    EtlError::InvalidFormat { format: "IDX", message }
+}
+
+/// Read an IDX file from a stream
+pub fn read_idx<R: Read>(reader: &mut R) -> Result<IdxArray, EtlError> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+
+    if magic[0] != 0 || magic[1] != 0 {
+        return Err(invalid("the magic number does not start with two zeros".to_string()));
+    }
+
+    let value_type = IdxType::from_code(magic[2])?;
+
+    let mut dims = Vec::with_capacity(magic[3] as usize);
+    for _ in 0..magic[3] {
+        let mut dim = [0u8; 4];
+        reader.read_exact(&mut dim)?;
+        dims.push(u32::from_be_bytes(dim) as usize);
+    }
+
+    // The dimensions come from the file, the number of bytes must not overflow
+    let Some(len) = dims.iter().try_fold(value_type.size(), |len, &dim| len.checked_mul(dim)) else {
+        return Err(invalid(format!("the dimensions {dims:?} are too large")));
+    };
+
+    // The buffer only grows with the bytes actually read, corrupted dimensions cannot allocate
+    let mut bytes = Vec::new();
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+
+    if bytes.len() < len {
+        return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+    }
+
+    Ok(IdxArray { value_type, dims, bytes })
+}
+
+fn load_idx<P: AsRef<Path>>(path: P) -> Result<IdxArray, EtlError> {
+    let mut reader = BufReader::new(File::open(path)?);
+    read_idx(&mut reader)
+}
+
+fn check_rank(array: &IdxArray, op: &'static str, expected: usize) -> Result<(), EtlError> {
+    if array.dims.len() != expected {
+        return Err(EtlError::RankMismatch {
+            op,
+            expected,
+            actual: array.dims.len(),
+        });
+    }
+
+    Ok(())
+}
+
+// The u8 pixels are normalized to [0, 1], the other types are kept as is
+fn pixel<T: EtlValueType>(array: &IdxArray, value: f64) -> T
+where
+    f64: Cast<T>,
+{
+    if array.value_type == IdxType::U8 { (value / 255.0).cast() } else { value.cast() }
+}
+
+/// Load the images of an IDX file in a matrix with one flattened image per row
+///
+/// The u8 pixels are normalized to [0, 1].
+pub fn load_idx_images<T: EtlValueType, P: AsRef<Path>>(path: P) -> Result<Matrix2d<T>, EtlError>
+where
+    f64: Cast<T>,
+{
+    let array = load_idx(path)?;
+    check_rank(&array, "loading of IDX images", 3)?;
+
+    let mut images = Matrix2d::new(array.dims[0], array.dims[1] * array.dims[2]);
+    for (image, value) in images.data.iter_mut().zip(array.values()) {
+        *image = pixel(&array, value);
+    }
+
+    Ok(images)
+}
+
+/// Load the images of an IDX file in a 4D matrix of images with a single channel
+///
+/// The u8 pixels are normalized to [0, 1].
+pub fn load_idx_images_4d<T: EtlValueType, P: AsRef<Path>>(path: P) -> Result<Matrix4d<T>, EtlError>
+where
+    f64: Cast<T>,
+{
+    let array = load_idx(path)?;
+    check_rank(&array, "loading of IDX images", 3)?;
+
+    let mut images = Matrix4d::new(array.dims[0], 1, array.dims[1], array.dims[2]);
+    for (image, value) in images.data.iter_mut().zip(array.values()) {
+        *image = pixel(&array, value);
+    }
+
+    Ok(images)
+}
+
+/// Load the labels of an IDX file
+pub fn load_idx_labels<T: EtlValueType, P: AsRef<Path>>(path: P) -> Result<Vector<T>, EtlError>
+where
+    f64: Cast<T>,
+{
+    let array = load_idx(path)?;
+    check_rank(&array, "loading of IDX labels", 1)?;
+
+    let mut labels = Vector::new(array.dims[0]);
+    for (label, value) in labels.data.iter_mut().zip(array.values()) {
+        *label = value.cast();
+    }
+
+    Ok(labels)
+}
+
+/// Load the labels of an IDX file, one-hot encoded into a matrix with one row per label
+pub fn load_idx_labels_one_hot<T: EtlValueType, P: AsRef<Path>>(path: P, classes: usize) -> Result<Matrix2d<T>, EtlError> {
+    let labels = load_idx_labels::<f64, _>(path)?;
+    one_hot(&labels, classes)
+}
+
+/// Encode labels into a matrix with one row per label, with a one in the column of the label
+pub fn one_hot<T: EtlValueType, L: EtlValueType + Cast<f64>, Expr: EtlExpr<L>>(labels: &Expr, classes: usize) -> Result<Matrix2d<T>, EtlError> {
+    let mut encoded = Matrix2d::new(labels.size(), classes);
+
+    for i in 0..labels.size() {
+        let label: f64 = labels.at(i).cast();
+
+        if label < 0.0 || label.fract() != 0.0 || label >= classes as f64 {
+            return Err(EtlError::InvalidFormat {
+                format: "label",
+                message: format!("{label} is not a class in [0, {classes})"),
+            });
+        }
+
+        *encoded.at_mut(i, label as usize) = T::one();
+    }
+
+    Ok(encoded)
+}
+
+// The tests
+
+#[cfg(test)]
+mod tests {
+    use crate::etl_error::EtlError;
+    use crate::etl_expr::{Etl2d, Etl4d, EtlExpr};
+    use crate::idx::*;
+    use crate::vector::Vector;
+
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("etl_idx_{}_{name}", std::process::id()))
+    }
+
+    fn idx_bytes(code: u8, dims: &[u32], values: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![0, 0, code, dims.len() as u8];
+        for dim in dims {
+            bytes.extend_from_slice(&dim.to_be_bytes());
+        }
+        bytes.extend_from_slice(values);
+        bytes
+    }
+
+    #[test]
+    fn images() {
+        let path = temp_path("images.idx3-ubyte");
+
+        // Two images of 2x3 pixels
+        let pixels = (0..12).map(|i| (i * 20) as u8).collect::<Vec<_>>();
+        std::fs::write(&path, idx_bytes(0x08, &[2, 2, 3], &pixels)).unwrap();
+
+        let images = load_idx_images::<f32, _>(&path).unwrap();
+        assert_eq!(images.rows(), 2);
+        assert_eq!(images.columns(), 6);
+        assert_eq!(images.at2(0, 0), 0.0);
+        assert_eq!(images.at2(1, 5), 220.0 / 255.0);
+
+        let images = load_idx_images_4d::<f64, _>(&path).unwrap();
+        assert_eq!(images.dim(1), 1);
+        assert_eq!(images.dim(3), 3);
+        assert_eq!(images.at4(1, 0, 1, 2), 220.0 / 255.0);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn labels() {
+        let path = temp_path("labels.idx1-ubyte");
+
+        std::fs::write(&path, idx_bytes(0x08, &[4], &[3, 0, 9, 3])).unwrap();
+
+        let labels = load_idx_labels::<i32, _>(&path).unwrap();
+        assert_eq!(labels.size(), 4);
+        assert_eq!(labels.at(2), 9);
+
+        let encoded = load_idx_labels_one_hot::<f32, _>(&path, 10).unwrap();
+        assert_eq!(encoded.rows(), 4);
+        assert_eq!(encoded.columns(), 10);
+        assert_eq!(encoded.at2(0, 3), 1.0);
+        assert_eq!(encoded.at2(2, 9), 1.0);
+        assert_eq!((0..10).map(|c| encoded.at2(1, c)).sum::<f32>(), 1.0);
+
+        let error = load_idx_labels_one_hot::<f32, _>(&path, 5).err();
+        assert!(matches!(error, Some(EtlError::InvalidFormat { format: "label", .. })));
+
+        let error = load_idx_images::<f32, _>(&path).err();
+        assert!(matches!(error, Some(EtlError::RankMismatch { expected: 3, actual: 1, .. })));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn value_types() {
+        let values = [-2i16, 300].iter().flat_map(|v| v.to_be_bytes()).collect::<Vec<_>>();
+        let array = read_idx(&mut &idx_bytes(0x0B, &[2], &values)[..]).unwrap();
+        assert_eq!(array.value_type, IdxType::I16);
+        assert_eq!(array.values().collect::<Vec<_>>(), vec![-2.0, 300.0]);
+
+        let values = [1.5f32, -0.25].iter().flat_map(|v| v.to_be_bytes()).collect::<Vec<_>>();
+        let array = read_idx(&mut &idx_bytes(0x0D, &[1, 2], &values)[..]).unwrap();
+        assert_eq!(array.dims, vec![1, 2]);
+        assert_eq!(array.values().collect::<Vec<_>>(), vec![1.5, -0.25]);
+
+        let array = read_idx(&mut &idx_bytes(0x09, &[2], &[0xFF, 0x05])[..]).unwrap();
+        assert_eq!(array.values().collect::<Vec<_>>(), vec![-1.0, 5.0]);
+
+        assert!(matches!(read_idx(&mut &idx_bytes(0x07, &[1], &[0])[..]), Err(EtlError::InvalidFormat { .. })));
+        assert!(matches!(read_idx(&mut &idx_bytes(0x0C, &[3], &[0; 8])[..]), Err(EtlError::Io { .. })));
+
+        // The dimensions are huge, but the values are missing
+        assert!(matches!(read_idx(&mut &idx_bytes(0x0E, &[u32::MAX, 1000], &[0; 8])[..]), Err(EtlError::Io { .. })));
+
+        let error = read_idx(&mut &idx_bytes(0x0E, &[u32::MAX; 3], &[0; 8])[..]).unwrap_err();
+        assert!(error.to_string().ends_with("are too large"));
+    }
+
+    #[test]
+    fn one_hot_expr() {
+        let labels = Vector::<u8>::new_iota(3, 0);
+
+        let encoded = one_hot::<f64, _, _>(&(&labels + &labels), 5).unwrap();
+        assert_eq!(encoded.at2(2, 4), 1.0);
+        assert_eq!(encoded.at2(2, 2), 0.0);
+    }
+}
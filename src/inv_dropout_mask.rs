@@ -87,10 +87,6 @@ impl<T: EtlValueType + RandFloat> EtlExpr<T> for InvDropoutMask<T> {
         0
     }
 
-    fn columns(&self) -> usize {
-        0
-    }
-
     fn at(&self, _i: usize) -> T {
         self.next_value()
     }
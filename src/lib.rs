@@ -1,8 +1,16 @@
 #![feature(portable_simd)]
 
 // The basic traits
+pub mod aligned_buffer;
 pub mod base_traits;
+pub mod complex;
+pub mod deferred_temp;
+pub mod etl_error;
 pub mod etl_expr;
+pub mod half_float;
+pub mod print;
+pub mod shape;
+pub mod workspace;
 
 // The expressions
 pub mod abs_expr;
@@ -13,6 +21,9 @@ pub mod batch_softmax_expr;
 pub mod batch_stable_softmax_expr;
 pub mod bias_add_expr;
 pub mod bias_batch_sum_expr;
+pub mod cast_expr;
+pub mod column_view;
+pub mod complex_expr;
 pub mod div_expr;
 pub mod exp_expr;
 pub mod log_expr;
@@ -20,9 +31,11 @@ pub mod min_expr;
 pub mod mul_expr;
 pub mod relu_derivative_expr;
 pub mod relu_expr;
+pub mod reshape_view;
 pub mod scale_expr;
 pub mod sigmoid_derivative_expr;
 pub mod sigmoid_expr;
+pub mod slice_view;
 pub mod softmax_expr;
 pub mod sqrt_expr;
 pub mod stable_softmax_expr;
@@ -31,9 +44,15 @@ pub mod sub_view;
 pub mod transpose_expr;
 
 // The containers
+pub mod fixed_matrix_2d;
+pub mod fixed_vector;
+#[cfg(unix)]
+pub mod mapped_tensor;
 pub mod matrix_2d;
 pub mod matrix_3d;
 pub mod matrix_4d;
+pub mod matrix_view;
+pub mod tensor;
 pub mod vector;
 
 // The pseudo containers
@@ -42,3 +61,10 @@ pub mod inv_dropout_mask;
 
 // Free functions
 pub mod reductions;
+
+// Input and output
+pub mod checkpoint;
+pub mod csv;
+pub mod idx;
+pub mod npy;
+pub mod parse;
@@ -63,8 +63,8 @@ impl<T: EtlValueType + Float, Expr: WrappableExpr<T>> EtlExpr<T> for LogExpr<T,
         self.expr.value.rows()
     }
 
-    fn columns(&self) -> usize {
-        self.expr.value.columns()
+    fn dim(&self, i: usize) -> usize {
+        self.expr.value.dim(i)
     }
 
     fn at(&self, i: usize) -> T {
@@ -103,6 +103,9 @@ pub fn log<T: EtlValueType + Float, Expr: WrappableExpr<T>>(expr: Expr) -> LogEx
     LogExpr::<T, Expr>::new(expr)
 }
 
+crate::impl_rank_unary_expr_trait!(Float, LogExpr<T, Expr>);
+crate::impl_fixed_shape_unary_expr_trait!(Float, LogExpr<T, Expr>);
+
 crate::impl_add_op_unary_expr_trait!(Float, LogExpr<T, Expr>);
 crate::impl_sub_op_unary_expr_trait!(Float, LogExpr<T, Expr>);
 crate::impl_mul_op_unary_expr_trait!(Float, LogExpr<T, Expr>);
@@ -0,0 +1,455 @@
+use crate::aligned_buffer::ALIGNMENT;
+use crate::checkpoint::{CheckpointType, parse_header};
+use crate::etl_error::EtlError;
+use crate::etl_expr::*;
+use crate::print::{fmt_container, fmt_container_debug};
+use crate::shape::Shape;
+
+use std::ffi::c_void;
+use std::fmt;
+use std::fs::File;
+use std::os::fd::AsRawFd;
+use std::path::Path;
+use std::ptr::NonNull;
+use std::sync::Arc;
+
+// The mapping of a file, with mmap and munmap from the C library
+
+const PROT_READ: i32 = 1;
+const MAP_PRIVATE: i32 = 2;
+
+unsafe extern "C" {
+    // The offset is an off_t, which is a long on the supported platforms
+    fn mmap(addr: *mut c_void, len: usize, prot: i32, flags: i32, fd: i32, offset: isize) -> *mut c_void;
+    fn munmap(addr: *mut c_void, len: usize) -> i32;
+}
+
+// A read-only mapping of a whole file, unmapped when the last container using it is dropped
+struct Mapping {
+    ptr: NonNull<u8>,
+    len: usize,
+}
+
+// The mapping is never written to
+unsafe impl Send for Mapping {}
+unsafe impl Sync for Mapping {}
+
+impl Mapping {
+    fn new<P: AsRef<Path>>(path: P) -> Result<Self, EtlError> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len() as usize;
+
+        if len == 0 {
+            // An empty mapping is not allowed, but the pointer must still be aligned
+            let ptr = NonNull::new(std::ptr::without_provenance_mut(ALIGNMENT)).unwrap();
+            return Ok(Self { ptr, len });
+        }
+
+        // Safety: the arguments describe a valid read-only mapping of the file
+        let ptr = unsafe { mmap(std::ptr::null_mut(), len, PROT_READ, MAP_PRIVATE, file.as_raw_fd(), 0) };
+
+        // MAP_FAILED is (void*) -1
+        if ptr as isize == -1 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+
+        Ok(Self {
+            ptr: NonNull::new(ptr as *mut u8).unwrap(),
+            len,
+        })
+    }
+
+    fn bytes(&self) -> &[u8] {
+        // Safety: the mapping holds len bytes
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        if self.len > 0 {
+            // Safety: this is the mapping created in new
+            unsafe { munmap(self.ptr.as_ptr() as *mut c_void, self.len) };
+        }
+    }
+}
+
+fn invalid(message: String) -> EtlError {
+    EtlError::InvalidFormat { format: "mapped", message }
+}
+
+// The declaration of MappedTensor<T, D>
+
+/// A read-only D-dimensional container backed by a memory-mapped file
+///
+/// The values are stored in the file exactly like in the other containers: row-major, in little
+/// endian, padded to padded_size values and starting on a multiple of ALIGNMENT bytes. Nothing is
+/// copied, the pages are loaded by the system when they are used. The clones share the mapping.
+#[derive(Clone)]
+pub struct MappedTensor<T: EtlValueType, const D: usize> {
+    mapping: Arc<Mapping>,
+    offset: usize,
+    shape: Shape<D>,
+    _marker: std::marker::PhantomData<T>,
+}
+
+// The functions of MappedTensor<T, D>
+
+impl<T: CheckpointType, const D: usize> MappedTensor<T, D> {
+    /// Map the values stored at offset (in bytes) in a file
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated, by this process or by any other one, as long as
+    /// the container or one of its clones is alive. The values are read directly from the mapped
+    /// pages, so a modification changes values behind shared references and a truncation makes
+    /// the reads fault.
+    pub unsafe fn open<P: AsRef<Path>>(path: P, offset: usize, dims: [usize; D]) -> Result<Self, EtlError> {
+        Self::from_mapping(Arc::new(Mapping::new(path)?), offset, dims)
+    }
+
+    /// Map a tensor of a checkpoint, its dtype and its rank must match
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while it is mapped, see open.
+    pub unsafe fn open_checkpoint<P: AsRef<Path>>(path: P, name: &str) -> Result<Self, EtlError> {
+        let mapping = Arc::new(Mapping::new(path)?);
+
+        let entries = parse_header(mapping.bytes())?;
+        let Some(entry) = entries.iter().find(|entry| entry.name == name) else {
+            return Err(invalid(format!("there is no tensor named {name}")));
+        };
+
+        if entry.dtype != T::DTYPE {
+            return Err(invalid(format!("{name} is stored as {:?}, not as {:?}", entry.dtype, T::DTYPE)));
+        }
+
+        if entry.shape.len() != D {
+            return Err(EtlError::RankMismatch {
+                op: "mapping of checkpoint tensor",
+                expected: D,
+                actual: entry.shape.len(),
+            });
+        }
+
+        Self::from_mapping(mapping, entry.offset, std::array::from_fn(|d| entry.shape[d]))
+    }
+
+    fn from_mapping(mapping: Arc<Mapping>, offset: usize, dims: [usize; D]) -> Result<Self, EtlError> {
+        assert!(D > 0, "MappedTensor must have at least one dimension");
+
+        if cfg!(target_endian = "big") {
+            return Err(invalid("the values are stored in little endian".to_string()));
+        }
+
+        if !offset.is_multiple_of(ALIGNMENT) {
+            return Err(invalid(format!("the offset {offset} is not a multiple of {ALIGNMENT}")));
+        }
+
+        // The dimensions and the offset may come from the file, the end must not overflow
+        let end = dims
+            .iter()
+            .try_fold(1usize, |size, &dim| size.checked_mul(dim))
+            .and_then(|size| size.checked_add(7).map(|_| padded_size(size)))
+            .and_then(|size| size.checked_mul(std::mem::size_of::<T>()))
+            .and_then(|len| len.checked_add(offset))
+            .ok_or_else(|| invalid(format!("the end of the values {dims:?} at offset {offset} overflows")))?;
+
+        let shape = Shape::new(dims);
+
+        if end > mapping.len {
+            return Err(invalid(format!("the file is too small ({} < {end})", mapping.len)));
+        }
+
+        Ok(Self {
+            mapping,
+            offset,
+            shape,
+            _marker: std::marker::PhantomData,
+        })
+    }
+}
+
+impl<T: EtlValueType, const D: usize> MappedTensor<T, D> {
+    pub fn shape(&self) -> &Shape<D> {
+        &self.shape
+    }
+
+    pub fn at_nd(&self, index: &[usize]) -> T {
+        self.data()[self.shape.offset(index)]
+    }
+
+    /// The padded values
+    pub fn data(&self) -> &[T] {
+        // Safety: the values are in the mapping and aligned (checked in from_mapping) and every
+        // bit pattern is a valid CheckpointType value
+        unsafe { std::slice::from_raw_parts(self.mapping.ptr.as_ptr().add(self.offset) as *const T, padded_size(self.shape.size())) }
+    }
+}
+
+// MappedTensor is an EtlExpr
+impl<T: EtlValueType, const D: usize> EtlExpr<T> for MappedTensor<T, D> {
+    const DIMENSIONS: usize = D;
+    const TYPE: EtlType = EtlType::Value;
+    const THREAD_SAFE: bool = true;
+
+    type Iter<'x>
+        = std::iter::Cloned<std::slice::Iter<'x, T>>
+    where
+        T: 'x;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.data().iter().cloned()
+    }
+
+    fn iter_range(&self, range: std::ops::Range<usize>) -> Self::Iter<'_> {
+        self.data()[range].iter().cloned()
+    }
+
+    fn size(&self) -> usize {
+        self.shape.size()
+    }
+
+    fn rows(&self) -> usize {
+        self.shape.dim(0)
+    }
+
+    fn dim(&self, i: usize) -> usize {
+        self.shape.dim(i)
+    }
+
+    #[inline(always)]
+    fn at(&self, i: usize) -> T {
+        self.data()[i]
+    }
+
+    fn get_data(&self) -> &[T] {
+        self.data()
+    }
+}
+
+impl<T: EtlValueType> Etl2d<T> for MappedTensor<T, 2> {
+    fn at2(&self, i1: usize, i2: usize) -> T {
+        self.at_nd(&[i1, i2])
+    }
+}
+
+impl<T: EtlValueType> Etl3d<T> for MappedTensor<T, 3> {
+    fn at3(&self, i1: usize, i2: usize, i3: usize) -> T {
+        self.at_nd(&[i1, i2, i3])
+    }
+}
+
+impl<T: EtlValueType> Etl4d<T> for MappedTensor<T, 4> {
+    fn at4(&self, i1: usize, i2: usize, i3: usize, i4: usize) -> T {
+        self.at_nd(&[i1, i2, i3, i4])
+    }
+}
+
+impl<T: EtlValueType, const D: usize> EtlExpr<T> for &MappedTensor<T, D> {
+    const DIMENSIONS: usize = D;
+    const TYPE: EtlType = EtlType::Value;
+    const THREAD_SAFE: bool = true;
+
+    type Iter<'x>
+        = std::iter::Cloned<std::slice::Iter<'x, T>>
+    where
+        T: 'x,
+        Self: 'x;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.data().iter().cloned()
+    }
+
+    fn iter_range(&self, range: std::ops::Range<usize>) -> Self::Iter<'_> {
+        self.data()[range].iter().cloned()
+    }
+
+    fn size(&self) -> usize {
+        self.shape.size()
+    }
+
+    fn rows(&self) -> usize {
+        self.shape.dim(0)
+    }
+
+    fn dim(&self, i: usize) -> usize {
+        self.shape.dim(i)
+    }
+
+    #[inline(always)]
+    fn at(&self, i: usize) -> T {
+        self.data()[i]
+    }
+
+    fn get_data(&self) -> &[T] {
+        self.data()
+    }
+}
+
+impl<T: EtlValueType> Etl2d<T> for &MappedTensor<T, 2> {
+    fn at2(&self, i1: usize, i2: usize) -> T {
+        self.at_nd(&[i1, i2])
+    }
+}
+
+impl<T: EtlValueType> Etl3d<T> for &MappedTensor<T, 3> {
+    fn at3(&self, i1: usize, i2: usize, i3: usize) -> T {
+        self.at_nd(&[i1, i2, i3])
+    }
+}
+
+impl<T: EtlValueType> Etl4d<T> for &MappedTensor<T, 4> {
+    fn at4(&self, i1: usize, i2: usize, i3: usize, i4: usize) -> T {
+        self.at_nd(&[i1, i2, i3, i4])
+    }
+}
+
+// MappedTensor<T, D> wraps as reference
+impl<'a, T: EtlValueType, const D: usize> EtlWrappable<T> for &'a MappedTensor<T, D> {
+    type WrappedAs = &'a MappedTensor<T, D>;
+
+    fn wrap(self) -> EtlWrapper<T, Self::WrappedAs> {
+        EtlWrapper {
+            value: self,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+// MappedTensor<T, D> computes as itself
+impl<T: EtlValueType, const D: usize> EtlComputable<T> for &MappedTensor<T, D> {
+    fn to_data(&self) -> Vec<T> {
+        self.data().to_vec()
+    }
+}
+
+impl<T: EtlValueType, const D: usize> fmt::Display for MappedTensor<T, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_container(f, self.data(), self.shape.dims())
+    }
+}
+
+impl<T: EtlValueType, const D: usize> fmt::Debug for MappedTensor<T, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_container_debug(f, "MappedTensor", self.data(), self.shape.dims())
+    }
+}
+
+// Operator overloading for MappedTensor<T, D>
+
+impl<T: EtlValueType, const D: usize> std::ops::Index<usize> for MappedTensor<T, D> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.data()[index]
+    }
+}
+
+// Operations
+
+// The container is read-only, so only the operators of the views are implemented, on references
+crate::impl_view_ops!(['a, T: EtlValueType, const D: usize] &'a MappedTensor<T, D>);
+
+// The tests
+
+#[cfg(test)]
+mod tests {
+    use crate::checkpoint::{Checkpoint, CheckpointType};
+    use crate::etl_error::EtlError;
+    use crate::etl_expr::{Etl2d, EtlExpr};
+    use crate::mapped_tensor::*;
+    use crate::matrix_2d::Matrix2d;
+    use crate::sigmoid_expr::sigmoid;
+    use crate::vector::Vector;
+
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("etl_mapped_{}_{name}", std::process::id()))
+    }
+
+    #[test]
+    fn raw() {
+        let path = temp_path("raw");
+
+        // The padded values of a container, after a first block of 64 bytes
+        let a = Matrix2d::<f32>::new_iota(3, 5, 1.0);
+        let mut bytes = vec![0u8; 64];
+        for value in a.data.iter() {
+            value.append_le_bytes(&mut bytes);
+        }
+        std::fs::write(&path, &bytes).unwrap();
+
+        let m = unsafe { MappedTensor::<f32, 2>::open(&path, 64, [3, 5]) }.unwrap();
+        assert_eq!(m.size(), 15);
+        assert_eq!(m.columns(), 5);
+        assert_eq!(m.at2(2, 4), 15.0);
+        assert_eq!(m[3], 4.0);
+
+        let mut b = Matrix2d::<f32>::new(3, 5);
+        b |= &m + &a;
+        assert_eq!(b.at2(1, 1), 14.0);
+
+        b |= sigmoid(&m - &a);
+        assert_eq!(b.at2(2, 2), 0.5);
+
+        let c = Matrix2d::<f32>::new_iota(5, 2, 0.0);
+        let mut d = Matrix2d::<f32>::new(3, 2);
+        let mut e = Matrix2d::<f32>::new(3, 2);
+        d |= &m * &c;
+        e |= &a * &c;
+        assert_eq!((0..6).map(|i| d.at(i)).collect::<Vec<_>>(), (0..6).map(|i| e.at(i)).collect::<Vec<_>>());
+
+        // The clones share the mapping
+        let n = m.clone();
+        drop(m);
+        assert_eq!(n.at2(0, 0), 1.0);
+
+        let error = unsafe { MappedTensor::<f32, 2>::open(&path, 32, [3, 5]) }.err();
+        assert!(matches!(error, Some(EtlError::InvalidFormat { format: "mapped", .. })));
+
+        let error = unsafe { MappedTensor::<f32, 2>::open(&path, 64, [3, 6]) }.err().unwrap();
+        assert_eq!(error.to_string(), "Invalid mapped data: the file is too small (128 < 160)");
+
+        let error = unsafe { MappedTensor::<f32, 2>::open(&path, 64, [usize::MAX / 2, 3]) }.err().unwrap();
+        assert_eq!(error.to_string(), format!("Invalid mapped data: the end of the values [{}, 3] at offset 64 overflows", usize::MAX / 2));
+
+        let error = unsafe { MappedTensor::<f32, 2>::open(&path, usize::MAX - 63, [1, 1]) }.err();
+        assert!(matches!(error, Some(EtlError::InvalidFormat { format: "mapped", .. })));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn checkpoint() {
+        let path = temp_path("checkpoint");
+
+        let embeddings = Matrix2d::<f64>::new_iota(7, 3, 0.5);
+        let biases = Vector::<i32>::new_iota(3, -1);
+        Checkpoint::save(&path, &[("embeddings", &embeddings), ("biases", &biases)]).unwrap();
+
+        let e = unsafe { MappedTensor::<f64, 2>::open_checkpoint(&path, "embeddings") }.unwrap();
+        let b = unsafe { MappedTensor::<i32, 1>::open_checkpoint(&path, "biases") }.unwrap();
+
+        assert_eq!(e.rows(), 7);
+        assert_eq!(e.at2(6, 2), 20.5);
+        assert_eq!(b.at(2), 1);
+
+        let mut v = Vector::<i32>::new(3);
+        v |= &b + &b;
+        assert_eq!(v.at(0), -2);
+
+        let error = unsafe { MappedTensor::<f32, 2>::open_checkpoint(&path, "embeddings") }.err().unwrap();
+        assert_eq!(error.to_string(), "Invalid mapped data: embeddings is stored as F64, not as F32");
+
+        let error = unsafe { MappedTensor::<f64, 1>::open_checkpoint(&path, "embeddings") }.err();
+        assert!(matches!(error, Some(EtlError::RankMismatch { expected: 1, actual: 2, .. })));
+
+        let error = unsafe { MappedTensor::<f64, 1>::open_checkpoint(&path, "missing") }.err();
+        assert!(matches!(error, Some(EtlError::InvalidFormat { format: "mapped", .. })));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,5 +1,8 @@
+use crate::aligned_buffer::AlignedBuffer;
 use crate::base_traits::Constants;
+use crate::etl_error::EtlError;
 use crate::etl_expr::*;
+use crate::print::{fmt_container, fmt_container_debug};
 
 use std::{fmt, ops::BitOrAssign};
 
@@ -10,7 +13,7 @@ use rand_distr::*;
 
 #[derive(Clone)]
 pub struct Matrix2d<T: EtlValueType> {
-    pub data: Vec<T>,
+    pub data: AlignedBuffer<T>,
     rows: usize,
     columns: usize,
 }
@@ -20,7 +23,7 @@ pub struct Matrix2d<T: EtlValueType> {
 impl<T: EtlValueType> Matrix2d<T> {
     pub fn new(rows: usize, columns: usize) -> Self {
         Self {
-            data: vec![T::default(); padded_size(rows * columns)],
+            data: AlignedBuffer::new(padded_size(rows * columns)),
             rows,
             columns,
         }
@@ -30,18 +33,29 @@ impl<T: EtlValueType> Matrix2d<T> {
         assert_eq!(Expr::DIMENSIONS, 2);
 
         let mut vec = Self {
-            data: vec![T::default(); padded_size(expr.size())],
+            data: AlignedBuffer::new(padded_size(expr.size())),
             rows: expr.rows(),
-            columns: expr.columns(),
+            columns: expr.dim(1),
         };
 
-        for (lhs, rhs) in vec.data.iter_mut().zip(expr.iter()) {
-            *lhs = rhs;
-        }
+        let size = expr.size();
+        assign_direct(&mut vec.data[..size], &expr);
 
         vec
     }
 
+    pub fn try_new_from_expr<Expr: EtlExpr<T>>(expr: Expr) -> Result<Self, EtlError> {
+        if Expr::DIMENSIONS != 2 {
+            return Err(EtlError::RankMismatch {
+                op: "construction of Matrix2d",
+                expected: 2,
+                actual: Expr::DIMENSIONS,
+            });
+        }
+
+        Ok(Self::new_from_expr(expr))
+    }
+
     pub fn new_copy(rhs: &Matrix2d<T>) -> Self {
         Self {
             data: rhs.data.clone(),
@@ -52,7 +66,7 @@ impl<T: EtlValueType> Matrix2d<T> {
 
     pub fn new_iota(rows: usize, columns: usize, value: T) -> Self {
         let mut mat = Self {
-            data: vec![T::default(); padded_size(rows * columns)],
+            data: AlignedBuffer::new(padded_size(rows * columns)),
             rows,
             columns,
         };
@@ -101,20 +115,29 @@ impl<T: EtlValueType> Matrix2d<T> {
         &mut self.data[row * self.columns + column]
     }
 
+    pub fn try_at2(&self, row: usize, column: usize) -> Result<T, EtlError> {
+        if row >= self.rows || column >= self.columns {
+            return Err(EtlError::OutOfBounds {
+                index: vec![row, column],
+                dims: vec![self.rows, self.columns],
+            });
+        }
+
+        Ok(self.data[row * self.columns + column])
+    }
+
     pub fn clear(&mut self) {
         self.data.fill(T::default());
     }
 
     pub fn fill(&mut self, value: T) {
-        self.data.fill(value);
+        let size = self.size();
+        self.data[..size].fill(value);
     }
 
     pub fn iota_fill(&mut self, value: T) {
-        let mut acc = value;
-        for value in self.data.iter_mut() {
-            *value = acc;
-            acc += T::one();
-        }
+        let size = self.size();
+        iota_direct(&mut self.data[..size], value);
     }
 
     pub fn rand_fill(&mut self)
@@ -122,8 +145,9 @@ impl<T: EtlValueType> Matrix2d<T> {
         rand::distr::StandardUniform: rand::distr::Distribution<T>,
     {
         let mut rng = rand::rng();
+        let size = self.size();
 
-        for value in self.data.iter_mut() {
+        for value in self.data[..size].iter_mut() {
             *value = rng.random::<T>();
         }
     }
@@ -137,8 +161,9 @@ impl<T: EtlValueType> Matrix2d<T> {
         let n = <T as Constants>::zero();
         let p = <T as Constants>::one();
         let normal = Normal::new(n, p).unwrap();
+        let size = self.size();
 
-        for value in self.data.iter_mut() {
+        for value in self.data[..size].iter_mut() {
             *value = normal.sample(&mut rng);
         }
     }
@@ -150,15 +175,32 @@ impl<T: EtlValueType> Matrix2d<T> {
     {
         let mut rng = rand::rng();
         let normal = Normal::new(mean, stddev).unwrap();
+        let size = self.size();
 
-        for value in self.data.iter_mut() {
+        for value in self.data[..size].iter_mut() {
             *value = normal.sample(&mut rng);
         }
     }
 
     pub fn inplace_axpy<RightExpr: EtlExpr<T>>(&mut self, alpha: T, beta: T, y: RightExpr) {
         validate_assign(self, &y);
-        axpy_direct(&mut self.data, alpha, beta, &y);
+        let size = self.size();
+        axpy_direct(&mut self.data[..size], alpha, beta, &y);
+    }
+
+    /// Assign an expression that reads this container, evaluated with evaluate, e.g.
+    /// `x.assign_with(|x| evaluate((&b >> x) + &z))` for `x = b * x + z`
+    pub fn assign_with(&mut self, f: impl FnOnce(&Self) -> Evaluated<T>) {
+        let rhs = f(self);
+        let size = self.size();
+        assign_evaluated(expr_dims(self), &mut self.data[..size], rhs);
+    }
+
+    pub fn try_assign<RightExpr: EtlExpr<T>>(&mut self, rhs: RightExpr) -> Result<(), EtlError> {
+        try_validate_assign(self, &rhs)?;
+        let size = self.size();
+        assign_direct(&mut self.data[..size], &rhs);
+        Ok(())
     }
 }
 
@@ -188,8 +230,12 @@ impl<T: EtlValueType> EtlExpr<T> for Matrix2d<T> {
         self.rows
     }
 
-    fn columns(&self) -> usize {
-        self.columns
+    fn dim(&self, i: usize) -> usize {
+        match i {
+            0 => self.rows,
+            1 => self.columns,
+            _ => panic!("Invalid dimension access"),
+        }
     }
 
     #[inline(always)]
@@ -197,6 +243,16 @@ impl<T: EtlValueType> EtlExpr<T> for Matrix2d<T> {
         self.data[i]
     }
 
+    fn get_data(&self) -> &[T] {
+        &self.data
+    }
+}
+
+impl<T: EtlValueType> Etl2d<T> for Matrix2d<T> {
+    fn columns(&self) -> usize {
+        self.columns
+    }
+
     fn at2(&self, row: usize, column: usize) -> T {
         if row >= self.rows {
             panic!("Row {row} is out of bounds!");
@@ -208,10 +264,6 @@ impl<T: EtlValueType> EtlExpr<T> for Matrix2d<T> {
 
         self.data[row * self.columns + column]
     }
-
-    fn get_data(&self) -> &[T] {
-        &self.data
-    }
 }
 
 impl<T: EtlValueType> EtlExpr<T> for &Matrix2d<T> {
@@ -241,8 +293,12 @@ impl<T: EtlValueType> EtlExpr<T> for &Matrix2d<T> {
         self.rows
     }
 
-    fn columns(&self) -> usize {
-        self.columns
+    fn dim(&self, i: usize) -> usize {
+        match i {
+            0 => self.rows,
+            1 => self.columns,
+            _ => panic!("Invalid dimension access"),
+        }
     }
 
     #[inline(always)]
@@ -250,6 +306,16 @@ impl<T: EtlValueType> EtlExpr<T> for &Matrix2d<T> {
         self.data[i]
     }
 
+    fn get_data(&self) -> &[T] {
+        &self.data
+    }
+}
+
+impl<T: EtlValueType> Etl2d<T> for &Matrix2d<T> {
+    fn columns(&self) -> usize {
+        self.columns
+    }
+
     fn at2(&self, row: usize, column: usize) -> T {
         if row >= self.rows {
             panic!("Row {row} is out of bounds!");
@@ -261,10 +327,6 @@ impl<T: EtlValueType> EtlExpr<T> for &Matrix2d<T> {
 
         self.data[row * self.columns + column]
     }
-
-    fn get_data(&self) -> &[T] {
-        &self.data
-    }
 }
 
 // Matrix2d<T> wraps as reference
@@ -282,30 +344,19 @@ impl<'a, T: EtlValueType> EtlWrappable<T> for &'a Matrix2d<T> {
 // Matrix2d<T> computes as itself
 impl<T: EtlValueType> EtlComputable<T> for &Matrix2d<T> {
     fn to_data(&self) -> Vec<T> {
-        self.data.clone()
+        self.data.to_vec()
     }
 }
 
 impl<T: EtlValueType> fmt::Display for Matrix2d<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "[")?;
-
-        for row in 0..self.rows() {
-            if row > 0 {
-                writeln!(f)?;
-            }
-            write!(f, "[")?;
-            for column in 0..self.columns() {
-                if column > 0 {
-                    write!(f, ",")?;
-                }
-                write!(f, "{:.6}", self.at2(row, column))?;
-            }
-
-            write!(f, "]")?
-        }
+        fmt_container(f, &self.data, &expr_dims(self))
+    }
+}
 
-        write!(f, "]")
+impl<T: EtlValueType> fmt::Debug for Matrix2d<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_container_debug(f, "Matrix2d", &self.data, &expr_dims(self))
     }
 }
 
@@ -329,7 +380,8 @@ impl<T: EtlValueType> std::ops::IndexMut<usize> for Matrix2d<T> {
 impl<T: EtlValueType, RightExpr: EtlExpr<T>> BitOrAssign<RightExpr> for Matrix2d<T> {
     fn bitor_assign(&mut self, rhs: RightExpr) {
         validate_assign(self, &rhs);
-        assign_direct(&mut self.data, &rhs);
+        let size = self.size();
+        assign_direct(&mut self.data[..size], &rhs);
     }
 }
 
@@ -345,6 +397,8 @@ crate::impl_scale_op_value!(Matrix2d<T>);
 mod tests {
     use super::*;
 
+    use crate::transpose_expr::transpose;
+
     #[test]
     fn construct_i64() {
         let mat = Matrix2d::<i64>::new(4, 2);
@@ -395,7 +449,7 @@ mod tests {
 
         println!("Display matrix: {mat}");
         let str = format!("{mat}");
-        assert_eq!(str, "[[3,2]\n[1,5]\n[6,9]]");
+        assert_eq!(str, "[[3,2]\n [1,5]\n [6,9]]");
     }
 
     #[test]
@@ -409,7 +463,7 @@ mod tests {
 
         println!("Display matrix: {mat}");
         let str = format!("{mat}");
-        assert_eq!(str, "[[3.000000,2.000000]\n[1.000000,5.000000]]");
+        assert_eq!(str, "[[3.000000,2.000000]\n [1.000000,5.000000]]");
     }
 
     #[test]
@@ -540,4 +594,94 @@ mod tests {
         assert_eq!(b.at2(1, 0), 27);
         assert_eq!(b.at2(1, 1), 42);
     }
+
+    #[test]
+    fn assign_with_elementwise() {
+        let mut x = Matrix2d::<i64>::new_iota(2, 3, 1);
+        let b = Matrix2d::<i64>::new_iota(2, 3, 1);
+
+        x.assign_with(|x| evaluate((x >> x) + &b));
+
+        assert_eq!(x.at2(0, 0), 2);
+        assert_eq!(x.at2(1, 2), 42);
+    }
+
+    #[test]
+    fn assign_with_temporary() {
+        let mut x = Matrix2d::<i64>::new_iota(2, 2, 1);
+        let w = Matrix2d::<i64>::new_iota(2, 2, 1);
+
+        // x is read non-locally by the GEMM, so the result must go through a temporary
+        x.assign_with(|x| evaluate((x * &w) + x));
+
+        assert_eq!(x.at2(0, 0), 8);
+        assert_eq!(x.at2(0, 1), 12);
+        assert_eq!(x.at2(1, 0), 18);
+        assert_eq!(x.at2(1, 1), 26);
+
+        x.assign_with(|x| evaluate(transpose(x)));
+
+        assert_eq!(x.at2(0, 1), 18);
+        assert_eq!(x.at2(1, 0), 12);
+    }
+
+    #[test]
+    #[should_panic]
+    fn assign_with_invalid() {
+        let mut x = Matrix2d::<i64>::new_iota(2, 3, 1);
+        x.assign_with(|x| evaluate(transpose(x) * x));
+    }
+
+    #[test]
+    fn try_at2() {
+        let mat = Matrix2d::<i64>::new_iota(2, 3, 1);
+
+        assert_eq!(mat.try_at2(1, 2), Ok(6));
+        assert_eq!(
+            mat.try_at2(2, 0),
+            Err(EtlError::OutOfBounds {
+                index: vec![2, 0],
+                dims: vec![2, 3]
+            })
+        );
+        assert!(mat.try_at2(0, 3).is_err());
+    }
+
+    #[test]
+    fn try_assign() {
+        let a = Matrix2d::<i64>::new_iota(2, 3, 1);
+        let b = Matrix2d::<i64>::new_iota(3, 3, 1);
+
+        let mut c = Matrix2d::<i64>::new(2, 3);
+        assert!(c.try_assign(&a + &a).is_ok());
+        assert_eq!(c.at2(1, 2), 12);
+
+        assert_eq!(
+            c.try_assign(&b).err(),
+            Some(EtlError::ShapeMismatch {
+                op: "assignment",
+                lhs: vec![2, 3],
+                rhs: vec![3, 3],
+            })
+        );
+        assert_eq!(c.at2(1, 2), 12);
+    }
+
+    #[test]
+    fn try_new_from_expr() {
+        let a = Matrix2d::<i64>::new_iota(2, 3, 1);
+        let b = crate::vector::Vector::<i64>::new_iota(3, 1);
+
+        let c = Matrix2d::<i64>::try_new_from_expr(&a >> &a).unwrap();
+        assert_eq!(c.at2(1, 2), 36);
+
+        assert_eq!(
+            Matrix2d::<i64>::try_new_from_expr(&b).err(),
+            Some(EtlError::RankMismatch {
+                op: "construction of Matrix2d",
+                expected: 2,
+                actual: 1
+            })
+        );
+    }
 }
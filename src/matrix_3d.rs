@@ -1,5 +1,8 @@
+use crate::aligned_buffer::AlignedBuffer;
 use crate::base_traits::Constants;
+use crate::etl_error::EtlError;
 use crate::etl_expr::*;
+use crate::print::{fmt_container, fmt_container_debug};
 
 use std::{fmt, ops::BitOrAssign};
 
@@ -10,7 +13,7 @@ use rand_distr::*;
 
 #[derive(Clone)]
 pub struct Matrix3d<T: EtlValueType> {
-    pub data: Vec<T>,
+    pub data: AlignedBuffer<T>,
     m: usize,
     n: usize,
     k: usize,
@@ -21,7 +24,7 @@ pub struct Matrix3d<T: EtlValueType> {
 impl<T: EtlValueType> Matrix3d<T> {
     pub fn new(m: usize, n: usize, k: usize) -> Self {
         Self {
-            data: vec![T::default(); padded_size(m * n * k)],
+            data: AlignedBuffer::new(padded_size(m * n * k)),
             m,
             n,
             k,
@@ -32,19 +35,30 @@ impl<T: EtlValueType> Matrix3d<T> {
         assert_eq!(Expr::DIMENSIONS, 3);
 
         let mut vec = Self {
-            data: vec![T::default(); padded_size(expr.size())],
+            data: AlignedBuffer::new(padded_size(expr.size())),
             m: expr.dim(0),
             n: expr.dim(1),
             k: expr.dim(2),
         };
 
-        for (lhs, rhs) in vec.data.iter_mut().zip(expr.iter()) {
-            *lhs = rhs;
-        }
+        let size = expr.size();
+        assign_direct(&mut vec.data[..size], &expr);
 
         vec
     }
 
+    pub fn try_new_from_expr<Expr: EtlExpr<T>>(expr: Expr) -> Result<Self, EtlError> {
+        if Expr::DIMENSIONS != 3 {
+            return Err(EtlError::RankMismatch {
+                op: "construction of Matrix3d",
+                expected: 3,
+                actual: Expr::DIMENSIONS,
+            });
+        }
+
+        Ok(Self::new_from_expr(expr))
+    }
+
     pub fn new_copy(rhs: &Matrix3d<T>) -> Self {
         Self {
             data: rhs.data.clone(),
@@ -56,7 +70,7 @@ impl<T: EtlValueType> Matrix3d<T> {
 
     pub fn new_iota(m: usize, n: usize, k: usize, value: T) -> Self {
         let mut mat = Self {
-            data: vec![T::default(); padded_size(m * n * k)],
+            data: AlignedBuffer::new(padded_size(m * n * k)),
             m,
             n,
             k,
@@ -115,15 +129,13 @@ impl<T: EtlValueType> Matrix3d<T> {
     }
 
     pub fn fill(&mut self, value: T) {
-        self.data.fill(value);
+        let size = self.size();
+        self.data[..size].fill(value);
     }
 
     pub fn iota_fill(&mut self, value: T) {
-        let mut acc = value;
-        for value in self.data.iter_mut() {
-            *value = acc;
-            acc += T::one();
-        }
+        let size = self.size();
+        iota_direct(&mut self.data[..size], value);
     }
 
     pub fn rand_fill(&mut self)
@@ -131,8 +143,9 @@ impl<T: EtlValueType> Matrix3d<T> {
         rand::distr::StandardUniform: rand::distr::Distribution<T>,
     {
         let mut rng = rand::rng();
+        let size = self.size();
 
-        for value in self.data.iter_mut() {
+        for value in self.data[..size].iter_mut() {
             *value = rng.random::<T>();
         }
     }
@@ -146,8 +159,9 @@ impl<T: EtlValueType> Matrix3d<T> {
         let n = <T as Constants>::zero();
         let p = <T as Constants>::one();
         let normal = Normal::new(n, p).unwrap();
+        let size = self.size();
 
-        for value in self.data.iter_mut() {
+        for value in self.data[..size].iter_mut() {
             *value = normal.sample(&mut rng);
         }
     }
@@ -159,15 +173,32 @@ impl<T: EtlValueType> Matrix3d<T> {
     {
         let mut rng = rand::rng();
         let normal = Normal::new(mean, stddev).unwrap();
+        let size = self.size();
 
-        for value in self.data.iter_mut() {
+        for value in self.data[..size].iter_mut() {
             *value = normal.sample(&mut rng);
         }
     }
 
     pub fn inplace_axpy<RightExpr: EtlExpr<T>>(&mut self, alpha: T, beta: T, y: RightExpr) {
         validate_assign(self, &y);
-        axpy_direct(&mut self.data, alpha, beta, &y);
+        let size = self.size();
+        axpy_direct(&mut self.data[..size], alpha, beta, &y);
+    }
+
+    /// Assign an expression that reads this container, evaluated with evaluate, e.g.
+    /// `x.assign_with(|x| evaluate((&b >> x) + &z))` for `x = b * x + z`
+    pub fn assign_with(&mut self, f: impl FnOnce(&Self) -> Evaluated<T>) {
+        let rhs = f(self);
+        let size = self.size();
+        assign_evaluated(expr_dims(self), &mut self.data[..size], rhs);
+    }
+
+    pub fn try_assign<RightExpr: EtlExpr<T>>(&mut self, rhs: RightExpr) -> Result<(), EtlError> {
+        try_validate_assign(self, &rhs)?;
+        let size = self.size();
+        assign_direct(&mut self.data[..size], &rhs);
+        Ok(())
     }
 }
 
@@ -197,10 +228,6 @@ impl<T: EtlValueType> EtlExpr<T> for Matrix3d<T> {
         self.m
     }
 
-    fn columns(&self) -> usize {
-        self.n
-    }
-
     fn dim(&self, i: usize) -> usize {
         match i {
             0 => self.m,
@@ -215,6 +242,12 @@ impl<T: EtlValueType> EtlExpr<T> for Matrix3d<T> {
         self.data[i]
     }
 
+    fn get_data(&self) -> &[T] {
+        &self.data
+    }
+}
+
+impl<T: EtlValueType> Etl3d<T> for Matrix3d<T> {
     fn at3(&self, m: usize, n: usize, k: usize) -> T {
         if m >= self.m {
             panic!("Row {m} is out of bounds!");
@@ -230,10 +263,6 @@ impl<T: EtlValueType> EtlExpr<T> for Matrix3d<T> {
 
         self.data[m * self.n * self.k + n * self.k + k]
     }
-
-    fn get_data(&self) -> &[T] {
-        &self.data
-    }
 }
 
 impl<T: EtlValueType> EtlExpr<T> for &Matrix3d<T> {
@@ -263,10 +292,6 @@ impl<T: EtlValueType> EtlExpr<T> for &Matrix3d<T> {
         self.m
     }
 
-    fn columns(&self) -> usize {
-        self.n
-    }
-
     fn dim(&self, i: usize) -> usize {
         match i {
             0 => self.m,
@@ -281,6 +306,12 @@ impl<T: EtlValueType> EtlExpr<T> for &Matrix3d<T> {
         self.data[i]
     }
 
+    fn get_data(&self) -> &[T] {
+        &self.data
+    }
+}
+
+impl<T: EtlValueType> Etl3d<T> for &Matrix3d<T> {
     fn at3(&self, m: usize, n: usize, k: usize) -> T {
         if m >= self.m {
             panic!("Row {m} is out of bounds!");
@@ -296,10 +327,6 @@ impl<T: EtlValueType> EtlExpr<T> for &Matrix3d<T> {
 
         self.data[m * self.n * self.k + n * self.k + k]
     }
-
-    fn get_data(&self) -> &[T] {
-        &self.data
-    }
 }
 
 // Matrix3d<T> wraps as reference
@@ -317,38 +344,19 @@ impl<'a, T: EtlValueType> EtlWrappable<T> for &'a Matrix3d<T> {
 // Matrix3d<T> computes as itself
 impl<T: EtlValueType> EtlComputable<T> for &Matrix3d<T> {
     fn to_data(&self) -> Vec<T> {
-        self.data.clone()
+        self.data.to_vec()
     }
 }
 
 impl<T: EtlValueType> fmt::Display for Matrix3d<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "[")?;
-
-        for row in 0..self.m {
-            if row > 0 {
-                writeln!(f)?;
-            }
-            write!(f, "[")?;
-            for column in 0..self.n {
-                if column > 0 {
-                    writeln!(f)?;
-                }
-                write!(f, "[")?;
-                for inner in 0..self.k {
-                    if inner > 0 {
-                        write!(f, ",")?;
-                    }
-
-                    write!(f, "{:.6}", self.at3(row, column, inner))?;
-                }
-                write!(f, "]")?
-            }
-
-            write!(f, "]")?
-        }
+        fmt_container(f, &self.data, &expr_dims(self))
+    }
+}
 
-        write!(f, "]")
+impl<T: EtlValueType> fmt::Debug for Matrix3d<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_container_debug(f, "Matrix3d", &self.data, &expr_dims(self))
     }
 }
 
@@ -372,7 +380,8 @@ impl<T: EtlValueType> std::ops::IndexMut<usize> for Matrix3d<T> {
 impl<T: EtlValueType, RightExpr: EtlExpr<T>> BitOrAssign<RightExpr> for Matrix3d<T> {
     fn bitor_assign(&mut self, rhs: RightExpr) {
         validate_assign(self, &rhs);
-        assign_direct(&mut self.data, &rhs);
+        let size = self.size();
+        assign_direct(&mut self.data[..size], &rhs);
     }
 }
 
@@ -393,7 +402,6 @@ mod tests {
         let mat = Matrix3d::<i64>::new(4, 2, 3);
         assert_eq!(mat.size(), 24);
         assert_eq!(mat.rows(), 4);
-        assert_eq!(mat.columns(), 2);
         assert_eq!(mat.dim(0), 4);
         assert_eq!(mat.dim(1), 2);
         assert_eq!(mat.dim(2), 3);
@@ -434,7 +442,7 @@ mod tests {
 
         println!("Display matrix: {mat}");
         let str = format!("{mat}");
-        assert_eq!(str, "[[[1,2]\n[3,4]]\n[[5,6]\n[7,8]]\n[[9,10]\n[11,12]]]");
+        assert_eq!(str, "[[[ 1, 2]\n  [ 3, 4]]\n [[ 5, 6]\n  [ 7, 8]]\n [[ 9,10]\n  [11,12]]]");
     }
 
     #[test]
@@ -447,7 +455,7 @@ mod tests {
         let str = format!("{mat}");
         assert_eq!(
             str,
-            "[[[1.000000,2.000000]\n[3.000000,4.000000]]\n[[5.000000,6.000000]\n[7.000000,8.000000]]\n[[9.000000,10.000000]\n[11.000000,12.000000]]]"
+            "[[[ 1.000000, 2.000000]\n  [ 3.000000, 4.000000]]\n [[ 5.000000, 6.000000]\n  [ 7.000000, 8.000000]]\n [[ 9.000000,10.000000]\n  [11.000000,12.000000]]]"
         );
     }
 
@@ -464,7 +472,6 @@ mod tests {
 
         assert_eq!(copy.size(), 8);
         assert_eq!(copy.rows(), 2);
-        assert_eq!(copy.columns(), 2);
         assert_eq!(copy.at(0), 3);
         assert_eq!(copy.at(1), 2);
         assert_eq!(copy.at(2), 1);
@@ -550,7 +557,7 @@ mod tests {
 
         a.inplace_axpy(3, 5, b);
 
-        for (n, it) in a.iter().enumerate() {
+        for (n, it) in a.iter().take(a.size()).enumerate() {
             assert_eq!(it, (3 * (n + 1) + 5 * (n + 2)).try_into().unwrap());
         }
     }
@@ -1,5 +1,8 @@
+use crate::aligned_buffer::AlignedBuffer;
 use crate::base_traits::Constants;
+use crate::etl_error::EtlError;
 use crate::etl_expr::*;
+use crate::print::{fmt_container, fmt_container_debug};
 
 use std::{fmt, ops::BitOrAssign};
 
@@ -10,7 +13,7 @@ use rand_distr::*;
 
 #[derive(Clone)]
 pub struct Matrix4d<T: EtlValueType> {
-    pub data: Vec<T>,
+    pub data: AlignedBuffer<T>,
     b: usize,
     c: usize,
     w: usize,
@@ -22,7 +25,7 @@ pub struct Matrix4d<T: EtlValueType> {
 impl<T: EtlValueType> Matrix4d<T> {
     pub fn new(b: usize, c: usize, w: usize, h: usize) -> Self {
         Self {
-            data: vec![T::default(); padded_size(b * c * w * h)],
+            data: AlignedBuffer::new(padded_size(b * c * w * h)),
             b,
             c,
             w,
@@ -34,20 +37,31 @@ impl<T: EtlValueType> Matrix4d<T> {
         assert_eq!(Expr::DIMENSIONS, 4);
 
         let mut vec = Self {
-            data: vec![T::default(); padded_size(expr.size())],
+            data: AlignedBuffer::new(padded_size(expr.size())),
             b: expr.dim(0),
             c: expr.dim(1),
             w: expr.dim(2),
             h: expr.dim(3),
         };
 
-        for (lhs, rhs) in vec.data.iter_mut().zip(expr.iter()) {
-            *lhs = rhs;
-        }
+        let size = expr.size();
+        assign_direct(&mut vec.data[..size], &expr);
 
         vec
     }
 
+    pub fn try_new_from_expr<Expr: EtlExpr<T>>(expr: Expr) -> Result<Self, EtlError> {
+        if Expr::DIMENSIONS != 4 {
+            return Err(EtlError::RankMismatch {
+                op: "construction of Matrix4d",
+                expected: 4,
+                actual: Expr::DIMENSIONS,
+            });
+        }
+
+        Ok(Self::new_from_expr(expr))
+    }
+
     pub fn new_copy(rhs: &Matrix4d<T>) -> Self {
         Self {
             data: rhs.data.clone(),
@@ -60,7 +74,7 @@ impl<T: EtlValueType> Matrix4d<T> {
 
     pub fn new_iota(b: usize, c: usize, w: usize, h: usize, value: T) -> Self {
         let mut mat = Self {
-            data: vec![T::default(); padded_size(b * c * w * h)],
+            data: AlignedBuffer::new(padded_size(b * c * w * h)),
             b,
             c,
             w,
@@ -124,15 +138,13 @@ impl<T: EtlValueType> Matrix4d<T> {
     }
 
     pub fn fill(&mut self, value: T) {
-        self.data.fill(value);
+        let size = self.size();
+        self.data[..size].fill(value);
     }
 
     pub fn iota_fill(&mut self, value: T) {
-        let mut acc = value;
-        for value in self.data.iter_mut() {
-            *value = acc;
-            acc += T::one();
-        }
+        let size = self.size();
+        iota_direct(&mut self.data[..size], value);
     }
 
     pub fn rand_fill(&mut self)
@@ -140,8 +152,9 @@ impl<T: EtlValueType> Matrix4d<T> {
         rand::distr::StandardUniform: rand::distr::Distribution<T>,
     {
         let mut rng = rand::rng();
+        let size = self.size();
 
-        for value in self.data.iter_mut() {
+        for value in self.data[..size].iter_mut() {
             *value = rng.random::<T>();
         }
     }
@@ -155,8 +168,9 @@ impl<T: EtlValueType> Matrix4d<T> {
         let n = <T as Constants>::zero();
         let p = <T as Constants>::one();
         let normal = Normal::new(n, p).unwrap();
+        let size = self.size();
 
-        for value in self.data.iter_mut() {
+        for value in self.data[..size].iter_mut() {
             *value = normal.sample(&mut rng);
         }
     }
@@ -168,15 +182,32 @@ impl<T: EtlValueType> Matrix4d<T> {
     {
         let mut rng = rand::rng();
         let normal = Normal::new(mean, stddev).unwrap();
+        let size = self.size();
 
-        for value in self.data.iter_mut() {
+        for value in self.data[..size].iter_mut() {
             *value = normal.sample(&mut rng);
         }
     }
 
     pub fn inplace_axpy<RightExpr: EtlExpr<T>>(&mut self, alpha: T, beta: T, y: RightExpr) {
         validate_assign(self, &y);
-        axpy_direct(&mut self.data, alpha, beta, &y);
+        let size = self.size();
+        axpy_direct(&mut self.data[..size], alpha, beta, &y);
+    }
+
+    /// Assign an expression that reads this container, evaluated with evaluate, e.g.
+    /// `x.assign_with(|x| evaluate((&b >> x) + &z))` for `x = b * x + z`
+    pub fn assign_with(&mut self, f: impl FnOnce(&Self) -> Evaluated<T>) {
+        let rhs = f(self);
+        let size = self.size();
+        assign_evaluated(expr_dims(self), &mut self.data[..size], rhs);
+    }
+
+    pub fn try_assign<RightExpr: EtlExpr<T>>(&mut self, rhs: RightExpr) -> Result<(), EtlError> {
+        try_validate_assign(self, &rhs)?;
+        let size = self.size();
+        assign_direct(&mut self.data[..size], &rhs);
+        Ok(())
     }
 }
 
@@ -206,10 +237,6 @@ impl<T: EtlValueType> EtlExpr<T> for Matrix4d<T> {
         self.b
     }
 
-    fn columns(&self) -> usize {
-        self.c
-    }
-
     fn dim(&self, i: usize) -> usize {
         match i {
             0 => self.b,
@@ -225,6 +252,12 @@ impl<T: EtlValueType> EtlExpr<T> for Matrix4d<T> {
         self.data[i]
     }
 
+    fn get_data(&self) -> &[T] {
+        &self.data
+    }
+}
+
+impl<T: EtlValueType> Etl4d<T> for Matrix4d<T> {
     fn at4(&self, b: usize, c: usize, w: usize, h: usize) -> T {
         if b >= self.b {
             panic!("Row {b} is out of bounds!");
@@ -244,10 +277,6 @@ impl<T: EtlValueType> EtlExpr<T> for Matrix4d<T> {
 
         self.data[b * self.c * self.w * self.h + c * self.w * self.h + w * self.h + h]
     }
-
-    fn get_data(&self) -> &[T] {
-        &self.data
-    }
 }
 
 impl<T: EtlValueType> EtlExpr<T> for &Matrix4d<T> {
@@ -277,10 +306,6 @@ impl<T: EtlValueType> EtlExpr<T> for &Matrix4d<T> {
         self.b
     }
 
-    fn columns(&self) -> usize {
-        self.c
-    }
-
     fn dim(&self, i: usize) -> usize {
         match i {
             0 => self.b,
@@ -296,6 +321,12 @@ impl<T: EtlValueType> EtlExpr<T> for &Matrix4d<T> {
         self.data[i]
     }
 
+    fn get_data(&self) -> &[T] {
+        &self.data
+    }
+}
+
+impl<T: EtlValueType> Etl4d<T> for &Matrix4d<T> {
     fn at4(&self, b: usize, c: usize, w: usize, h: usize) -> T {
         if b >= self.b {
             panic!("Row {b} is out of bounds!");
@@ -315,10 +346,6 @@ impl<T: EtlValueType> EtlExpr<T> for &Matrix4d<T> {
 
         self.data[b * self.c * self.w * self.h + c * self.w * self.h + w * self.h + h]
     }
-
-    fn get_data(&self) -> &[T] {
-        &self.data
-    }
 }
 
 // Matrix4d<T> wraps as reference
@@ -336,46 +363,19 @@ impl<'a, T: EtlValueType> EtlWrappable<T> for &'a Matrix4d<T> {
 // Matrix4d<T> computes as itself
 impl<T: EtlValueType> EtlComputable<T> for &Matrix4d<T> {
     fn to_data(&self) -> Vec<T> {
-        self.data.clone()
+        self.data.to_vec()
     }
 }
 
 impl<T: EtlValueType> fmt::Display for Matrix4d<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "[")?;
-
-        for b in 0..self.b {
-            if b > 0 {
-                writeln!(f)?;
-            }
-            write!(f, "[")?;
-            for row in 0..self.c {
-                if row > 0 {
-                    writeln!(f)?;
-                }
-                write!(f, "[")?;
-                for column in 0..self.w {
-                    if column > 0 {
-                        writeln!(f)?;
-                    }
-                    write!(f, "[")?;
-                    for inner in 0..self.h {
-                        if inner > 0 {
-                            write!(f, ",")?;
-                        }
-
-                        write!(f, "{:.6}", self.at4(b, row, column, inner))?;
-                    }
-                    write!(f, "]")?
-                }
-
-                write!(f, "]")?
-            }
-
-            write!(f, "]")?
-        }
+        fmt_container(f, &self.data, &expr_dims(self))
+    }
+}
 
-        write!(f, "]")
+impl<T: EtlValueType> fmt::Debug for Matrix4d<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_container_debug(f, "Matrix4d", &self.data, &expr_dims(self))
     }
 }
 
@@ -399,7 +399,8 @@ impl<T: EtlValueType> std::ops::IndexMut<usize> for Matrix4d<T> {
 impl<T: EtlValueType, RightExpr: EtlExpr<T>> BitOrAssign<RightExpr> for Matrix4d<T> {
     fn bitor_assign(&mut self, rhs: RightExpr) {
         validate_assign(self, &rhs);
-        assign_direct(&mut self.data, &rhs);
+        let size = self.size();
+        assign_direct(&mut self.data[..size], &rhs);
     }
 }
 
@@ -420,7 +421,6 @@ mod tests {
         let mat = Matrix4d::<i64>::new(4, 2, 3, 2);
         assert_eq!(mat.size(), 48);
         assert_eq!(mat.rows(), 4);
-        assert_eq!(mat.columns(), 2);
         assert_eq!(mat.dim(0), 4);
         assert_eq!(mat.dim(1), 2);
         assert_eq!(mat.dim(2), 3);
@@ -464,7 +464,7 @@ mod tests {
         let str = format!("{mat}");
         assert_eq!(
             str,
-            "[[[[1,2]\n[3,4]]\n[[5,6]\n[7,8]]]\n[[[9,10]\n[11,12]]\n[[13,14]\n[15,16]]]\n[[[17,18]\n[19,20]]\n[[21,22]\n[23,24]]]]"
+            "[[[[ 1, 2]\n   [ 3, 4]]\n  [[ 5, 6]\n   [ 7, 8]]]\n [[[ 9,10]\n   [11,12]]\n  [[13,14]\n   [15,16]]]\n [[[17,18]\n   [19,20]]\n  [[21,22]\n   [23,24]]]]"
         );
     }
 
@@ -478,7 +478,7 @@ mod tests {
         let str = format!("{mat}");
         assert_eq!(
             str,
-            "[[[[1.000000,2.000000]\n[3.000000,4.000000]]\n[[5.000000,6.000000]\n[7.000000,8.000000]]]\n[[[9.000000,10.000000]\n[11.000000,12.000000]]\n[[13.000000,14.000000]\n[15.000000,16.000000]]]\n[[[17.000000,18.000000]\n[19.000000,20.000000]]\n[[21.000000,22.000000]\n[23.000000,24.000000]]]]"
+            "[[[[ 1.000000, 2.000000]\n   [ 3.000000, 4.000000]]\n  [[ 5.000000, 6.000000]\n   [ 7.000000, 8.000000]]]\n [[[ 9.000000,10.000000]\n   [11.000000,12.000000]]\n  [[13.000000,14.000000]\n   [15.000000,16.000000]]]\n [[[17.000000,18.000000]\n   [19.000000,20.000000]]\n  [[21.000000,22.000000]\n   [23.000000,24.000000]]]]"
         );
     }
 
@@ -495,7 +495,6 @@ mod tests {
 
         assert_eq!(copy.size(), 16);
         assert_eq!(copy.rows(), 2);
-        assert_eq!(copy.columns(), 2);
         assert_eq!(copy.at(0), 3);
         assert_eq!(copy.at(1), 2);
         assert_eq!(copy.at(2), 1);
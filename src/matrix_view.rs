@@ -0,0 +1,516 @@
+use crate::etl_expr::*;
+use crate::print::fmt_container;
+use crate::shape::Shape;
+
+use std::{fmt, ops::BitOrAssign};
+
+// The declaration of MatrixView<'a, T, D>
+
+/// A read-only D-dimensional view over a borrowed row-major slice
+#[derive(Clone, Copy)]
+pub struct MatrixView<'a, T: EtlValueType, const D: usize> {
+    data: &'a [T],
+    shape: Shape<D>,
+}
+
+// The declaration of MatrixViewMut<'a, T, D>
+
+/// A mutable D-dimensional view over a borrowed row-major slice
+pub struct MatrixViewMut<'a, T: EtlValueType, const D: usize> {
+    data: &'a mut [T],
+    shape: Shape<D>,
+}
+
+// The functions of MatrixView<'a, T, D>
+
+impl<'a, T: EtlValueType, const D: usize> MatrixView<'a, T, D> {
+    pub fn new(data: &'a [T], dims: [usize; D]) -> Self {
+        assert!(D > 0, "MatrixView must have at least one dimension");
+
+        let shape = Shape::new(dims);
+
+        if data.len() < shape.size() {
+            panic!("Slice is too small for the view ({} < {})", data.len(), shape.size());
+        }
+
+        Self {
+            data: &data[..shape.size()],
+            shape,
+        }
+    }
+
+    pub fn shape(&self) -> &Shape<D> {
+        &self.shape
+    }
+
+    pub fn at_nd(&self, index: &[usize]) -> T {
+        self.data[self.shape.offset(index)]
+    }
+}
+
+// The functions of MatrixViewMut<'a, T, D>
+
+impl<'a, T: EtlValueType, const D: usize> MatrixViewMut<'a, T, D> {
+    pub fn new(data: &'a mut [T], dims: [usize; D]) -> Self {
+        assert!(D > 0, "MatrixViewMut must have at least one dimension");
+
+        let shape = Shape::new(dims);
+
+        if data.len() < shape.size() {
+            panic!("Slice is too small for the view ({} < {})", data.len(), shape.size());
+        }
+
+        Self {
+            data: &mut data[..shape.size()],
+            shape,
+        }
+    }
+
+    pub fn shape(&self) -> &Shape<D> {
+        &self.shape
+    }
+
+    pub fn as_view(&self) -> MatrixView<'_, T, D> {
+        MatrixView {
+            data: self.data,
+            shape: self.shape,
+        }
+    }
+
+    pub fn at_nd(&self, index: &[usize]) -> T {
+        self.data[self.shape.offset(index)]
+    }
+
+    pub fn at_nd_mut(&mut self, index: &[usize]) -> &mut T {
+        &mut self.data[self.shape.offset(index)]
+    }
+
+    pub fn clear(&mut self) {
+        self.data.fill(T::default());
+    }
+
+    pub fn fill(&mut self, value: T) {
+        self.data.fill(value);
+    }
+
+    pub fn inplace_axpy<RightExpr: EtlExpr<T>>(&mut self, alpha: T, beta: T, y: RightExpr) {
+        validate_assign(&&*self, &y);
+        axpy_direct(self.data, alpha, beta, &y);
+    }
+}
+
+// MatrixView is an EtlExpr
+impl<T: EtlValueType, const D: usize> EtlExpr<T> for MatrixView<'_, T, D> {
+    const DIMENSIONS: usize = D;
+    const TYPE: EtlType = EtlType::Value;
+    const THREAD_SAFE: bool = true;
+
+    type Iter<'x>
+        = std::iter::Cloned<std::slice::Iter<'x, T>>
+    where
+        T: 'x,
+        Self: 'x;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.data.iter().cloned()
+    }
+
+    fn iter_range(&self, range: std::ops::Range<usize>) -> Self::Iter<'_> {
+        // The view is not padded, but the range may be
+        self.data[range.start.min(self.data.len())..range.end.min(self.data.len())].iter().cloned()
+    }
+
+    fn size(&self) -> usize {
+        self.shape.size()
+    }
+
+    fn rows(&self) -> usize {
+        self.shape.dim(0)
+    }
+
+    fn dim(&self, i: usize) -> usize {
+        self.shape.dim(i)
+    }
+
+    #[inline(always)]
+    fn at(&self, i: usize) -> T {
+        self.data[i]
+    }
+
+    fn get_data(&self) -> &[T] {
+        self.data
+    }
+}
+
+impl<T: EtlValueType> Etl2d<T> for MatrixView<'_, T, 2> {
+    fn at2(&self, i1: usize, i2: usize) -> T {
+        self.at_nd(&[i1, i2])
+    }
+}
+
+impl<T: EtlValueType> Etl3d<T> for MatrixView<'_, T, 3> {
+    fn at3(&self, i1: usize, i2: usize, i3: usize) -> T {
+        self.at_nd(&[i1, i2, i3])
+    }
+}
+
+impl<T: EtlValueType> Etl4d<T> for MatrixView<'_, T, 4> {
+    fn at4(&self, i1: usize, i2: usize, i3: usize, i4: usize) -> T {
+        self.at_nd(&[i1, i2, i3, i4])
+    }
+}
+
+// MatrixViewMut is an EtlExpr (when borrowed)
+impl<T: EtlValueType, const D: usize> EtlExpr<T> for &MatrixViewMut<'_, T, D> {
+    const DIMENSIONS: usize = D;
+    const TYPE: EtlType = EtlType::Value;
+    const THREAD_SAFE: bool = true;
+
+    type Iter<'x>
+        = std::iter::Cloned<std::slice::Iter<'x, T>>
+    where
+        T: 'x,
+        Self: 'x;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.data.iter().cloned()
+    }
+
+    fn iter_range(&self, range: std::ops::Range<usize>) -> Self::Iter<'_> {
+        // The view is not padded, but the range may be
+        self.data[range.start.min(self.data.len())..range.end.min(self.data.len())].iter().cloned()
+    }
+
+    fn size(&self) -> usize {
+        self.shape.size()
+    }
+
+    fn rows(&self) -> usize {
+        self.shape.dim(0)
+    }
+
+    fn dim(&self, i: usize) -> usize {
+        self.shape.dim(i)
+    }
+
+    #[inline(always)]
+    fn at(&self, i: usize) -> T {
+        self.data[i]
+    }
+
+    fn get_data(&self) -> &[T] {
+        self.data
+    }
+}
+
+impl<T: EtlValueType> Etl2d<T> for &MatrixViewMut<'_, T, 2> {
+    fn at2(&self, i1: usize, i2: usize) -> T {
+        self.at_nd(&[i1, i2])
+    }
+}
+
+impl<T: EtlValueType> Etl3d<T> for &MatrixViewMut<'_, T, 3> {
+    fn at3(&self, i1: usize, i2: usize, i3: usize) -> T {
+        self.at_nd(&[i1, i2, i3])
+    }
+}
+
+impl<T: EtlValueType> Etl4d<T> for &MatrixViewMut<'_, T, 4> {
+    fn at4(&self, i1: usize, i2: usize, i3: usize, i4: usize) -> T {
+        self.at_nd(&[i1, i2, i3, i4])
+    }
+}
+
+// MatrixView is an EtlWrappable
+// MatrixView wraps as value
+impl<'a, T: EtlValueType, const D: usize> EtlWrappable<T> for MatrixView<'a, T, D> {
+    type WrappedAs = MatrixView<'a, T, D>;
+
+    fn wrap(self) -> EtlWrapper<T, Self::WrappedAs> {
+        EtlWrapper {
+            value: self,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+// MatrixViewMut wraps as reference
+impl<'a, T: EtlValueType, const D: usize> EtlWrappable<T> for &'a MatrixViewMut<'_, T, D> {
+    type WrappedAs = &'a MatrixViewMut<'a, T, D>;
+
+    fn wrap(self) -> EtlWrapper<T, Self::WrappedAs> {
+        EtlWrapper {
+            value: self,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+// MatrixView computes as a padded copy
+impl<T: EtlValueType, const D: usize> EtlComputable<T> for MatrixView<'_, T, D> {
+    fn to_data(&self) -> Vec<T> {
+        let mut vec = vec![T::default(); padded_size(self.size())];
+        vec[..self.size()].copy_from_slice(self.data);
+        vec
+    }
+}
+
+// MatrixViewMut computes as a padded copy
+impl<T: EtlValueType, const D: usize> EtlComputable<T> for &MatrixViewMut<'_, T, D> {
+    fn to_data(&self) -> Vec<T> {
+        self.as_view().to_data()
+    }
+}
+
+impl<T: EtlValueType, const D: usize> fmt::Display for MatrixView<'_, T, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_container(f, self.data, self.shape.dims())
+    }
+}
+
+impl<T: EtlValueType, const D: usize> fmt::Display for MatrixViewMut<'_, T, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_container(f, self.data, self.shape.dims())
+    }
+}
+
+// Operator overloading for MatrixView<'a, T, D> and MatrixViewMut<'a, T, D>
+
+impl<T: EtlValueType, const D: usize> std::ops::Index<usize> for MatrixView<'_, T, D> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.data[index]
+    }
+}
+
+impl<T: EtlValueType, const D: usize> std::ops::Index<usize> for MatrixViewMut<'_, T, D> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.data[index]
+    }
+}
+
+impl<T: EtlValueType, const D: usize> std::ops::IndexMut<usize> for MatrixViewMut<'_, T, D> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        &mut self.data[index]
+    }
+}
+
+// Since we can't overload Assign, we settle for BitOrAssign
+impl<T: EtlValueType, const D: usize, RightExpr: EtlExpr<T>> BitOrAssign<RightExpr> for MatrixViewMut<'_, T, D> {
+    fn bitor_assign(&mut self, rhs: RightExpr) {
+        validate_assign(&&*self, &rhs);
+        assign_direct(self.data, &rhs);
+    }
+}
+
+impl<T: EtlValueType, const D: usize, RightExpr: EtlExpr<T>> std::ops::AddAssign<RightExpr> for MatrixViewMut<'_, T, D> {
+    fn add_assign(&mut self, rhs: RightExpr) {
+        validate_assign(&&*self, &rhs);
+        add_assign_direct(self.data, &rhs);
+    }
+}
+
+impl<T: EtlValueType, const D: usize, RightExpr: EtlExpr<T>> std::ops::SubAssign<RightExpr> for MatrixViewMut<'_, T, D> {
+    fn sub_assign(&mut self, rhs: RightExpr) {
+        validate_assign(&&*self, &rhs);
+        sub_assign_direct(self.data, &rhs);
+    }
+}
+
+impl<T: EtlValueType, const D: usize, RightExpr: EtlExpr<T>> std::ops::ShrAssign<RightExpr> for MatrixViewMut<'_, T, D> {
+    fn shr_assign(&mut self, rhs: RightExpr) {
+        validate_assign(&&*self, &rhs);
+        scale_assign_direct(self.data, &rhs);
+    }
+}
+
+impl<T: EtlValueType, const D: usize, RightExpr: EtlExpr<T>> std::ops::DivAssign<RightExpr> for MatrixViewMut<'_, T, D> {
+    fn div_assign(&mut self, rhs: RightExpr) {
+        validate_assign(&&*self, &rhs);
+        div_assign_direct(self.data, &rhs);
+    }
+}
+
+// Operations
+
+// Views are small and wrapped by value, so the operators are implemented on the view itself rather
+// than on a reference to it
+
+#[macro_export]
+macro_rules! impl_view_ops {
+    ([$($generics:tt)*] $type:ty) => {
+        impl<$($generics)*, RightExpr: WrappableExpr<T>> std::ops::Add<RightExpr> for $type {
+            type Output = $crate::add_expr::AddExpr<T, $type, RightExpr>;
+
+            fn add(self, other: RightExpr) -> Self::Output {
+                Self::Output::new(self, other)
+            }
+        }
+
+        impl<$($generics)*, RightExpr: WrappableExpr<T>> std::ops::Sub<RightExpr> for $type {
+            type Output = $crate::sub_expr::SubExpr<T, $type, RightExpr>;
+
+            fn sub(self, other: RightExpr) -> Self::Output {
+                Self::Output::new(self, other)
+            }
+        }
+
+        impl<$($generics)*, RightExpr: WrappableExpr<T>> std::ops::Mul<RightExpr> for $type
+        where
+            T: $crate::base_traits::Vectorizable,
+        {
+            type Output = $crate::mul_expr::MulExpr<T, $type, RightExpr>;
+
+            fn mul(self, other: RightExpr) -> Self::Output {
+                Self::Output::new(self, other)
+            }
+        }
+
+        impl<$($generics)*, RightExpr: WrappableExpr<T>> std::ops::Div<RightExpr> for $type {
+            type Output = $crate::div_expr::DivExpr<T, $type, RightExpr>;
+
+            fn div(self, other: RightExpr) -> Self::Output {
+                Self::Output::new(self, other)
+            }
+        }
+
+        impl<$($generics)*, RightExpr: WrappableExpr<T>> std::ops::Shr<RightExpr> for $type {
+            type Output = $crate::scale_expr::ScaleExpr<T, $type, RightExpr>;
+
+            fn shr(self, other: RightExpr) -> Self::Output {
+                Self::Output::new(self, other)
+            }
+        }
+    };
+}
+
+crate::impl_view_ops!(['a, T: EtlValueType, const D: usize] MatrixView<'a, T, D>);
+
+// The tests
+
+#[cfg(test)]
+mod tests {
+    use crate::constant::cst;
+    use crate::etl_expr::EtlExpr;
+    use crate::matrix_2d::Matrix2d;
+    use crate::matrix_view::*;
+    use crate::sigmoid_expr::sigmoid;
+    use crate::vector::Vector;
+
+    #[test]
+    fn basic_view() {
+        let buffer = [1, 2, 3, 4, 5, 6];
+
+        let view = MatrixView::<i64, 2>::new(&buffer, [2, 3]);
+
+        assert_eq!(view.size(), 6);
+        assert_eq!(view.rows(), 2);
+        assert_eq!(view.columns(), 3);
+        assert_eq!(view.at2(0, 0), 1);
+        assert_eq!(view.at2(1, 2), 6);
+        assert_eq!(view.get_data(), &buffer);
+    }
+
+    #[test]
+    #[should_panic]
+    fn too_small() {
+        let buffer = [1, 2, 3, 4, 5];
+        let _view = MatrixView::<i64, 2>::new(&buffer, [2, 3]);
+    }
+
+    #[test]
+    fn view_expr() {
+        let buffer = vec![1.0, 2.0, 3.0, 4.0];
+
+        let view = MatrixView::<f64, 1>::new(&buffer, [4]);
+        let b = Vector::<f64>::new_iota(4, 1.0);
+        let mut c = Vector::<f64>::new(4);
+
+        c |= view + &b;
+
+        assert_eq!(c.at(0), 2.0);
+        assert_eq!(c.at(3), 8.0);
+
+        c |= sigmoid(view >> cst(2.0));
+
+        approx::assert_relative_eq!(c.at(0), 1.0 / (1.0 + (-2.0_f64).exp()), epsilon = 1e-6);
+    }
+
+    #[test]
+    fn view_gemm() {
+        let lhs = [1, 2, 3, 4, 5, 6];
+        let rhs = [7, 8, 9, 10, 11, 12];
+
+        let a = MatrixView::<i64, 2>::new(&lhs, [2, 3]);
+        let b = MatrixView::<i64, 2>::new(&rhs, [3, 2]);
+        let mut c = Matrix2d::<i64>::new(2, 2);
+
+        c |= a * b;
+
+        assert_eq!(c.at2(0, 0), 58);
+        assert_eq!(c.at2(0, 1), 64);
+        assert_eq!(c.at2(1, 0), 139);
+        assert_eq!(c.at2(1, 1), 154);
+    }
+
+    #[test]
+    fn view_mut_assign() {
+        let mut buffer = [0; 6];
+        let a = Matrix2d::<i64>::new_iota(2, 3, 1);
+
+        {
+            let mut view = MatrixViewMut::<i64, 2>::new(&mut buffer, [2, 3]);
+
+            view |= &a;
+            view += &a;
+            view -= cst(1);
+            view >>= &a;
+            view /= cst(2);
+
+            assert_eq!(view.at_nd(&[1, 2]), 33);
+        }
+
+        assert_eq!(buffer, [0, 3, 7, 14, 22, 33]);
+    }
+
+    #[test]
+    fn view_mut_smart() {
+        let mut buffer = [0; 4];
+        let a = Matrix2d::<i64>::new_iota(2, 3, 1);
+        let b = Matrix2d::<i64>::new_iota(3, 2, 7);
+
+        let mut view = MatrixViewMut::<i64, 2>::new(&mut buffer, [2, 2]);
+
+        view |= &a * &b;
+
+        let mut c = Matrix2d::<i64>::new(2, 2);
+        c |= &view;
+
+        assert_eq!(c.at2(0, 0), 58);
+        assert_eq!(c.at2(1, 1), 154);
+
+        let mut d = Matrix2d::<i64>::new(2, 2);
+        d |= view.as_view() + &c;
+        assert_eq!(d.at2(1, 1), 308);
+    }
+
+    #[test]
+    #[should_panic]
+    fn view_mut_invalid_assign() {
+        let mut buffer = [0; 6];
+        let a = Matrix2d::<i64>::new_iota(2, 2, 1);
+
+        let mut view = MatrixViewMut::<i64, 2>::new(&mut buffer, [2, 3]);
+        view |= &a;
+    }
+
+    #[test]
+    fn print() {
+        let buffer = [1, 2, 3, 4, 5, 6];
+        let view = MatrixView::<i32, 2>::new(&buffer, [3, 2]);
+        assert_eq!(format!("{view}"), "[[1,2]\n [3,4]\n [5,6]]");
+    }
+}
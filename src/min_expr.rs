@@ -1,3 +1,4 @@
+use crate::etl_error::EtlError;
 use crate::etl_expr::*;
 
 // The declaration of MinExpr
@@ -20,14 +21,25 @@ fn min_impl<T: EtlValueType>(a: T, b: T) -> T {
 
 impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> MinExpr<T, LeftExpr, RightExpr> {
     pub fn new(lhs: LeftExpr, rhs: RightExpr) -> Self {
+        match Self::try_new(lhs, rhs) {
+            Ok(expr) => expr,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    pub fn try_new(lhs: LeftExpr, rhs: RightExpr) -> Result<Self, EtlError> {
         if LeftExpr::DIMENSIONS > 0 && RightExpr::DIMENSIONS > 0 && lhs.size() != rhs.size() {
-            panic!("Cannot add expressions of different sizes ({} + {})", lhs.size(), rhs.size());
+            return Err(EtlError::ShapeMismatch {
+                op: "min",
+                lhs: expr_dims(&lhs),
+                rhs: expr_dims(&rhs),
+            });
         }
 
-        Self {
+        Ok(Self {
             lhs: lhs.wrap(),
             rhs: rhs.wrap(),
-        }
+        })
     }
 }
 
@@ -89,17 +101,21 @@ impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> E
         }
     }
 
-    fn columns(&self) -> usize {
+    fn dim(&self, i: usize) -> usize {
         if LeftExpr::DIMENSIONS > 0 {
-            self.lhs.value.columns()
+            self.lhs.value.dim(i)
         } else {
-            self.rhs.value.columns()
+            self.rhs.value.dim(i)
         }
     }
 
     fn at(&self, i: usize) -> T {
         min_impl(self.lhs.value.at(i), self.rhs.value.at(i))
     }
+
+    fn compute_at(&self, i: usize) -> T::Compute {
+        min_impl(self.lhs.value.compute_at(i), self.rhs.value.compute_at(i))
+    }
 }
 
 // MinExpr is an EtlWrappable
@@ -130,6 +146,9 @@ pub fn binary_min<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: Wrappa
     MinExpr::<T, LeftExpr, RightExpr>::new(lhs, rhs)
 }
 
+crate::impl_rank_binary_expr!(MinExpr<T, LeftExpr, RightExpr>);
+crate::impl_fixed_shape_binary_expr!(MinExpr<T, LeftExpr, RightExpr>);
+
 crate::impl_add_op_binary_expr!(MinExpr<T, LeftExpr, RightExpr>);
 crate::impl_sub_op_binary_expr!(MinExpr<T, LeftExpr, RightExpr>);
 crate::impl_mul_op_binary_expr!(MinExpr<T, LeftExpr, RightExpr>);
@@ -140,7 +159,7 @@ crate::impl_scale_op_binary_expr!(MinExpr<T, LeftExpr, RightExpr>);
 
 #[cfg(test)]
 mod tests {
-    use crate::etl_expr::EtlExpr;
+    use crate::etl_expr::{Etl2d, EtlExpr};
     use crate::matrix_2d::Matrix2d;
     use crate::min_expr::binary_min;
     use crate::vector::Vector;
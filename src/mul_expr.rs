@@ -1,7 +1,9 @@
+use crate::aligned_buffer::*;
 use crate::base_traits::*;
+use crate::deferred_temp::DeferredTemp;
+use crate::etl_error::EtlError;
 use crate::etl_expr::*;
-
-use std::simd::*;
+use crate::matrix_view::MatrixView;
 
 // The declaration of MulExpr
 
@@ -12,90 +14,84 @@ use std::simd::*;
 #[derive(Clone)]
 pub struct MulExpr<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>>
 where
-    Simd<T, 8>: SimdHelper,
+    T: Vectorizable,
 {
     lhs: EtlWrapper<T, LeftExpr::WrappedAs>,
     rhs: EtlWrapper<T, RightExpr::WrappedAs>,
-    pub temp: Vec<T>,
+    temp: DeferredTemp<T>,
 }
 
 // The functions of MulExpr
 
 impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> MulExpr<T, LeftExpr, RightExpr>
 where
-    Simd<T, 8>: SimdHelper,
+    T: Vectorizable,
 {
     pub fn new(lhs: LeftExpr, rhs: RightExpr) -> Self {
+        match Self::try_new(lhs, rhs) {
+            Ok(expr) => expr,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    pub fn try_new(lhs: LeftExpr, rhs: RightExpr) -> Result<Self, EtlError> {
         if LeftExpr::DIMENSIONS == 1 && RightExpr::DIMENSIONS == 2 {
             if lhs.rows() != rhs.rows() {
-                panic!("Invalid vector matrix multiplication dimensions ([{}]*[{},{}])", lhs.rows(), rhs.rows(), rhs.columns());
+                return Err(EtlError::ShapeMismatch {
+                    op: "vector matrix multiplication",
+                    lhs: vec![lhs.rows()],
+                    rhs: vec![rhs.rows(), rhs.dim(1)],
+                });
             }
         } else if LeftExpr::DIMENSIONS == 2 && RightExpr::DIMENSIONS == 1 {
-            if lhs.columns() != rhs.rows() {
-                panic!("Invalid matrix vector multiplication dimensions ([{},{}]*[{}])", lhs.rows(), rhs.rows(), rhs.columns());
+            if lhs.dim(1) != rhs.rows() {
+                return Err(EtlError::ShapeMismatch {
+                    op: "matrix vector multiplication",
+                    lhs: vec![lhs.rows(), lhs.dim(1)],
+                    rhs: vec![rhs.rows()],
+                });
             }
         } else if LeftExpr::DIMENSIONS == 2 && RightExpr::DIMENSIONS == 2 {
-            if lhs.columns() != rhs.rows() {
-                panic!(
-                    "Invalid matrix matrix multiplication dimensions ([{},{}]*[{},{}])",
-                    lhs.rows(),
-                    lhs.columns(),
-                    rhs.rows(),
-                    rhs.columns()
-                );
+            if lhs.dim(1) != rhs.rows() {
+                return Err(EtlError::ShapeMismatch {
+                    op: "matrix matrix multiplication",
+                    lhs: vec![lhs.rows(), lhs.dim(1)],
+                    rhs: vec![rhs.rows(), rhs.dim(1)],
+                });
             }
         } else {
-            panic!("Invalid vector matrix multiplication dimensions ({}D*{}D)", LeftExpr::DIMENSIONS, RightExpr::DIMENSIONS);
+            return Err(EtlError::RankMismatch {
+                op: "multiplication",
+                expected: 2,
+                actual: if LeftExpr::DIMENSIONS == 2 { RightExpr::DIMENSIONS } else { LeftExpr::DIMENSIONS },
+            });
         }
 
-        let mut expr = Self {
+        Ok(Self {
             lhs: lhs.wrap(),
             rhs: rhs.wrap(),
-            temp: Vec::<T>::new(),
-        };
-
-        let mut temp = vec![T::default(); padded_size(expr.size())];
-        expr.compute_gemm_impl(&mut temp);
-        expr.temp = temp;
+            temp: DeferredTemp::new(),
+        })
+    }
 
-        expr
+    fn temp(&self) -> &[T] {
+        self.temp.get_or_compute(self.size(), |temp| self.compute_gemm_impl(temp, false))
     }
 
     fn compute_gemm(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        output[..self.temp.len()].copy_from_slice(&self.temp[..]);
+        self.temp.compute_into(output, |output| {
+            // Most kernels accumulate into the output
+            output.fill(T::default());
+            self.compute_gemm_impl(output, false);
+        });
     }
 
     fn compute_gemm_add(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (n, value) in output.iter_mut().enumerate() {
-            *value += self.temp[n];
-        }
-    }
-
-    fn compute_gemm_sub(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (n, value) in output.iter_mut().enumerate() {
-            *value -= self.temp[n];
-        }
-    }
-
-    fn compute_gemm_scale(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (n, value) in output.iter_mut().enumerate() {
-            *value *= self.temp[n];
-        }
-    }
-
-    fn compute_gemm_div(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (n, value) in output.iter_mut().enumerate() {
-            *value /= self.temp[n];
+        if self.temp.get().is_some() {
+            self.temp.compute_into_add(output, self.size(), |temp| self.compute_gemm_impl(temp, false));
+        } else {
+            // The kernels can accumulate directly into the output
+            self.compute_gemm_impl(output, true);
         }
     }
 
@@ -116,11 +112,11 @@ where
                 let row2 = row + 1;
 
                 // inner = 0
-                let mut l1 = Simd::<T, 8>::splat(lhs[row1 * n]);
-                let mut l2 = Simd::<T, 8>::splat(lhs[row2 * n]);
+                let mut l1 = T::Lanes::splat(lhs[row1 * n]);
+                let mut l2 = T::Lanes::splat(lhs[row2 * n]);
 
-                let mut r1 = Simd::<T, 8>::from_slice(&rhs[column1..]);
-                let mut r2 = Simd::<T, 8>::from_slice(&rhs[column2..]);
+                let mut r1 = T::Lanes::from_slice(&rhs[column1..]);
+                let mut r2 = T::Lanes::from_slice(&rhs[column2..]);
 
                 let mut v1 = l1 * r1;
                 let mut v2 = l1 * r2;
@@ -128,11 +124,11 @@ where
                 let mut v4 = l2 * r2;
 
                 for inner in 1..n {
-                    l1 = Simd::<T, 8>::splat(lhs[row1 * n + inner]);
-                    l2 = Simd::<T, 8>::splat(lhs[row2 * n + inner]);
+                    l1 = T::Lanes::splat(lhs[row1 * n + inner]);
+                    l2 = T::Lanes::splat(lhs[row2 * n + inner]);
 
-                    r1 = Simd::<T, 8>::from_slice(&rhs[inner * k + column1..]);
-                    r2 = Simd::<T, 8>::from_slice(&rhs[inner * k + column2..]);
+                    r1 = T::Lanes::from_slice(&rhs[inner * k + column1..]);
+                    r2 = T::Lanes::from_slice(&rhs[inner * k + column2..]);
 
                     v1 += l1 * r1;
                     v2 += l1 * r2;
@@ -150,19 +146,19 @@ where
 
             if row < m {
                 // inner = 0
-                let mut l1 = Simd::<T, 8>::splat(lhs[row * n]);
+                let mut l1 = T::Lanes::splat(lhs[row * n]);
 
-                let mut r1 = Simd::<T, 8>::from_slice(&rhs[column1..]);
-                let mut r2 = Simd::<T, 8>::from_slice(&rhs[column2..]);
+                let mut r1 = T::Lanes::from_slice(&rhs[column1..]);
+                let mut r2 = T::Lanes::from_slice(&rhs[column2..]);
 
                 let mut v1 = l1 * r1;
                 let mut v2 = l1 * r2;
 
                 for inner in 1..n {
-                    l1 = Simd::<T, 8>::splat(lhs[row * n + inner]);
+                    l1 = T::Lanes::splat(lhs[row * n + inner]);
 
-                    r1 = Simd::<T, 8>::from_slice(&rhs[inner * k + column1..]);
-                    r2 = Simd::<T, 8>::from_slice(&rhs[inner * k + column2..]);
+                    r1 = T::Lanes::from_slice(&rhs[inner * k + column1..]);
+                    r2 = T::Lanes::from_slice(&rhs[inner * k + column2..]);
 
                     v1 += l1 * r1;
                     v2 += l1 * r2;
@@ -184,19 +180,19 @@ where
                 let row2 = row + 1;
 
                 // inner = 0
-                let mut l1 = Simd::<T, 8>::splat(lhs[row1 * n]);
-                let mut l2 = Simd::<T, 8>::splat(lhs[row2 * n]);
+                let mut l1 = T::Lanes::splat(lhs[row1 * n]);
+                let mut l2 = T::Lanes::splat(lhs[row2 * n]);
 
-                let mut r1 = Simd::<T, 8>::from_slice(&rhs[column..]);
+                let mut r1 = T::Lanes::from_slice(&rhs[column..]);
 
                 let mut v1 = l1 * r1;
                 let mut v2 = l2 * r1;
 
                 for inner in 1..n {
-                    l1 = Simd::<T, 8>::splat(lhs[row1 * n + inner]);
-                    l2 = Simd::<T, 8>::splat(lhs[row2 * n + inner]);
+                    l1 = T::Lanes::splat(lhs[row1 * n + inner]);
+                    l2 = T::Lanes::splat(lhs[row2 * n + inner]);
 
-                    r1 = Simd::<T, 8>::from_slice(&rhs[inner * k + column..]);
+                    r1 = T::Lanes::from_slice(&rhs[inner * k + column..]);
 
                     v1 += l1 * r1;
                     v2 += l2 * r1;
@@ -210,13 +206,13 @@ where
 
             if row < m {
                 // inner = 0
-                let mut l1 = Simd::<T, 8>::splat(lhs[row * n]);
-                let mut r1 = Simd::<T, 8>::from_slice(&rhs[column..]);
+                let mut l1 = T::Lanes::splat(lhs[row * n]);
+                let mut r1 = T::Lanes::from_slice(&rhs[column..]);
                 let mut v1 = l1 * r1;
 
                 for inner in 1..n {
-                    l1 = Simd::<T, 8>::splat(lhs[row * n + inner]);
-                    r1 = Simd::<T, 8>::from_slice(&rhs[inner * k + column..]);
+                    l1 = T::Lanes::splat(lhs[row * n + inner]);
+                    r1 = T::Lanes::from_slice(&rhs[inner * k + column..]);
                     v1 += l1 * r1;
                 }
 
@@ -309,7 +305,20 @@ where
         }
     }
 
-    fn medium_gemm_kernel(m: usize, n: usize, k: usize, out: &mut [T], lhs: &[T], rhs: &[T]) {
+    fn medium_gemm_kernel(m: usize, n: usize, k: usize, out: &mut [T], lhs: &[T], rhs: &[T], accumulate: bool) {
+        // The vectorized columns start at multiples of 8, so every row of out and rhs starts
+        // aligned when the containers are aligned and k is a multiple of 8
+        if k.is_multiple_of(8) && is_simd_aligned(out) && is_simd_aligned(rhs) {
+            // Safety: the alignment of out and rhs is checked above
+            unsafe { Self::medium_gemm_kernel_impl::<true>(m, n, k, out, lhs, rhs, accumulate) }
+        } else {
+            // Safety: unaligned loads and stores have no requirements
+            unsafe { Self::medium_gemm_kernel_impl::<false>(m, n, k, out, lhs, rhs, accumulate) }
+        }
+    }
+
+    // Safety: when ALIGNED is true, out and rhs must be aligned for Simd<T, 8> and k a multiple of 8
+    unsafe fn medium_gemm_kernel_impl<const ALIGNED: bool>(m: usize, n: usize, k: usize, out: &mut [T], lhs: &[T], rhs: &[T], accumulate: bool) {
         let lanes = 8;
 
         let k_block_size = 128;
@@ -328,10 +337,12 @@ where
             while row_start < m {
                 let row_end = if row_start + m_block_size > m { m } else { row_start + m_block_size };
 
-                // Zero out the block
-                for column in column_start..column_end {
-                    for row in row_start..row_end {
-                        out[row * k + column] = T::default();
+                // Zero out the block (unless we accumulate into the output)
+                if !accumulate {
+                    for column in column_start..column_end {
+                        for row in row_start..row_end {
+                            out[row * k + column] = T::default();
+                        }
                     }
                 }
 
@@ -354,17 +365,17 @@ where
                             let row1 = row;
                             let row2 = row + 1;
 
-                            let mut v1 = Simd::<T, 8>::from_slice(&out[row1 * k + column1..]);
-                            let mut v2 = Simd::<T, 8>::from_slice(&out[row1 * k + column2..]);
-                            let mut v3 = Simd::<T, 8>::from_slice(&out[row2 * k + column2..]);
-                            let mut v4 = Simd::<T, 8>::from_slice(&out[row2 * k + column2..]);
+                            let mut v1 = unsafe { simd_load::<T, ALIGNED>(out, row1 * k + column1) };
+                            let mut v2 = unsafe { simd_load::<T, ALIGNED>(out, row1 * k + column2) };
+                            let mut v3 = unsafe { simd_load::<T, ALIGNED>(out, row2 * k + column1) };
+                            let mut v4 = unsafe { simd_load::<T, ALIGNED>(out, row2 * k + column2) };
 
                             for inner in inner_start..inner_end {
-                                let l1 = Simd::<T, 8>::splat(lhs[row1 * n + inner]);
-                                let l2 = Simd::<T, 8>::splat(lhs[row2 * n + inner]);
+                                let l1 = T::Lanes::splat(lhs[row1 * n + inner]);
+                                let l2 = T::Lanes::splat(lhs[row2 * n + inner]);
 
-                                let r1 = Simd::<T, 8>::from_slice(&rhs[inner * k + column1..]);
-                                let r2 = Simd::<T, 8>::from_slice(&rhs[inner * k + column2..]);
+                                let r1 = unsafe { simd_load::<T, ALIGNED>(rhs, inner * k + column1) };
+                                let r2 = unsafe { simd_load::<T, ALIGNED>(rhs, inner * k + column2) };
 
                                 v1 += l1 * r1;
                                 v2 += l1 * r2;
@@ -372,30 +383,30 @@ where
                                 v4 += l2 * r2;
                             }
 
-                            out[row1 * k + column1..row1 * k + column1 + lanes].copy_from_slice(&v1.to_array());
-                            out[row1 * k + column2..row1 * k + column2 + lanes].copy_from_slice(&v2.to_array());
-                            out[row2 * k + column1..row2 * k + column1 + lanes].copy_from_slice(&v3.to_array());
-                            out[row2 * k + column2..row2 * k + column2 + lanes].copy_from_slice(&v4.to_array());
+                            unsafe { simd_store::<T, ALIGNED>(out, row1 * k + column1, v1) };
+                            unsafe { simd_store::<T, ALIGNED>(out, row1 * k + column2, v2) };
+                            unsafe { simd_store::<T, ALIGNED>(out, row2 * k + column1, v3) };
+                            unsafe { simd_store::<T, ALIGNED>(out, row2 * k + column2, v4) };
 
                             row += 2;
                         }
 
                         if row < row_end {
-                            let mut v1 = Simd::<T, 8>::from_slice(&out[row * k + column1..]);
-                            let mut v2 = Simd::<T, 8>::from_slice(&out[row * k + column2..]);
+                            let mut v1 = unsafe { simd_load::<T, ALIGNED>(out, row * k + column1) };
+                            let mut v2 = unsafe { simd_load::<T, ALIGNED>(out, row * k + column2) };
 
                             for inner in inner_start..inner_end {
-                                let l1 = Simd::<T, 8>::splat(lhs[row * n + inner]);
+                                let l1 = T::Lanes::splat(lhs[row * n + inner]);
 
-                                let r1 = Simd::<T, 8>::from_slice(&rhs[inner * k + column1..]);
-                                let r2 = Simd::<T, 8>::from_slice(&rhs[inner * k + column2..]);
+                                let r1 = unsafe { simd_load::<T, ALIGNED>(rhs, inner * k + column1) };
+                                let r2 = unsafe { simd_load::<T, ALIGNED>(rhs, inner * k + column2) };
 
                                 v1 += l1 * r1;
                                 v2 += l1 * r2;
                             }
 
-                            out[row * k + column1..row * k + column1 + lanes].copy_from_slice(&v1.to_array());
-                            out[row * k + column2..row * k + column2 + lanes].copy_from_slice(&v2.to_array());
+                            unsafe { simd_store::<T, ALIGNED>(out, row * k + column1, v1) };
+                            unsafe { simd_store::<T, ALIGNED>(out, row * k + column2, v2) };
                         }
 
                         column += 2 * lanes;
@@ -404,15 +415,15 @@ where
                     // vectorized loop
                     while column + lanes - 1 < column_end {
                         for row in row_start..row_end {
-                            let mut v1 = Simd::<T, 8>::from_slice(&out[row * k + column..]);
+                            let mut v1 = unsafe { simd_load::<T, ALIGNED>(out, row * k + column) };
 
                             for inner in inner_start..inner_end {
-                                let l1 = Simd::<T, 8>::splat(lhs[row * n + inner]);
-                                let r1 = Simd::<T, 8>::from_slice(&rhs[inner * k + column..]);
+                                let l1 = T::Lanes::splat(lhs[row * n + inner]);
+                                let r1 = unsafe { simd_load::<T, ALIGNED>(rhs, inner * k + column) };
                                 v1 += l1 * r1;
                             }
 
-                            out[row * k + column..row * k + column + lanes].copy_from_slice(&v1.to_array());
+                            unsafe { simd_store::<T, ALIGNED>(out, row * k + column, v1) };
                         }
 
                         column += lanes;
@@ -451,14 +462,16 @@ where
     }
 
     // Multiply LHS[m, n] with RHS[n, k] into OUT[m, k]
+    #[allow(clippy::too_many_arguments)]
     fn large_gemm_kernel(column_first: usize, column_last: usize, rows: usize, inner_size: usize, columns: usize, out: &mut [T], lhs: &[T], rhs: &[T]) {
         let lanes = 8;
 
         let inner_block_size = 112 * (16 / 4); // Optimized for f32
         let column_block_size = 96;
 
-        let mut lhs2 = vec![T::default(); rows * inner_block_size]; // [rows, inner_block_size] (RM)
-        let mut rhs2 = vec![T::default(); column_block_size * inner_block_size]; // [inner_block_size, column_block_size] (CM)
+        // Both buffers are aligned and inner_block_size is a multiple of 8, so all the loads are aligned
+        let mut lhs2 = AlignedBuffer::<T>::from_workspace(rows * inner_block_size); // [rows, inner_block_size] (RM)
+        let mut rhs2 = AlignedBuffer::<T>::from_workspace(column_block_size * inner_block_size); // [inner_block_size, column_block_size] (CM)
 
         let mut inner_block_index = 0;
 
@@ -509,13 +522,13 @@ where
                         let column2 = column + 1;
 
                         // inner = 0
-                        let l1 = Simd::<T, 8>::from_slice(&lhs2[row1 * inner_block_size..]);
-                        let l2 = Simd::<T, 8>::from_slice(&lhs2[row2 * inner_block_size..]);
-                        let l3 = Simd::<T, 8>::from_slice(&lhs2[row3 * inner_block_size..]);
-                        let l4 = Simd::<T, 8>::from_slice(&lhs2[row4 * inner_block_size..]);
+                        let l1 = unsafe { simd_load::<T, true>(&lhs2, row1 * inner_block_size) };
+                        let l2 = unsafe { simd_load::<T, true>(&lhs2, row2 * inner_block_size) };
+                        let l3 = unsafe { simd_load::<T, true>(&lhs2, row3 * inner_block_size) };
+                        let l4 = unsafe { simd_load::<T, true>(&lhs2, row4 * inner_block_size) };
 
-                        let r1 = Simd::<T, 8>::from_slice(&rhs2[column1 * inner_block_size..]);
-                        let r2 = Simd::<T, 8>::from_slice(&rhs2[column2 * inner_block_size..]);
+                        let r1 = unsafe { simd_load::<T, true>(&rhs2, column1 * inner_block_size) };
+                        let r2 = unsafe { simd_load::<T, true>(&rhs2, column2 * inner_block_size) };
 
                         let mut v1 = l1 * r1;
                         let mut v2 = l2 * r1;
@@ -527,13 +540,13 @@ where
                         let mut v8 = l4 * r2;
 
                         for inner in (lanes..inner_block).step_by(lanes) {
-                            let l1 = Simd::<T, 8>::from_slice(&lhs2[row1 * inner_block_size + inner..]);
-                            let l2 = Simd::<T, 8>::from_slice(&lhs2[row2 * inner_block_size + inner..]);
-                            let l3 = Simd::<T, 8>::from_slice(&lhs2[row3 * inner_block_size + inner..]);
-                            let l4 = Simd::<T, 8>::from_slice(&lhs2[row4 * inner_block_size + inner..]);
+                            let l1 = unsafe { simd_load::<T, true>(&lhs2, row1 * inner_block_size + inner) };
+                            let l2 = unsafe { simd_load::<T, true>(&lhs2, row2 * inner_block_size + inner) };
+                            let l3 = unsafe { simd_load::<T, true>(&lhs2, row3 * inner_block_size + inner) };
+                            let l4 = unsafe { simd_load::<T, true>(&lhs2, row4 * inner_block_size + inner) };
 
-                            let r1 = Simd::<T, 8>::from_slice(&rhs2[column1 * inner_block_size + inner..]);
-                            let r2 = Simd::<T, 8>::from_slice(&rhs2[column2 * inner_block_size + inner..]);
+                            let r1 = unsafe { simd_load::<T, true>(&rhs2, column1 * inner_block_size + inner) };
+                            let r2 = unsafe { simd_load::<T, true>(&rhs2, column2 * inner_block_size + inner) };
 
                             v1 += l1 * r1;
                             v2 += l2 * r1;
@@ -559,12 +572,12 @@ where
 
                     if column < column_block {
                         // inner = 0
-                        let l1 = Simd::<T, 8>::from_slice(&lhs2[row1 * inner_block_size..]);
-                        let l2 = Simd::<T, 8>::from_slice(&lhs2[row2 * inner_block_size..]);
-                        let l3 = Simd::<T, 8>::from_slice(&lhs2[row3 * inner_block_size..]);
-                        let l4 = Simd::<T, 8>::from_slice(&lhs2[row4 * inner_block_size..]);
+                        let l1 = unsafe { simd_load::<T, true>(&lhs2, row1 * inner_block_size) };
+                        let l2 = unsafe { simd_load::<T, true>(&lhs2, row2 * inner_block_size) };
+                        let l3 = unsafe { simd_load::<T, true>(&lhs2, row3 * inner_block_size) };
+                        let l4 = unsafe { simd_load::<T, true>(&lhs2, row4 * inner_block_size) };
 
-                        let r1 = Simd::<T, 8>::from_slice(&rhs2[column * inner_block_size..]);
+                        let r1 = unsafe { simd_load::<T, true>(&rhs2, column * inner_block_size) };
 
                         let mut v1 = l1 * r1;
                         let mut v2 = l2 * r1;
@@ -572,12 +585,12 @@ where
                         let mut v4 = l4 * r1;
 
                         for inner in (lanes..inner_block).step_by(lanes) {
-                            let l1 = Simd::<T, 8>::from_slice(&lhs2[row1 * inner_block_size + inner..]);
-                            let l2 = Simd::<T, 8>::from_slice(&lhs2[row2 * inner_block_size + inner..]);
-                            let l3 = Simd::<T, 8>::from_slice(&lhs2[row3 * inner_block_size + inner..]);
-                            let l4 = Simd::<T, 8>::from_slice(&lhs2[row4 * inner_block_size + inner..]);
+                            let l1 = unsafe { simd_load::<T, true>(&lhs2, row1 * inner_block_size + inner) };
+                            let l2 = unsafe { simd_load::<T, true>(&lhs2, row2 * inner_block_size + inner) };
+                            let l3 = unsafe { simd_load::<T, true>(&lhs2, row3 * inner_block_size + inner) };
+                            let l4 = unsafe { simd_load::<T, true>(&lhs2, row4 * inner_block_size + inner) };
 
-                            let r1 = Simd::<T, 8>::from_slice(&rhs2[column * inner_block_size + inner..]);
+                            let r1 = unsafe { simd_load::<T, true>(&rhs2, column * inner_block_size + inner) };
 
                             v1 += l1 * r1;
                             v2 += l2 * r1;
@@ -605,11 +618,11 @@ where
                         let column2 = column + 1;
 
                         // inner = 0
-                        let l1 = Simd::<T, 8>::from_slice(&lhs2[row1 * inner_block_size..]);
-                        let l2 = Simd::<T, 8>::from_slice(&lhs2[row2 * inner_block_size..]);
+                        let l1 = unsafe { simd_load::<T, true>(&lhs2, row1 * inner_block_size) };
+                        let l2 = unsafe { simd_load::<T, true>(&lhs2, row2 * inner_block_size) };
 
-                        let r1 = Simd::<T, 8>::from_slice(&rhs2[column1 * inner_block_size..]);
-                        let r2 = Simd::<T, 8>::from_slice(&rhs2[column2 * inner_block_size..]);
+                        let r1 = unsafe { simd_load::<T, true>(&rhs2, column1 * inner_block_size) };
+                        let r2 = unsafe { simd_load::<T, true>(&rhs2, column2 * inner_block_size) };
 
                         let mut v1 = l1 * r1;
                         let mut v2 = l2 * r1;
@@ -617,11 +630,11 @@ where
                         let mut v4 = l2 * r2;
 
                         for inner in (lanes..inner_block).step_by(lanes) {
-                            let l1 = Simd::<T, 8>::from_slice(&lhs2[row1 * inner_block_size + inner..]);
-                            let l2 = Simd::<T, 8>::from_slice(&lhs2[row2 * inner_block_size + inner..]);
+                            let l1 = unsafe { simd_load::<T, true>(&lhs2, row1 * inner_block_size + inner) };
+                            let l2 = unsafe { simd_load::<T, true>(&lhs2, row2 * inner_block_size + inner) };
 
-                            let r1 = Simd::<T, 8>::from_slice(&rhs2[column1 * inner_block_size + inner..]);
-                            let r2 = Simd::<T, 8>::from_slice(&rhs2[column2 * inner_block_size + inner..]);
+                            let r1 = unsafe { simd_load::<T, true>(&rhs2, column1 * inner_block_size + inner) };
+                            let r2 = unsafe { simd_load::<T, true>(&rhs2, column2 * inner_block_size + inner) };
 
                             v1 += l1 * r1;
                             v2 += l2 * r1;
@@ -639,19 +652,19 @@ where
 
                     if column < column_block {
                         // inner = 0
-                        let l1 = Simd::<T, 8>::from_slice(&lhs2[row1 * inner_block_size..]);
-                        let l2 = Simd::<T, 8>::from_slice(&lhs2[row2 * inner_block_size..]);
+                        let l1 = unsafe { simd_load::<T, true>(&lhs2, row1 * inner_block_size) };
+                        let l2 = unsafe { simd_load::<T, true>(&lhs2, row2 * inner_block_size) };
 
-                        let r1 = Simd::<T, 8>::from_slice(&rhs2[column * inner_block_size..]);
+                        let r1 = unsafe { simd_load::<T, true>(&rhs2, column * inner_block_size) };
 
                         let mut v1 = l1 * r1;
                         let mut v2 = l2 * r1;
 
                         for inner in (lanes..inner_block).step_by(lanes) {
-                            let l1 = Simd::<T, 8>::from_slice(&lhs2[row1 * inner_block_size + inner..]);
-                            let l2 = Simd::<T, 8>::from_slice(&lhs2[row2 * inner_block_size + inner..]);
+                            let l1 = unsafe { simd_load::<T, true>(&lhs2, row1 * inner_block_size + inner) };
+                            let l2 = unsafe { simd_load::<T, true>(&lhs2, row2 * inner_block_size + inner) };
 
-                            let r1 = Simd::<T, 8>::from_slice(&rhs2[column * inner_block_size + inner..]);
+                            let r1 = unsafe { simd_load::<T, true>(&rhs2, column * inner_block_size + inner) };
 
                             v1 += l1 * r1;
                             v2 += l2 * r1;
@@ -672,19 +685,19 @@ where
                         let column2 = column + 1;
 
                         // inner = 0
-                        let l1 = Simd::<T, 8>::from_slice(&lhs2[row * inner_block_size..]);
+                        let l1 = unsafe { simd_load::<T, true>(&lhs2, row * inner_block_size) };
 
-                        let r1 = Simd::<T, 8>::from_slice(&rhs2[column1 * inner_block_size..]);
-                        let r2 = Simd::<T, 8>::from_slice(&rhs2[column2 * inner_block_size..]);
+                        let r1 = unsafe { simd_load::<T, true>(&rhs2, column1 * inner_block_size) };
+                        let r2 = unsafe { simd_load::<T, true>(&rhs2, column2 * inner_block_size) };
 
                         let mut v1 = l1 * r1;
                         let mut v2 = l1 * r2;
 
                         for inner in (lanes..inner_block).step_by(lanes) {
-                            let l1 = Simd::<T, 8>::from_slice(&lhs2[row * inner_block_size + inner..]);
+                            let l1 = unsafe { simd_load::<T, true>(&lhs2, row * inner_block_size + inner) };
 
-                            let r1 = Simd::<T, 8>::from_slice(&rhs2[column1 * inner_block_size + inner..]);
-                            let r2 = Simd::<T, 8>::from_slice(&rhs2[column2 * inner_block_size + inner..]);
+                            let r1 = unsafe { simd_load::<T, true>(&rhs2, column1 * inner_block_size + inner) };
+                            let r2 = unsafe { simd_load::<T, true>(&rhs2, column2 * inner_block_size + inner) };
 
                             v1 += l1 * r1;
                             v2 += l1 * r2;
@@ -698,13 +711,13 @@ where
 
                     if column < column_block {
                         // inner = 0
-                        let l1 = Simd::<T, 8>::from_slice(&lhs2[row * inner_block_size..]);
-                        let r1 = Simd::<T, 8>::from_slice(&rhs2[column * inner_block_size..]);
+                        let l1 = unsafe { simd_load::<T, true>(&lhs2, row * inner_block_size) };
+                        let r1 = unsafe { simd_load::<T, true>(&rhs2, column * inner_block_size) };
                         let mut v1 = l1 * r1;
 
                         for inner in (lanes..inner_block).step_by(lanes) {
-                            let l1 = Simd::<T, 8>::from_slice(&lhs2[row * inner_block_size + inner..]);
-                            let r1 = Simd::<T, 8>::from_slice(&rhs2[column * inner_block_size + inner..]);
+                            let l1 = unsafe { simd_load::<T, true>(&lhs2, row * inner_block_size + inner) };
+                            let r1 = unsafe { simd_load::<T, true>(&rhs2, column * inner_block_size + inner) };
                             v1 += l1 * r1;
                         }
 
@@ -770,12 +783,116 @@ where
         }
     }
 
-    fn compute_gemm_impl(&self, output: &mut [T]) {
+    // Multiply LHS[m, n] with RHS[n, k] into OUT[m, k], with LHS given as LHS_T[n, m]
+    fn tn_gemm_kernel(m: usize, n: usize, k: usize, out: &mut [T], lhs_t: &[T], rhs: &[T]) {
+        let lanes = 8;
+        let k_vec = Self::prev_block(k, lanes);
+
+        for inner in 0..n {
+            for row in 0..m {
+                let l = lhs_t[inner * m + row];
+                let l1 = T::Lanes::splat(l);
+
+                let mut column = 0;
+
+                while column < k_vec {
+                    let mut v1 = T::Lanes::from_slice(&out[row * k + column..]);
+                    v1 += l1 * T::Lanes::from_slice(&rhs[inner * k + column..]);
+                    v1.copy_to_slice(&mut out[row * k + column..row * k + column + lanes]);
+
+                    column += lanes;
+                }
+
+                while column < k {
+                    out[row * k + column] += l * rhs[inner * k + column];
+
+                    column += 1;
+                }
+            }
+        }
+    }
+
+    // Multiply LHS[m, n] with RHS[n, k] into OUT[m, k], with RHS given as RHS_T[k, n]
+    fn nt_gemm_kernel(m: usize, n: usize, k: usize, out: &mut [T], lhs: &[T], rhs_t: &[T]) {
+        let lanes = 8;
+        let n_vec = Self::prev_block(n, lanes);
+
+        for row in 0..m {
+            let l = &lhs[row * n..];
+
+            for column in 0..k {
+                let r = &rhs_t[column * n..];
+
+                // Both operands are contiguous, so this is a simple dot product
+                let mut v1 = T::Lanes::splat(T::zero());
+
+                let mut inner = 0;
+
+                while inner < n_vec {
+                    v1 += T::Lanes::from_slice(&l[inner..]) * T::Lanes::from_slice(&r[inner..]);
+
+                    inner += lanes;
+                }
+
+                let mut value = Self::sum(v1.as_array());
+
+                while inner < n {
+                    value += l[inner] * r[inner];
+
+                    inner += 1;
+                }
+
+                out[row * k + column] += value;
+            }
+        }
+    }
+
+    // Convert the values of an operand to the compute type, in logical order
+    fn converted_data<Expr: EtlExpr<T>>(expr: &Expr) -> AlignedBuffer<T::Compute> {
+        let mut data = AlignedBuffer::from_workspace(padded_size(expr.size()));
+
+        for (value, converted) in expr.iter().zip(data.iter_mut()) {
+            *converted = value.to_compute();
+        }
+
+        data
+    }
+
+    // The reduced precision types are multiplied in their compute type (f32), so that the sums are
+    // accumulated in f32 and only rounded once, when the result is stored
+    fn compute_converted_gemm(&self, output: &mut [T], accumulate: bool) {
+        let lhs = Self::converted_data(&self.lhs.value);
+        let rhs = Self::converted_data(&self.rhs.value);
+        let mut out = AlignedBuffer::<T::Compute>::from_workspace(output.len());
+
         if LeftExpr::DIMENSIONS == 1 && RightExpr::DIMENSIONS == 2 {
-            // No need to zero the vector since we did that a construction
+            let lhs = MatrixView::new(&lhs, [self.lhs.value.rows()]);
+            let rhs = MatrixView::new(&rhs, [self.rhs.value.rows(), self.rhs.value.dim(1)]);
+            MulExpr::new(lhs, rhs).compute_into(&mut out);
+        } else if LeftExpr::DIMENSIONS == 2 && RightExpr::DIMENSIONS == 1 {
+            let lhs = MatrixView::new(&lhs, [self.lhs.value.rows(), self.lhs.value.dim(1)]);
+            let rhs = MatrixView::new(&rhs, [self.rhs.value.rows()]);
+            MulExpr::new(lhs, rhs).compute_into(&mut out);
+        } else {
+            let lhs = MatrixView::new(&lhs, [self.lhs.value.rows(), self.lhs.value.dim(1)]);
+            let rhs = MatrixView::new(&rhs, [self.rhs.value.rows(), self.rhs.value.dim(1)]);
+            MulExpr::new(lhs, rhs).compute_into(&mut out);
+        }
+
+        for (value, result) in output.iter_mut().zip(out.iter()) {
+            *value = if accumulate { T::from_compute(value.to_compute() + *result) } else { T::from_compute(*result) };
+        }
+    }
+
+    // When accumulate is set, the result is added to the output, otherwise the output must be zero
+    fn compute_gemm_impl(&self, output: &mut [T], accumulate: bool) {
+        if T::CONVERTED {
+            self.compute_converted_gemm(output, accumulate);
+        } else if LeftExpr::DIMENSIONS == 1 && RightExpr::DIMENSIONS == 2 {
+            // The output is either zero or accumulated into, the kernels only add to it
 
             let m = self.rhs.value.rows();
-            let n = self.rhs.value.columns();
+            let n = self.rhs.value.dim(1);
 
             let functor = |out: &mut [T], lhs: &[T], rhs: &[T]| {
                 for row in 0..m {
@@ -787,10 +904,10 @@ where
 
             forward_data_binary(output, &self.lhs.value, &self.rhs.value, functor);
         } else if LeftExpr::DIMENSIONS == 2 && RightExpr::DIMENSIONS == 1 {
-            // No need to zero the vector since we did that a construction
+            // The output is either zero or accumulated into, the kernels only add to it
 
             let m = self.lhs.value.rows();
-            let n = self.lhs.value.columns();
+            let n = self.lhs.value.dim(1);
 
             let functor = |out: &mut [T], lhs: &[T], rhs: &[T]| {
                 for row in 0..m {
@@ -801,18 +918,39 @@ where
             };
 
             forward_data_binary(output, &self.lhs.value, &self.rhs.value, functor);
-        } else if LeftExpr::DIMENSIONS == 2 && RightExpr::DIMENSIONS == 2 {
-            // No need to zero the vector since we did that a construction
+        } else if LeftExpr::DIMENSIONS == 2 && RightExpr::DIMENSIONS == 2 && LeftExpr::TRANSPOSED && !RightExpr::TRANSPOSED {
+            // The output is either zero or accumulated into, the kernels only add to it
+            // The transposed lhs is not materialized, we use the data of its sub expression
+
+            let m = self.lhs.value.rows();
+            let n = self.lhs.value.dim(1);
+            let k = self.rhs.value.dim(1);
+
+            let lhs_t = self.lhs.value.get_transposed_data();
+            let tn_gemm_kernel = |out: &mut [T], rhs: &[T]| Self::tn_gemm_kernel(m, n, k, out, lhs_t, rhs);
+            forward_data_unary(output, &self.rhs.value, tn_gemm_kernel);
+        } else if LeftExpr::DIMENSIONS == 2 && RightExpr::DIMENSIONS == 2 && !LeftExpr::TRANSPOSED && RightExpr::TRANSPOSED {
+            // The output is either zero or accumulated into, the kernels only add to it
+            // The transposed rhs is not materialized, we use the data of its sub expression
 
             let m = self.lhs.value.rows();
-            let n = self.lhs.value.columns();
-            let k = self.rhs.value.columns();
+            let n = self.lhs.value.dim(1);
+            let k = self.rhs.value.dim(1);
+
+            let rhs_t = self.rhs.value.get_transposed_data();
+            let nt_gemm_kernel = |out: &mut [T], lhs: &[T]| Self::nt_gemm_kernel(m, n, k, out, lhs, rhs_t);
+            forward_data_unary(output, &self.lhs.value, nt_gemm_kernel);
+        } else if LeftExpr::DIMENSIONS == 2 && RightExpr::DIMENSIONS == 2 {
+            let m = self.lhs.value.rows();
+            let n = self.lhs.value.dim(1);
+            let k = self.rhs.value.dim(1);
 
-            if n * m < 100 * 100 {
+            // The small kernel overwrites the output, so it cannot accumulate
+            if n * m < 100 * 100 && !accumulate {
                 let small_gemm_kernel = |out: &mut [T], lhs: &[T], rhs: &[T]| Self::small_gemm_kernel(m, n, k, out, lhs, rhs);
                 forward_data_binary(output, &self.lhs.value, &self.rhs.value, small_gemm_kernel);
             } else if n * m < 200 * 200 {
-                let medium_gemm_kernel = |out: &mut [T], lhs: &[T], rhs: &[T]| Self::medium_gemm_kernel(m, n, k, out, lhs, rhs);
+                let medium_gemm_kernel = |out: &mut [T], lhs: &[T], rhs: &[T]| Self::medium_gemm_kernel(m, n, k, out, lhs, rhs, accumulate);
                 forward_data_binary(output, &self.lhs.value, &self.rhs.value, medium_gemm_kernel);
             } else {
                 // the forwarding kernel
@@ -829,29 +967,33 @@ where
         }
     }
 
-    fn validate_gemm<OutputExpr: EtlExpr<T>>(&self, lhs: &OutputExpr) {
-        if LeftExpr::DIMENSIONS == 1 && RightExpr::DIMENSIONS == 2 {
-            if lhs.rows() != self.rhs.value.columns() {
-                panic!("Invalid dimensions for assignment of GEVM result");
-            }
+    fn validate_gemm<OutputExpr: EtlExpr<T>>(&self, lhs: &OutputExpr) -> Result<(), EtlError> {
+        let valid = if LeftExpr::DIMENSIONS == 1 && RightExpr::DIMENSIONS == 2 {
+            lhs.rows() == self.rhs.value.dim(1)
         } else if LeftExpr::DIMENSIONS == 2 && RightExpr::DIMENSIONS == 1 {
-            if lhs.rows() != self.lhs.value.rows() {
-                panic!("Invalid dimensions for assignment of GEMV result");
-            }
+            lhs.rows() == self.lhs.value.rows()
         } else if LeftExpr::DIMENSIONS == 2 && RightExpr::DIMENSIONS == 2 {
-            if lhs.rows() != self.lhs.value.rows() || lhs.columns() != self.rhs.value.columns() {
-                panic!("Invalid dimensions for assignment of GEMM result");
-            }
+            lhs.rows() == self.lhs.value.rows() && lhs.dim(1) == self.rhs.value.dim(1)
         } else {
             panic!("This code should be unreachable!");
+        };
+
+        if !valid {
+            return Err(EtlError::ShapeMismatch {
+                op: "assignment of multiplication result",
+                lhs: expr_dims(lhs),
+                rhs: expr_dims(self),
+            });
         }
+
+        Ok(())
     }
 }
 
 // MulExpr is an EtlExpr
 impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> EtlExpr<T> for MulExpr<T, LeftExpr, RightExpr>
 where
-    Simd<T, 8>: SimdHelper,
+    T: Vectorizable,
 {
     const DIMENSIONS: usize = if LeftExpr::DIMENSIONS == 2 && RightExpr::DIMENSIONS == 2 { 2 } else { 1 };
     const TYPE: EtlType = EtlType::Smart;
@@ -864,72 +1006,82 @@ where
         Self: 'x;
 
     fn iter(&self) -> Self::Iter<'_> {
-        self.temp.iter().cloned()
+        self.temp().iter().cloned()
     }
 
     fn iter_range(&self, range: std::ops::Range<usize>) -> Self::Iter<'_> {
-        self.temp[range].iter().cloned()
+        self.temp()[range].iter().cloned()
     }
 
     fn size(&self) -> usize {
         if LeftExpr::DIMENSIONS == 1 && RightExpr::DIMENSIONS == 2 {
-            self.rhs.value.columns()
+            self.rhs.value.dim(1)
         } else if LeftExpr::DIMENSIONS == 2 && RightExpr::DIMENSIONS == 1 {
             self.lhs.value.rows()
         } else {
-            self.lhs.value.rows() * self.rhs.value.columns()
+            self.lhs.value.rows() * self.rhs.value.dim(1)
         }
     }
 
     fn rows(&self) -> usize {
         if LeftExpr::DIMENSIONS == 1 && RightExpr::DIMENSIONS == 2 {
-            self.rhs.value.columns()
+            self.rhs.value.dim(1)
         } else {
             self.lhs.value.rows()
         }
     }
 
-    fn columns(&self) -> usize {
-        self.rhs.value.columns()
+    fn dim(&self, i: usize) -> usize {
+        match i {
+            0 => self.rows(),
+            1 => self.rhs.value.dim(1),
+            _ => panic!("Invalid dimension access ({i} for 2D)"),
+        }
     }
 
-    fn validate_assign<OutputExpr: EtlExpr<T>>(&self, lhs: &OutputExpr) {
-        self.validate_gemm(lhs);
+    fn try_validate_assign<OutputExpr: EtlExpr<T>>(&self, lhs: &OutputExpr) -> Result<(), EtlError> {
+        self.validate_gemm(lhs)
     }
 
-    fn compute_into(&self, output: &mut Vec<T>) {
+    fn compute_into(&self, output: &mut [T]) {
         self.compute_gemm(output);
     }
 
-    fn compute_into_add(&self, output: &mut Vec<T>) {
+    fn compute_into_add(&self, output: &mut [T]) {
         self.compute_gemm_add(output);
     }
 
-    fn compute_into_sub(&self, output: &mut Vec<T>) {
-        self.compute_gemm_sub(output);
+    fn compute_into_sub(&self, output: &mut [T]) {
+        self.temp.compute_into_sub(output, self.size(), |temp| self.compute_gemm_impl(temp, false));
     }
 
-    fn compute_into_scale(&self, output: &mut Vec<T>) {
-        self.compute_gemm_scale(output);
+    fn compute_into_scale(&self, output: &mut [T]) {
+        self.temp.compute_into_scale(output, self.size(), |temp| self.compute_gemm_impl(temp, false));
     }
 
-    fn compute_into_div(&self, output: &mut Vec<T>) {
-        self.compute_gemm_div(output);
+    fn compute_into_div(&self, output: &mut [T]) {
+        self.temp.compute_into_div(output, self.size(), |temp| self.compute_gemm_impl(temp, false));
     }
 
     fn at(&self, i: usize) -> T {
-        self.temp[i]
+        self.temp()[i]
     }
 
     fn get_data(&self) -> &[T] {
-        &self.temp
+        self.temp()
     }
 }
 
+// MulExpr is an Etl2d when both its operands are
+impl<T: EtlValueType, LeftExpr: WrappableExpr<T> + Etl2d<T>, RightExpr: WrappableExpr<T> + Etl2d<T>> Etl2d<T> for MulExpr<T, LeftExpr, RightExpr> where
+    T: Vectorizable
+{
+}
+
 // MulExpr is an EtlWrappable
 impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> EtlWrappable<T> for MulExpr<T, LeftExpr, RightExpr>
 where
-    Simd<T, 8>: SimdHelper,
+    T: Vectorizable,
 {
     type WrappedAs = MulExpr<T, LeftExpr, RightExpr>;
 
@@ -944,15 +1096,22 @@ where
 // MulExpr computes as copy
 impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> EtlComputable<T> for MulExpr<T, LeftExpr, RightExpr>
 where
-    Simd<T, 8>: SimdHelper,
+    T: Vectorizable,
 {
     fn to_data(&self) -> Vec<T> {
-        self.temp.clone()
+        self.temp().to_vec()
     }
 }
 
 // Operations
 
+pub fn try_mul<T: EtlValueType + Vectorizable, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>>(
+    lhs: LeftExpr,
+    rhs: RightExpr,
+) -> Result<MulExpr<T, LeftExpr, RightExpr>, EtlError> {
+    MulExpr::<T, LeftExpr, RightExpr>::try_new(lhs, rhs)
+}
+
 // Unfortunately, because of the Orphan rule, we cannot implement this trait for each structure
 // implementing EtlExpr
 // Therefore, we provide macros for other structures and expressions
@@ -963,10 +1122,10 @@ where
 
 #[macro_export]
 macro_rules! impl_mul_op_value {
-    ($type:ty) => {
-        impl<'a, T: EtlValueType, RightExpr: WrappableExpr<T>> std::ops::Mul<RightExpr> for &'a $type
+    ($type:ty $(, const $dim:ident)*) => {
+        impl<'a, T: EtlValueType, $(const $dim: usize,)* RightExpr: WrappableExpr<T>> std::ops::Mul<RightExpr> for &'a $type
         where
-            std::simd::Simd<T, 8>: $crate::base_traits::SimdHelper,
+            T: $crate::base_traits::Vectorizable,
         {
             type Output = $crate::mul_expr::MulExpr<T, &'a $type, RightExpr>;
 
@@ -982,7 +1141,7 @@ macro_rules! impl_mul_op_binary_expr {
     ($type:ty) => {
         impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>, OuterRightExpr: WrappableExpr<T>> std::ops::Mul<OuterRightExpr> for $type
         where
-            std::simd::Simd<T, 8>: $crate::base_traits::SimdHelper,
+            T: $crate::base_traits::Vectorizable,
         {
             type Output = $crate::mul_expr::MulExpr<T, $type, OuterRightExpr>;
 
@@ -999,7 +1158,7 @@ macro_rules! impl_mul_op_binary_expr_simd {
     ($type:ty) => {
         impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>, OuterRightExpr: WrappableExpr<T>> std::ops::Mul<OuterRightExpr> for $type
         where
-            std::simd::Simd<T, 8>: $crate::base_traits::SimdHelper,
+            T: $crate::base_traits::Vectorizable,
         {
             type Output = $crate::mul_expr::MulExpr<T, $type, OuterRightExpr>;
 
@@ -1015,7 +1174,7 @@ macro_rules! impl_mul_op_unary_expr {
     ($type:ty) => {
         impl<T: EtlValueType, Expr: WrappableExpr<T>, OuterRightExpr: WrappableExpr<T>> std::ops::Mul<OuterRightExpr> for $type
         where
-            std::simd::Simd<T, 8>: $crate::base_traits::SimdHelper,
+            T: $crate::base_traits::Vectorizable,
         {
             type Output = $crate::mul_expr::MulExpr<T, $type, OuterRightExpr>;
 
@@ -1031,7 +1190,7 @@ macro_rules! impl_mul_op_unary_expr_trait {
     ($trait:tt, $type:ty) => {
         impl<T: EtlValueType + $trait, Expr: WrappableExpr<T>, OuterRightExpr: WrappableExpr<T>> std::ops::Mul<OuterRightExpr> for $type
         where
-            std::simd::Simd<T, 8>: $crate::base_traits::SimdHelper,
+            T: $crate::base_traits::Vectorizable,
         {
             type Output = $crate::mul_expr::MulExpr<T, $type, OuterRightExpr>;
 
@@ -1052,9 +1211,14 @@ crate::impl_scale_op_binary_expr_simd!(MulExpr<T, LeftExpr, RightExpr>);
 
 #[cfg(test)]
 mod tests {
+    use crate::complex::Complex;
     use crate::constant::cst;
-    use crate::etl_expr::EtlExpr;
+    use crate::etl_error::EtlError;
+    use crate::etl_expr::{Etl2d, EtlExpr};
+    use crate::half_float::{bf16, f16};
     use crate::matrix_2d::Matrix2d;
+    use crate::mul_expr::{MulExpr, try_mul};
+    use crate::transpose_expr::transpose;
     use crate::vector::Vector;
 
     #[test]
@@ -1346,7 +1510,7 @@ mod tests {
         let expr = &a * &b;
         assert_eq!(expr.size(), 24);
         assert_eq!(expr.rows(), 4);
-        assert_eq!(expr.columns(), 6);
+        assert_eq!(expr.dim(1), 6);
 
         c |= expr;
         assert_eq!(c.at2(0, 0), 48);
@@ -1459,4 +1623,289 @@ mod tests {
             assert_eq!(c.at(i), c_ref.at(i), "Invalid value at index {i}");
         }
     }
+
+    fn gemm_reference(lhs: &Matrix2d<i64>, rhs: &Matrix2d<i64>) -> Matrix2d<i64> {
+        let mut c_ref = Matrix2d::<i64>::new(lhs.rows(), rhs.dim(1));
+
+        for row in 0..lhs.rows() {
+            for column in 0..rhs.dim(1) {
+                let mut v = 0;
+                for inner in 0..lhs.dim(1) {
+                    v += lhs.at2(row, inner) * rhs.at2(inner, column);
+                }
+                *c_ref.at_mut(row, column) = v;
+            }
+        }
+
+        c_ref
+    }
+
+    #[test]
+    fn gemm_tn() {
+        let (m, n, k) = (17, 11, 39);
+
+        let lhs_t = Matrix2d::<i64>::new_iota(n, m, 1);
+        let rhs = Matrix2d::<i64>::new_iota(n, k, 2);
+
+        let mut c = Matrix2d::<i64>::new(m, k);
+        c |= transpose(&lhs_t) * &rhs;
+
+        let c_ref = gemm_reference(&Matrix2d::new_from_expr(transpose(&lhs_t)), &rhs);
+
+        for i in 0..(m * k) {
+            assert_eq!(c.at(i), c_ref.at(i), "Invalid value at index {i}");
+        }
+    }
+
+    #[test]
+    fn gemm_nt() {
+        let (m, n, k) = (13, 21, 9);
+
+        let lhs = Matrix2d::<i64>::new_iota(m, n, 1);
+        let rhs_t = Matrix2d::<i64>::new_iota(k, n, 2);
+
+        let mut c = Matrix2d::<i64>::new(m, k);
+        c |= &lhs * transpose(&rhs_t);
+
+        let c_ref = gemm_reference(&lhs, &Matrix2d::new_from_expr(transpose(&rhs_t)));
+
+        for i in 0..(m * k) {
+            assert_eq!(c.at(i), c_ref.at(i), "Invalid value at index {i}");
+        }
+    }
+
+    #[test]
+    fn gemm_tt() {
+        let (m, n, k) = (5, 7, 3);
+
+        let lhs_t = Matrix2d::<i64>::new_iota(n, m, 1);
+        let rhs_t = Matrix2d::<i64>::new_iota(k, n, 2);
+
+        let mut c = Matrix2d::<i64>::new(m, k);
+        c |= transpose(&lhs_t) * transpose(&rhs_t);
+
+        let c_ref = gemm_reference(&Matrix2d::new_from_expr(transpose(&lhs_t)), &Matrix2d::new_from_expr(transpose(&rhs_t)));
+
+        for i in 0..(m * k) {
+            assert_eq!(c.at(i), c_ref.at(i), "Invalid value at index {i}");
+        }
+    }
+
+    #[test]
+    fn gemv_t() {
+        let a = Matrix2d::<i64>::new_iota(3, 2, 1);
+        let b = Vector::<i64>::new_iota(3, 1);
+
+        let mut c = Vector::<i64>::new(2);
+        c |= transpose(&a) * &b;
+
+        assert_eq!(c.at(0), 22);
+        assert_eq!(c.at(1), 28);
+    }
+
+    #[test]
+    fn gemm_deferred() {
+        let a = Matrix2d::<i64>::new_iota(2, 3, 1);
+        let b = Matrix2d::<i64>::new_iota(3, 2, 7);
+
+        let expr: MulExpr<i64, &Matrix2d<i64>, &Matrix2d<i64>> = &a * &b;
+        assert!(expr.temp.get().is_none());
+
+        let mut c = Matrix2d::<i64>::new(2, 2);
+        c.fill(42);
+        c |= expr.clone();
+
+        assert!(expr.temp.get().is_none());
+        assert_eq!(c.at2(0, 0), 58);
+        assert_eq!(c.at2(1, 1), 154);
+
+        // Reading the expression computes the temporary once
+        assert_eq!(expr.at(3), 154);
+        assert!(expr.temp.get().is_some());
+
+        c |= expr;
+        assert_eq!(c.at2(0, 1), 64);
+    }
+
+    #[test]
+    fn gemm_compound_add_sizes() {
+        for (m, n, k) in [(7, 5, 19), (120, 150, 33), (120, 150, 32), (210, 220, 41), (210, 220, 48)] {
+            let lhs = Matrix2d::<i64>::new_iota(m, n, 1);
+            let rhs = Matrix2d::<i64>::new_iota(n, k, 2);
+
+            let mut c = Matrix2d::<i64>::new_iota(m, k, 3);
+            c += &lhs * &rhs;
+
+            let c_ref = gemm_reference(&lhs, &rhs);
+
+            for i in 0..(m * k) {
+                assert_eq!(c.at(i), c_ref.at(i) + 3 + i as i64, "Invalid value at index {i} for {m}x{n}x{k}");
+            }
+
+            c |= &lhs * &rhs;
+
+            for i in 0..(m * k) {
+                assert_eq!(c.at(i), c_ref.at(i), "Invalid value at index {i} for {m}x{n}x{k}");
+            }
+        }
+    }
+
+    #[test]
+    fn gemv_compound_add() {
+        let a = Matrix2d::<i64>::new_iota(2, 3, 1);
+        let b = Vector::<i64>::new_iota(3, 1);
+
+        let mut c = Vector::<i64>::new(2);
+        c.fill(100);
+        c += &a * &b;
+
+        assert_eq!(c.at(0), 114);
+        assert_eq!(c.at(1), 132);
+
+        c |= &b * transpose(&a);
+
+        assert_eq!(c.at(0), 14);
+        assert_eq!(c.at(1), 32);
+    }
+
+    #[test]
+    fn try_mul_invalid() {
+        let a = Matrix2d::<i64>::new_iota(2, 3, 1);
+        let b = Matrix2d::<i64>::new_iota(2, 3, 1);
+
+        let error = try_mul(&a, &b).err().unwrap();
+        assert_eq!(
+            error,
+            EtlError::ShapeMismatch {
+                op: "matrix matrix multiplication",
+                lhs: vec![2, 3],
+                rhs: vec![2, 3]
+            }
+        );
+
+        let mut c = Matrix2d::<i64>::new(3, 3);
+        assert!(c.try_assign(try_mul(&a, transpose(&b)).unwrap()).is_err());
+
+        let mut d = Matrix2d::<i64>::new(2, 2);
+        assert!(d.try_assign(try_mul(&a, transpose(&b)).unwrap()).is_ok());
+        assert_eq!(d.at2(0, 0), 14);
+        assert_eq!(d.at2(1, 1), 77);
+    }
+
+    #[test]
+    fn gemm_complex() {
+        // Small integer values keep the results exact, one size for each kernel
+        for (m, n, k) in [(3, 4, 5), (120, 150, 33), (210, 220, 41)] {
+            let mut lhs = Matrix2d::<Complex<f64>>::new(m, n);
+            let mut rhs = Matrix2d::<Complex<f64>>::new(n, k);
+
+            for i in 0..(m * n) {
+                lhs[i] = Complex::new((i % 7) as f64, (i % 5) as f64 - 2.0);
+            }
+
+            for i in 0..(n * k) {
+                rhs[i] = Complex::new((i % 3) as f64 - 1.0, (i % 4) as f64);
+            }
+
+            let mut c = Matrix2d::<Complex<f64>>::new(m, k);
+            c |= &lhs * &rhs;
+
+            for row in 0..m {
+                for column in 0..k {
+                    let mut v = Complex::new(0.0, 0.0);
+                    for inner in 0..n {
+                        v += lhs.at2(row, inner) * rhs.at2(inner, column);
+                    }
+                    assert_eq!(c.at2(row, column), v, "Invalid value at ({row}, {column}) for {m}x{n}x{k}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn gemm_f16_accumulates_in_f32() {
+        // A sum of ones computed in f16 stops growing at 2048
+        let mut lhs = Matrix2d::<f16>::new(4, 3000);
+        let mut rhs = Matrix2d::<f16>::new(3000, 5);
+        let mut v = Vector::<f16>::new(3000);
+
+        lhs |= cst(f16::ONE);
+        rhs |= cst(f16::ONE);
+        v |= cst(f16::ONE);
+
+        let mut c = Matrix2d::<f16>::new(4, 5);
+        c |= &lhs * &rhs;
+
+        assert!((0..c.size()).all(|i| c.at(i).to_f32() == 3000.0));
+
+        c += &lhs * &rhs;
+
+        assert!((0..c.size()).all(|i| c.at(i).to_f32() == 6000.0));
+
+        let mut d = Vector::<f16>::new(5);
+        d |= &v * &rhs;
+
+        assert!((0..d.size()).all(|i| d.at(i).to_f32() == 3000.0));
+        assert_eq!((&lhs * &v).at(3).to_f32(), 3000.0);
+    }
+
+    #[test]
+    fn gemm_bf16() {
+        // The values are exact in bf16, only the rounding of the results differs from f32
+        for (m, n, k) in [(3, 4, 5), (120, 150, 33), (210, 220, 41)] {
+            let mut lhs = Matrix2d::<bf16>::new(m, n);
+            let mut rhs = Matrix2d::<bf16>::new(n, k);
+
+            for i in 0..(m * n) {
+                lhs[i] = bf16::from_f32((i % 7) as f32 - 3.0);
+            }
+
+            for i in 0..(n * k) {
+                rhs[i] = bf16::from_f32((i % 5) as f32 * 0.25);
+            }
+
+            let mut c = Matrix2d::<bf16>::new(m, k);
+            c |= &lhs * &rhs;
+
+            for row in 0..m {
+                for column in 0..k {
+                    let mut v = 0.0f32;
+                    for inner in 0..n {
+                        v += lhs.at2(row, inner).to_f32() * rhs.at2(inner, column).to_f32();
+                    }
+                    assert_eq!(c.at2(row, column), bf16::from_f32(v), "Invalid value at ({row}, {column}) for {m}x{n}x{k}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn gemm_unsigned() {
+        // One size for each kernel
+        for (m, n, k) in [(3, 4, 5), (120, 150, 33), (210, 220, 41)] {
+            let mut lhs = Matrix2d::<u32>::new(m, n);
+            let mut rhs = Matrix2d::<u32>::new(n, k);
+
+            for i in 0..(m * n) {
+                lhs[i] = (i % 7) as u32;
+            }
+
+            for i in 0..(n * k) {
+                rhs[i] = (i % 5) as u32;
+            }
+
+            let mut c = Matrix2d::<u32>::new(m, k);
+            c |= &lhs * &rhs;
+
+            for row in 0..m {
+                for column in 0..k {
+                    let mut v = 0;
+                    for inner in 0..n {
+                        v += lhs.at2(row, inner) * rhs.at2(inner, column);
+                    }
+                    assert_eq!(c.at2(row, column), v, "Invalid value at ({row}, {column}) for {m}x{n}x{k}");
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,756 @@
+use crate::etl_error::EtlError;
+use crate::etl_expr::*;
+use crate::matrix_2d::Matrix2d;
+use crate::matrix_3d::Matrix3d;
+use crate::matrix_4d::Matrix4d;
+use crate::tensor::Tensor;
+use crate::vector::Vector;
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+// Reading and writing of the NumPy formats
+//
+// The .npy format is a small header describing the array (a Python dict literal with the dtype,
+// the order and the shape) followed by the raw values. The .npz format is a zip archive of .npy
+// files, only the uncompressed archives (np.savez) are supported.
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+
+/// The value types that can be stored in .npy files
+pub trait NpyType: EtlValueType {
+    /// The kind of the dtype (f for floating point values, i for signed integers)
+    const KIND: char;
+
+    fn from_bytes(bytes: &[u8], little_endian: bool) -> Self;
+    fn append_le_bytes(self, out: &mut Vec<u8>);
+}
+
+macro_rules! impl_npy_type {
+    ($type:ty, $kind:literal) => {
+        impl NpyType for $type {
+            const KIND: char = $kind;
+
+            fn from_bytes(bytes: &[u8], little_endian: bool) -> Self {
+                let bytes = bytes.try_into().unwrap();
+                if little_endian { <$type>::from_le_bytes(bytes) } else { <$type>::from_be_bytes(bytes) }
+            }
+
+            fn append_le_bytes(self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+        }
+    };
+}
+
+impl_npy_type!(f32, 'f');
+impl_npy_type!(f64, 'f');
+impl_npy_type!(i32, 'i');
+impl_npy_type!(i64, 'i');
+
+/// An array read from a .npy file, the values are always in row-major (C) order
+#[derive(Debug, Clone, PartialEq)]
+pub struct NpyArray<T> {
+    pub shape: Vec<usize>,
+    pub data: Vec<T>,
+}
+
+fn invalid(message: String) -> EtlError {
+    EtlError::InvalidFormat { format: "npy", message }
+}
+
+// The value of a key of the header dict, with everything that follows it
+fn header_value<'a>(header: &'a str, key: &str) -> Result<&'a str, EtlError> {
+    for quote in ['\'', '"'] {
+        let pattern = format!("{quote}{key}{quote}");
+
+        if let Some(start) = header.find(&pattern) {
+            let rest = header[start + pattern.len()..].trim_start();
+            if let Some(value) = rest.strip_prefix(':') {
+                return Ok(value.trim_start());
+            }
+        }
+    }
+
+    Err(invalid(format!("the header does not contain {key}")))
+}
+
+fn parse_descr(header: &str) -> Result<&str, EtlError> {
+    let value = header_value(header, "descr")?;
+
+    let quote = value.chars().next().filter(|c| *c == '\'' || *c == '"');
+    let end = quote.and_then(|quote| value[1..].find(quote));
+
+    match end {
+        Some(end) => Ok(&value[1..end + 1]),
+        None => Err(invalid("the descr of the header is not a string".to_string())),
+    }
+}
+
+fn parse_fortran_order(header: &str) -> Result<bool, EtlError> {
+    let value = header_value(header, "fortran_order")?;
+
+    if value.starts_with("True") {
+        Ok(true)
+    } else if value.starts_with("False") {
+        Ok(false)
+    } else {
+        Err(invalid("the fortran_order of the header is not a boolean".to_string()))
+    }
+}
+
+fn parse_shape(header: &str) -> Result<Vec<usize>, EtlError> {
+    let value = header_value(header, "shape")?;
+
+    let tuple = value.strip_prefix('(').and_then(|value| value.split_once(')')).map(|(tuple, _)| tuple);
+    let Some(tuple) = tuple else {
+        return Err(invalid("the shape of the header is not a tuple".to_string()));
+    };
+
+    tuple
+        .split(',')
+        .map(str::trim)
+        .filter(|dim| !dim.is_empty())
+        .map(|dim| {
+            dim.trim_end_matches('L')
+                .parse()
+                .map_err(|_| invalid(format!("invalid dimension {dim} in the shape")))
+        })
+        .collect()
+}
+
+// Returns if the values are stored in little endian, or an error if the dtype is not T
+fn check_descr<T: NpyType>(descr: &str) -> Result<bool, EtlError> {
+    let expected = format!("{}{}", T::KIND, std::mem::size_of::<T>());
+
+    // The byte order is a single character, but the descr may start with any character
+    let mut chars = descr.chars();
+    let order = chars.next();
+    let dtype = chars.as_str();
+
+    if dtype != expected {
+        return Err(invalid(format!("the dtype {descr} does not match the element type ({expected})")));
+    }
+
+    match order {
+        Some('<') => Ok(true),
+        Some('>') => Ok(false),
+        Some('=') => Ok(cfg!(target_endian = "little")),
+        _ => Err(invalid(format!("invalid byte order in the dtype {descr}"))),
+    }
+}
+
+// Convert the column-major values to row-major
+fn fortran_to_c<T: Copy + Default>(shape: &[usize], values: impl ExactSizeIterator<Item = T>) -> Vec<T> {
+    // The row-major strides, the last index varies the fastest
+    let mut strides = vec![1; shape.len()];
+    for d in (0..shape.len() - 1).rev() {
+        strides[d] = strides[d + 1] * shape[d + 1];
+    }
+
+    let mut result = vec![T::default(); values.len()];
+
+    let mut index = vec![0; shape.len()];
+    let mut offset = 0;
+
+    for value in values {
+        result[offset] = value;
+
+        // The first index varies the fastest in column-major
+        for d in 0..shape.len() {
+            index[d] += 1;
+            offset += strides[d];
+
+            if index[d] < shape[d] {
+                break;
+            }
+
+            offset -= index[d] * strides[d];
+            index[d] = 0;
+        }
+    }
+
+    result
+}
+
+/// Read an array from a stream in the .npy format
+pub fn read_npy<T: NpyType, R: Read>(reader: &mut R) -> Result<NpyArray<T>, EtlError> {
+    let mut preamble = [0u8; 8];
+    reader.read_exact(&mut preamble)?;
+
+    if &preamble[..6] != MAGIC {
+        return Err(invalid("the magic string is missing".to_string()));
+    }
+
+    let header_len = match preamble[6] {
+        1 => {
+            let mut len = [0u8; 2];
+            reader.read_exact(&mut len)?;
+            u16::from_le_bytes(len) as usize
+        }
+        2 | 3 => {
+            let mut len = [0u8; 4];
+            reader.read_exact(&mut len)?;
+            u32::from_le_bytes(len) as usize
+        }
+        version => return Err(invalid(format!("unsupported version {version}"))),
+    };
+
+    let mut header = vec![0u8; header_len];
+    reader.read_exact(&mut header)?;
+    let header = String::from_utf8(header).map_err(|_| invalid("the header is not valid text".to_string()))?;
+
+    let little_endian = check_descr::<T>(parse_descr(&header)?)?;
+    let fortran_order = parse_fortran_order(&header)?;
+    let shape = parse_shape(&header)?;
+
+    let size = std::mem::size_of::<T>();
+
+    // The shape comes from the file, the number of bytes must not overflow
+    let Some(len) = shape.iter().try_fold(size, |len, &dim| len.checked_mul(dim)) else {
+        return Err(invalid(format!("the shape {shape:?} is too large")));
+    };
+
+    // The buffer only grows with the bytes actually read, a corrupted shape cannot allocate
+    let mut bytes = Vec::new();
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+
+    if bytes.len() < len {
+        return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+    }
+
+    let values = bytes.chunks_exact(size).map(|bytes| T::from_bytes(bytes, little_endian));
+    let data = if fortran_order && shape.len() > 1 { fortran_to_c(&shape, values) } else { values.collect() };
+
+    Ok(NpyArray { shape, data })
+}
+
+/// Write an array to a stream in the .npy format (version 1.0, little endian, C order)
+pub fn write_npy<T: NpyType, W: Write>(writer: &mut W, shape: &[usize], data: &[T]) -> Result<(), EtlError> {
+    let count = shape.iter().try_fold(1usize, |count, dim| count.checked_mul(*dim));
+    let Some(count) = count.filter(|count| *count <= data.len()) else {
+        return Err(EtlError::ShapeMismatch {
+            op: "writing of npy",
+            lhs: shape.to_vec(),
+            rhs: vec![data.len()],
+        });
+    };
+
+    // A tuple of a single element needs a trailing comma
+    let mut dims = shape.iter().map(|dim| dim.to_string()).collect::<Vec<_>>().join(", ");
+    if shape.len() == 1 {
+        dims.push(',');
+    }
+
+    let mut header = format!("{{'descr': '<{}{}', 'fortran_order': False, 'shape': ({dims}), }}", T::KIND, std::mem::size_of::<T>());
+
+    // The values start on a multiple of 64 bytes, and the header ends with a new line
+    let unpadded = MAGIC.len() + 2 + 2 + header.len() + 1;
+    header.push_str(&" ".repeat((64 - unpadded % 64) % 64));
+    header.push('\n');
+
+    let mut bytes = Vec::with_capacity(MAGIC.len() + 4 + header.len() + count * std::mem::size_of::<T>());
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&[1, 0]);
+    bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(header.as_bytes());
+
+    for value in &data[..count] {
+        value.append_le_bytes(&mut bytes);
+    }
+
+    writer.write_all(&bytes)?;
+    Ok(())
+}
+
+// The .npz archives
+
+fn invalid_npz(message: String) -> EtlError {
+    EtlError::InvalidFormat { format: "npz", message }
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Result<u16, EtlError> {
+    match offset.checked_add(2).and_then(|end| bytes.get(offset..end)) {
+        Some(value) => Ok(u16::from_le_bytes(value.try_into().unwrap())),
+        None => Err(invalid_npz("the archive is truncated".to_string())),
+    }
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, EtlError> {
+    match offset.checked_add(4).and_then(|end| bytes.get(offset..end)) {
+        Some(value) => Ok(u32::from_le_bytes(value.try_into().unwrap())),
+        None => Err(invalid_npz("the archive is truncated".to_string())),
+    }
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> Result<u64, EtlError> {
+    match offset.checked_add(8).and_then(|end| bytes.get(offset..end)) {
+        Some(value) => Ok(u64::from_le_bytes(value.try_into().unwrap())),
+        None => Err(invalid_npz("the archive is truncated".to_string())),
+    }
+}
+
+// The values of the central directory that do not fit in 32 bits are in the zip64 extra field,
+// in this order and only when their 32 bits value is saturated
+fn zip64_values(extra: &[u8], values: &mut [u64]) -> Result<(), EtlError> {
+    let mut offset = 0;
+
+    while offset + 4 <= extra.len() {
+        let id = read_u16(extra, offset)?;
+        let size = read_u16(extra, offset + 2)? as usize;
+
+        if id == 1 {
+            let mut field = offset + 4;
+            for value in values.iter_mut().filter(|value| **value == u32::MAX as u64) {
+                *value = read_u64(extra, field)?;
+                field += 8;
+            }
+            return Ok(());
+        }
+
+        offset += 4 + size;
+    }
+
+    Ok(())
+}
+
+// Find the data of the entry name.npy (or name) of a zip archive
+fn npz_entry<'a>(archive: &'a [u8], name: &str) -> Result<&'a [u8], EtlError> {
+    // The end of central directory record is at the end, followed by a comment of at most 64KiB
+    const END_SIGNATURE: u32 = 0x06054b50;
+
+    let last = archive
+        .len()
+        .checked_sub(22)
+        .ok_or_else(|| invalid_npz("the file is too small for a zip archive".to_string()))?;
+    let end = (last.saturating_sub(u16::MAX as usize)..=last)
+        .rev()
+        .find(|offset| read_u32(archive, *offset).ok() == Some(END_SIGNATURE))
+        .ok_or_else(|| invalid_npz("the end of the central directory is missing".to_string()))?;
+
+    let entries = read_u16(archive, end + 10)? as usize;
+    let mut offset = read_u32(archive, end + 16)? as usize;
+
+    if offset == u32::MAX as usize {
+        return Err(invalid_npz("zip64 archives with a large central directory are not supported".to_string()));
+    }
+
+    let file_name = format!("{name}.npy");
+
+    for _ in 0..entries {
+        if read_u32(archive, offset)? != 0x02014b50 {
+            return Err(invalid_npz("invalid central directory".to_string()));
+        }
+
+        let method = read_u16(archive, offset + 10)?;
+        let name_len = read_u16(archive, offset + 28)? as usize;
+        let extra_len = read_u16(archive, offset + 30)? as usize;
+        let comment_len = read_u16(archive, offset + 32)? as usize;
+
+        let entry_name = archive
+            .get(offset + 46..offset + 46 + name_len)
+            .ok_or_else(|| invalid_npz("the archive is truncated".to_string()))?;
+
+        if entry_name == file_name.as_bytes() || entry_name == name.as_bytes() {
+            if method != 0 {
+                return Err(invalid_npz(format!("{name} is compressed, only uncompressed archives (np.savez) are supported")));
+            }
+
+            // Uncompressed size, compressed size and offset of the local header
+            let mut values = [
+                read_u32(archive, offset + 24)? as u64,
+                read_u32(archive, offset + 20)? as u64,
+                read_u32(archive, offset + 42)? as u64,
+            ];
+            let extra = &archive[offset + 46 + name_len..(offset + 46 + name_len + extra_len).min(archive.len())];
+            zip64_values(extra, &mut values)?;
+
+            // The values are not trusted, they may point anywhere
+            let [_, size, local] = values.map(|value| usize::try_from(value).unwrap_or(usize::MAX));
+
+            if read_u32(archive, local)? != 0x04034b50 {
+                return Err(invalid_npz("invalid local header".to_string()));
+            }
+
+            // The local header is in the archive, so its end cannot overflow
+            let start = local + 30 + read_u16(archive, local + 26)? as usize + read_u16(archive, local + 28)? as usize;
+            return start
+                .checked_add(size)
+                .and_then(|end| archive.get(start..end))
+                .ok_or_else(|| invalid_npz("the archive is truncated".to_string()));
+        }
+
+        offset += 46 + name_len + extra_len + comment_len;
+    }
+
+    Err(invalid_npz(format!("the archive does not contain {name}")))
+}
+
+/// Read the array name of an uncompressed .npz archive
+pub fn read_npz<T: NpyType, P: AsRef<Path>>(path: P, name: &str) -> Result<NpyArray<T>, EtlError> {
+    let archive = std::fs::read(path)?;
+    let mut entry = npz_entry(&archive, name)?;
+    read_npy(&mut entry)
+}
+
+// The containers can be loaded from and saved to .npy files
+
+fn check_rank<T>(array: &NpyArray<T>, op: &'static str, rank: usize) -> Result<(), EtlError> {
+    if array.shape.len() != rank {
+        return Err(EtlError::RankMismatch {
+            op,
+            expected: rank,
+            actual: array.shape.len(),
+        });
+    }
+
+    Ok(())
+}
+
+fn save_npy<T: NpyType, Expr: EtlExpr<T>, P: AsRef<Path>>(expr: &Expr, data: &[T], path: P) -> Result<(), EtlError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_npy(&mut writer, &expr_dims(expr), data)?;
+    writer.flush()?;
+    Ok(())
+}
+
+macro_rules! impl_npy_container {
+    ($type:ident, $rank:literal, $op:literal, $shape:ident => $new:expr) => {
+        impl<T: NpyType> $type<T> {
+            /// Load a container from a .npy file
+            pub fn load_npy<P: AsRef<Path>>(path: P) -> Result<Self, EtlError> {
+                let mut reader = BufReader::new(File::open(path)?);
+                Self::from_npy_array(read_npy(&mut reader)?)
+            }
+
+            /// Load a container from the array name of an uncompressed .npz archive
+            pub fn load_npz<P: AsRef<Path>>(path: P, name: &str) -> Result<Self, EtlError> {
+                Self::from_npy_array(read_npz(path, name)?)
+            }
+
+            /// Save the container to a .npy file
+            pub fn save_npy<P: AsRef<Path>>(&self, path: P) -> Result<(), EtlError> {
+                save_npy(self, &self.data, path)
+            }
+
+            fn from_npy_array(array: NpyArray<T>) -> Result<Self, EtlError> {
+                check_rank(&array, $op, $rank)?;
+
+                let $shape = &array.shape;
+                let mut container = $new;
+                container.data[..array.data.len()].copy_from_slice(&array.data);
+                Ok(container)
+            }
+        }
+    };
+}
+
+impl_npy_container!(Vector, 1, "loading of Vector", shape => Vector::new(shape[0]));
+impl_npy_container!(Matrix2d, 2, "loading of Matrix2d", shape => Matrix2d::new(shape[0], shape[1]));
+impl_npy_container!(Matrix3d, 3, "loading of Matrix3d", shape => Matrix3d::new(shape[0], shape[1], shape[2]));
+impl_npy_container!(Matrix4d, 4, "loading of Matrix4d", shape => Matrix4d::new(shape[0], shape[1], shape[2], shape[3]));
+
+impl<T: NpyType, const D: usize> Tensor<T, D> {
+    /// Load a tensor from a .npy file
+    pub fn load_npy<P: AsRef<Path>>(path: P) -> Result<Self, EtlError> {
+        let mut reader = BufReader::new(File::open(path)?);
+        Self::from_npy_array(read_npy(&mut reader)?)
+    }
+
+    /// Load a tensor from the array name of an uncompressed .npz archive
+    pub fn load_npz<P: AsRef<Path>>(path: P, name: &str) -> Result<Self, EtlError> {
+        Self::from_npy_array(read_npz(path, name)?)
+    }
+
+    /// Save the tensor to a .npy file
+    pub fn save_npy<P: AsRef<Path>>(&self, path: P) -> Result<(), EtlError> {
+        save_npy(self, &self.data, path)
+    }
+
+    fn from_npy_array(array: NpyArray<T>) -> Result<Self, EtlError> {
+        check_rank(&array, "loading of Tensor", D)?;
+
+        let mut tensor = Tensor::new(std::array::from_fn(|d| array.shape[d]));
+        tensor.data[..array.data.len()].copy_from_slice(&array.data);
+        Ok(tensor)
+    }
+}
+
+// The tests
+
+#[cfg(test)]
+mod tests {
+    use crate::etl_error::EtlError;
+    use crate::etl_expr::{Etl2d, Etl3d, Etl4d, EtlExpr};
+    use crate::matrix_2d::Matrix2d;
+    use crate::matrix_3d::Matrix3d;
+    use crate::matrix_4d::Matrix4d;
+    use crate::npy::*;
+    use crate::tensor::Tensor;
+    use crate::vector::Vector;
+
+    use std::path::PathBuf;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("etl_npy_{}_{name}", std::process::id()))
+    }
+
+    // A .npy file as written by numpy, with a version 1.0 header
+    fn npy_bytes(header: &str, values: &[u8]) -> Vec<u8> {
+        let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+        bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        bytes.extend_from_slice(values);
+        bytes
+    }
+
+    // An uncompressed zip archive, the local headers use zip64 like np.savez
+    fn zip_bytes(entries: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut archive = Vec::new();
+        let mut directory = Vec::new();
+
+        for (name, data) in entries {
+            let offset = archive.len() as u32;
+
+            archive.extend_from_slice(&0x04034b50u32.to_le_bytes());
+            archive.extend_from_slice(&[45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+            archive.extend_from_slice(&u32::MAX.to_le_bytes());
+            archive.extend_from_slice(&u32::MAX.to_le_bytes());
+            archive.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            archive.extend_from_slice(&20u16.to_le_bytes());
+            archive.extend_from_slice(name.as_bytes());
+            archive.extend_from_slice(&1u16.to_le_bytes());
+            archive.extend_from_slice(&16u16.to_le_bytes());
+            archive.extend_from_slice(&(data.len() as u64).to_le_bytes());
+            archive.extend_from_slice(&(data.len() as u64).to_le_bytes());
+            archive.extend_from_slice(data);
+
+            directory.extend_from_slice(&0x02014b50u32.to_le_bytes());
+            directory.extend_from_slice(&[45, 0, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+            directory.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            directory.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            directory.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            directory.extend_from_slice(&[0; 12]);
+            directory.extend_from_slice(&offset.to_le_bytes());
+            directory.extend_from_slice(name.as_bytes());
+        }
+
+        let offset = archive.len() as u32;
+        archive.extend_from_slice(&directory);
+        archive.extend_from_slice(&0x06054b50u32.to_le_bytes());
+        archive.extend_from_slice(&[0, 0, 0, 0]);
+        archive.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        archive.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        archive.extend_from_slice(&(directory.len() as u32).to_le_bytes());
+        archive.extend_from_slice(&offset.to_le_bytes());
+        archive.extend_from_slice(&[0, 0]);
+        archive
+    }
+
+    #[test]
+    fn round_trip() {
+        let path = temp_path("round_trip.npy");
+
+        let a = Matrix2d::<f32>::new_iota(3, 5, 1.5);
+        a.save_npy(&path).unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(&bytes[..8], b"\x93NUMPY\x01\x00");
+        assert_eq!((10 + u16::from_le_bytes([bytes[8], bytes[9]]) as usize) % 64, 0);
+        assert_eq!(bytes.len(), 128 + 15 * 4);
+
+        let b = Matrix2d::<f32>::load_npy(&path).unwrap();
+        assert_eq!(b.rows(), 3);
+        assert_eq!(b.columns(), 5);
+        assert_eq!(b.at2(2, 4), 15.5);
+
+        let v = Vector::<i64>::new_iota(7, -3);
+        v.save_npy(&path).unwrap();
+        assert_eq!(Vector::<i64>::load_npy(&path).unwrap().at(6), 3);
+
+        let c = Matrix3d::<f64>::new_iota(2, 3, 4, 0.5);
+        c.save_npy(&path).unwrap();
+        let d = Matrix3d::<f64>::load_npy(&path).unwrap();
+        assert_eq!(d.dim(2), 4);
+        assert_eq!(d.at3(1, 2, 3), 23.5);
+
+        let e = Matrix4d::<i32>::new_iota(2, 1, 3, 2, 1);
+        e.save_npy(&path).unwrap();
+        assert_eq!(Matrix4d::<i32>::load_npy(&path).unwrap().at4(1, 0, 2, 1), 12);
+
+        let t = Tensor::<f32, 5>::new_iota([1, 2, 1, 2, 3], 1.0);
+        t.save_npy(&path).unwrap();
+        assert_eq!(Tensor::<f32, 5>::load_npy(&path).unwrap().at(11), 12.0);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn header() {
+        // Big endian, Fortran order, double quotes and the spacing of older versions of numpy
+        let values = [1i32, 4, 2, 5, 3, 6].iter().flat_map(|v| v.to_be_bytes()).collect::<Vec<_>>();
+        let bytes = npy_bytes("{\"descr\":'>i4',\"fortran_order\":True,\"shape\":(2,3)}\n", &values);
+
+        let array = read_npy::<i32, _>(&mut &bytes[..]).unwrap();
+
+        assert_eq!(array.shape, vec![2, 3]);
+        assert_eq!(array.data, vec![1, 2, 3, 4, 5, 6]);
+
+        let values = [1.0f64, 2.0].iter().flat_map(|v| v.to_le_bytes()).collect::<Vec<_>>();
+        let bytes = npy_bytes("{'descr': '<f8', 'fortran_order': False, 'shape': (2,), }\n", &values);
+
+        let array = read_npy::<f64, _>(&mut &bytes[..]).unwrap();
+
+        assert_eq!(array.shape, vec![2]);
+        assert_eq!(array.data, vec![1.0, 2.0]);
+    }
+
+    #[test]
+    fn fortran_order_3d() {
+        let shape = [2, 3, 4];
+
+        // Element (i, j, k) is at i + 2 * j + 6 * k in column-major order
+        let mut values = [0i64; 24];
+        for i in 0..2 {
+            for j in 0..3 {
+                for k in 0..4 {
+                    values[i + 2 * j + 6 * k] = (100 * i + 10 * j + k) as i64;
+                }
+            }
+        }
+
+        let bytes = values.iter().flat_map(|v| v.to_le_bytes()).collect::<Vec<_>>();
+        let array = read_npy::<i64, _>(&mut &npy_bytes("{'descr': '<i8', 'fortran_order': True, 'shape': (2, 3, 4), }\n", &bytes)[..]).unwrap();
+
+        assert_eq!(array.shape, shape);
+        assert_eq!(array.data[4 * 3 + 4 + 2], 112);
+        assert_eq!(array.data[23], 123);
+    }
+
+    #[test]
+    fn errors() {
+        let path = temp_path("errors.npy");
+
+        Matrix2d::<f32>::new(2, 2).save_npy(&path).unwrap();
+
+        let error = Matrix2d::<f64>::load_npy(&path).err().unwrap();
+        assert!(matches!(error, EtlError::InvalidFormat { format: "npy", .. }));
+
+        let error = Vector::<f32>::load_npy(&path).err().unwrap();
+        assert_eq!(
+            error,
+            EtlError::RankMismatch {
+                op: "loading of Vector",
+                expected: 1,
+                actual: 2,
+            }
+        );
+
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(Matrix2d::<f32>::load_npy(&path), Err(EtlError::Io { .. })));
+
+        let error = read_npy::<f32, _>(&mut &b"\x93NUMPX\x01\x00"[..]).unwrap_err();
+        assert!(matches!(error, EtlError::InvalidFormat { format: "npy", .. }));
+
+        // The data is shorter than the shape
+        let bytes = npy_bytes("{'descr': '<f4', 'fortran_order': False, 'shape': (4,), }\n", &[0; 8]);
+        assert!(matches!(read_npy::<f32, _>(&mut &bytes[..]), Err(EtlError::Io { .. })));
+
+        // The number of bytes of the shape overflows
+        let bytes = npy_bytes("{'descr': '<f4', 'fortran_order': False, 'shape': (4611686018427387904,), }\n", &[0; 8]);
+        let error = read_npy::<f32, _>(&mut &bytes[..]).unwrap_err();
+        assert_eq!(error.to_string(), "Invalid npy data: the shape [4611686018427387904] is too large");
+
+        // The shape is huge, but the data is missing
+        let bytes = npy_bytes("{'descr': '<f4', 'fortran_order': True, 'shape': (1000000000, 1000000), }\n", &[0; 8]);
+        assert!(matches!(read_npy::<f32, _>(&mut &bytes[..]), Err(EtlError::Io { .. })));
+
+        let bytes = npy_bytes("{'descr': '\u{e9}f4', 'fortran_order': False, 'shape': (2,), }\n", &[0; 8]);
+        let error = read_npy::<f32, _>(&mut &bytes[..]).unwrap_err();
+        assert_eq!(error.to_string(), "Invalid npy data: invalid byte order in the dtype \u{e9}f4");
+
+        let error = write_npy(&mut Vec::new(), &[2, 3], &[1.0f32; 5]).unwrap_err();
+        assert_eq!(
+            error,
+            EtlError::ShapeMismatch {
+                op: "writing of npy",
+                lhs: vec![2, 3],
+                rhs: vec![5],
+            }
+        );
+        assert!(write_npy(&mut Vec::new(), &[usize::MAX, 2], &[1.0f32; 5]).is_err());
+    }
+
+    // A zip archive with a single entry a.npy, whose size and local header offset are in the
+    // zip64 extra field of the central directory
+    fn zip64_bytes(size: u64, local: u64) -> Vec<u8> {
+        let mut archive = Vec::new();
+
+        archive.extend_from_slice(&0x04034b50u32.to_le_bytes());
+        archive.extend_from_slice(&[0; 22]);
+        archive.extend_from_slice(&5u16.to_le_bytes());
+        archive.extend_from_slice(&0u16.to_le_bytes());
+        archive.extend_from_slice(b"a.npy");
+
+        let offset = archive.len() as u32;
+        archive.extend_from_slice(&0x02014b50u32.to_le_bytes());
+        archive.extend_from_slice(&[45, 0, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        archive.extend_from_slice(&u32::MAX.to_le_bytes());
+        archive.extend_from_slice(&u32::MAX.to_le_bytes());
+        archive.extend_from_slice(&5u16.to_le_bytes());
+        archive.extend_from_slice(&28u16.to_le_bytes());
+        archive.extend_from_slice(&[0; 10]);
+        archive.extend_from_slice(&u32::MAX.to_le_bytes());
+        archive.extend_from_slice(b"a.npy");
+        archive.extend_from_slice(&1u16.to_le_bytes());
+        archive.extend_from_slice(&24u16.to_le_bytes());
+        archive.extend_from_slice(&size.to_le_bytes());
+        archive.extend_from_slice(&size.to_le_bytes());
+        archive.extend_from_slice(&local.to_le_bytes());
+
+        let directory = archive.len() as u32 - offset;
+        archive.extend_from_slice(&0x06054b50u32.to_le_bytes());
+        archive.extend_from_slice(&[0, 0, 0, 0, 1, 0, 1, 0]);
+        archive.extend_from_slice(&directory.to_le_bytes());
+        archive.extend_from_slice(&offset.to_le_bytes());
+        archive.extend_from_slice(&[0, 0]);
+        archive
+    }
+
+    #[test]
+    fn npz() {
+        let path = temp_path("arrays.npz");
+
+        let mut weights = Vec::new();
+        write_npy(&mut weights, &[2, 3], &[1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+
+        let mut bias = Vec::new();
+        write_npy(&mut bias, &[3], &[7i64, 8, 9]).unwrap();
+
+        std::fs::write(&path, zip_bytes(&[("weights.npy", weights), ("bias.npy", bias)])).unwrap();
+
+        let w = Matrix2d::<f32>::load_npz(&path, "weights").unwrap();
+        assert_eq!(w.at2(1, 0), 4.0);
+
+        let b = Vector::<i64>::load_npz(&path, "bias.npy").unwrap();
+        assert_eq!(b.at(2), 9);
+
+        let error = Vector::<i64>::load_npz(&path, "missing").err().unwrap();
+        assert_eq!(error.to_string(), "Invalid npz data: the archive does not contain missing");
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn npz_zip64_overflow() {
+        assert_eq!(npz_entry(&zip64_bytes(0, 0), "a"), Ok(&[][..]));
+
+        let error = npz_entry(&zip64_bytes(0, u64::MAX - 1), "a").unwrap_err();
+        assert_eq!(error.to_string(), "Invalid npz data: the archive is truncated");
+
+        let error = npz_entry(&zip64_bytes(u64::MAX - 10, 0), "a").unwrap_err();
+        assert_eq!(error.to_string(), "Invalid npz data: the archive is truncated");
+    }
+}
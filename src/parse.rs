@@ -0,0 +1,328 @@
+use crate::etl_error::EtlError;
+use crate::etl_expr::*;
+use crate::matrix_2d::Matrix2d;
+use crate::matrix_3d::Matrix3d;
+use crate::matrix_4d::Matrix4d;
+use crate::tensor::Tensor;
+use crate::vector::Vector;
+
+use std::str::FromStr;
+
+// Parsing of the containers from text
+//
+// The text is a nested list of values, e.g. [[1, 2], [3, 4]]. The commas between the elements
+// are optional, so that the output of Display can be parsed back, e.g.
+// [[1,2]
+//  [3,4]]
+// All the elements of a list must have the same shape.
+
+/// The values of a nested list, in row-major order
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedList<T> {
+    pub dims: Vec<usize>,
+    pub values: Vec<T>,
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+    line: usize,
+    column: usize,
+}
+
+fn fmt_dims(dims: &[usize]) -> String {
+    format!("[{}]", dims.iter().map(ToString::to_string).collect::<Vec<_>>().join(","))
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.position..].chars().next()
+    }
+
+    fn bump(&mut self) {
+        if let Some(c) = self.peek() {
+            self.position += c.len_utf8();
+
+            if c == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    fn error(&self, message: String) -> EtlError {
+        EtlError::Parse {
+            line: self.line,
+            column: self.column,
+            message,
+        }
+    }
+
+    // Parse a value or a list, its values are appended and its shape is returned
+    fn parse_element<T: FromStr>(&mut self, values: &mut Vec<T>) -> Result<Vec<usize>, EtlError> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            None => Err(self.error("unexpected end of input".to_string())),
+            Some('[') => self.parse_list(values),
+            Some(c @ (']' | ',')) => Err(self.error(format!("expected a value, found '{c}'"))),
+            Some(_) => {
+                let (line, column) = (self.line, self.column);
+
+                let start = self.position;
+                while self.peek().is_some_and(|c| !c.is_whitespace() && !matches!(c, '[' | ']' | ',')) {
+                    self.bump();
+                }
+                let token = &self.text[start..self.position];
+
+                let value = token.parse().map_err(|_| EtlError::Parse {
+                    line,
+                    column,
+                    message: if token == "..." {
+                        "elided values cannot be parsed".to_string()
+                    } else {
+                        format!("invalid value '{token}'")
+                    },
+                })?;
+
+                values.push(value);
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    fn parse_list<T: FromStr>(&mut self, values: &mut Vec<T>) -> Result<Vec<usize>, EtlError> {
+        // Skip the opening bracket
+        self.bump();
+
+        let mut count = 0;
+        let mut shape: Option<Vec<usize>> = None;
+
+        loop {
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(']') => {
+                    self.bump();
+                    break;
+                }
+                Some(',') if count > 0 => self.bump(),
+                None => return Err(self.error("missing ']'".to_string())),
+                _ => {}
+            }
+
+            self.skip_whitespace();
+            if count > 0 && self.peek() == Some(']') {
+                continue;
+            }
+
+            let (line, column) = (self.line, self.column);
+            let element = self.parse_element(values)?;
+
+            match &shape {
+                None => shape = Some(element),
+                Some(first) if *first != element => {
+                    return Err(EtlError::Parse {
+                        line,
+                        column,
+                        message: format!("ragged list, the shape {} does not match the shape {} of the first element", fmt_dims(&element), fmt_dims(first)),
+                    });
+                }
+                _ => {}
+            }
+
+            count += 1;
+        }
+
+        let mut dims = vec![count];
+        dims.extend(shape.unwrap_or_default());
+        Ok(dims)
+    }
+}
+
+/// Parse a nested list of values
+pub fn parse_list<T: FromStr>(text: &str) -> Result<ParsedList<T>, EtlError> {
+    let mut parser = Parser {
+        text,
+        position: 0,
+        line: 1,
+        column: 1,
+    };
+
+    let mut values = Vec::new();
+    let dims = parser.parse_element(&mut values)?;
+
+    parser.skip_whitespace();
+    if let Some(c) = parser.peek() {
+        return Err(parser.error(format!("unexpected '{c}' after the end of the list")));
+    }
+
+    Ok(ParsedList { dims, values })
+}
+
+// The containers can be parsed from their Display output
+
+macro_rules! impl_from_str {
+    ([$($generics:tt)*] $type:ty, $name:expr, $rank:expr, $dims:ident => $new:expr) => {
+        impl<T: EtlValueType + FromStr, $($generics)*> FromStr for $type {
+            type Err = EtlError;
+
+            fn from_str(text: &str) -> Result<Self, EtlError> {
+                let mut list = parse_list::<T>(text)?;
+
+                // An empty list does not show its inner dimensions (e.g. [] for 0x3), they are 0
+                if list.values.is_empty() && list.dims.len() < $rank {
+                    list.dims.resize($rank, 0);
+                }
+
+                if list.dims.len() != $rank {
+                    return Err(EtlError::RankMismatch {
+                        op: concat!("parsing of ", $name),
+                        expected: $rank,
+                        actual: list.dims.len(),
+                    });
+                }
+
+                let $dims = &list.dims;
+                let mut container: Self = $new;
+                container.data[..list.values.len()].copy_from_slice(&list.values);
+                Ok(container)
+            }
+        }
+    };
+}
+
+impl_from_str!([] Vector<T>, "Vector", 1, dims => Vector::new(dims[0]));
+impl_from_str!([] Matrix2d<T>, "Matrix2d", 2, dims => Matrix2d::new(dims[0], dims[1]));
+impl_from_str!([] Matrix3d<T>, "Matrix3d", 3, dims => Matrix3d::new(dims[0], dims[1], dims[2]));
+impl_from_str!([] Matrix4d<T>, "Matrix4d", 4, dims => Matrix4d::new(dims[0], dims[1], dims[2], dims[3]));
+impl_from_str!([const D: usize] Tensor<T, D>, "Tensor", D, dims => Tensor::new(std::array::from_fn(|d| dims[d])));
+
+// The tests
+
+#[cfg(test)]
+mod tests {
+    use crate::etl_error::EtlError;
+    use crate::etl_expr::{Etl2d, Etl3d, EtlExpr};
+    use crate::matrix_2d::Matrix2d;
+    use crate::matrix_3d::Matrix3d;
+    use crate::parse::*;
+    use crate::tensor::Tensor;
+    use crate::vector::Vector;
+
+    #[test]
+    fn nested() {
+        let a: Matrix2d<i32> = "[[1, 2, 3], [4, 5, -6]]".parse().unwrap();
+        assert_eq!(a.rows(), 2);
+        assert_eq!(a.columns(), 3);
+        assert_eq!(a.at2(1, 2), -6);
+
+        let b: Vector<f64> = " [1.5,\n 2e3, -0.25,] ".parse().unwrap();
+        assert_eq!(b.size(), 3);
+        assert_eq!(b.at(1), 2000.0);
+
+        let c = "[[[1 2] [3 4]], [[5 6] [7 8]]]".parse::<Tensor<u8, 3>>().unwrap();
+        assert_eq!(c.at(7), 8);
+
+        let empty: Matrix2d<f32> = "[[], []]".parse().unwrap();
+        assert_eq!(empty.rows(), 2);
+        assert_eq!(empty.columns(), 0);
+
+        let list = parse_list::<i64>("[]").unwrap();
+        assert_eq!(list.dims, vec![0]);
+    }
+
+    #[test]
+    fn display_round_trip() {
+        let a = Vector::<i64>::new_iota(5, -2);
+        let b: Vector<i64> = a.to_string().parse().unwrap();
+        assert_eq!((0..5).map(|i| b.at(i)).collect::<Vec<_>>(), [-2, -1, 0, 1, 2]);
+
+        let c = Matrix2d::<f32>::new_iota(3, 4, 0.5);
+        let d: Matrix2d<f32> = c.to_string().parse().unwrap();
+        assert_eq!(d.rows(), 3);
+        assert_eq!(d.at2(2, 3), 11.5);
+
+        let e = Matrix3d::<f64>::new_iota(2, 3, 2, 8.25);
+        let f: Matrix3d<f64> = format!("{e:8.2}").parse().unwrap();
+        assert_eq!(f.dim(1), 3);
+        assert_eq!(f.at3(1, 2, 1), 19.25);
+    }
+
+    #[test]
+    fn empty_round_trip() {
+        let a = Matrix2d::<f32>::new(0, 3);
+        let b: Matrix2d<f32> = a.to_string().parse().unwrap();
+        assert_eq!(b.size(), 0);
+        assert_eq!(b.rows(), 0);
+
+        let c = Matrix2d::<i32>::new(3, 0);
+        let d: Matrix2d<i32> = c.to_string().parse().unwrap();
+        assert_eq!(d.rows(), 3);
+        assert_eq!(d.columns(), 0);
+
+        let e = Matrix3d::<f64>::new(2, 0, 4);
+        let f: Matrix3d<f64> = e.to_string().parse().unwrap();
+        assert_eq!(f.size(), 0);
+        assert_eq!(f.dim(0), 2);
+
+        let g = Tensor::<u8, 4>::new([0, 1, 2, 3]);
+        let h: Tensor<u8, 4> = g.to_string().parse().unwrap();
+        assert_eq!(h.size(), 0);
+
+        let i: Vector<i64> = Vector::<i64>::new(0).to_string().parse().unwrap();
+        assert_eq!(i.size(), 0);
+    }
+
+    #[test]
+    fn errors() {
+        let error = "[[1, 2],\n [3, 4, 5]]".parse::<Matrix2d<i32>>().err();
+        assert_eq!(
+            error,
+            Some(EtlError::Parse {
+                line: 2,
+                column: 2,
+                message: "ragged list, the shape [3] does not match the shape [2] of the first element".to_string(),
+            })
+        );
+
+        let error = "[1, [2]]".parse::<Vector<i32>>().err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Parse error at line 1, column 5: ragged list, the shape [1] does not match the shape [] of the first element"
+        );
+
+        let error = "[1, x]".parse::<Vector<i32>>().err().unwrap();
+        assert_eq!(error.to_string(), "Parse error at line 1, column 5: invalid value 'x'");
+
+        let error = "[1,...,9]".parse::<Vector<i32>>().err().unwrap();
+        assert_eq!(error.to_string(), "Parse error at line 1, column 4: elided values cannot be parsed");
+
+        let error = "[1,,2]".parse::<Vector<i32>>().err().unwrap();
+        assert_eq!(error.to_string(), "Parse error at line 1, column 4: expected a value, found ','");
+
+        let error = "[[1, 2]".parse::<Matrix2d<i32>>().err().unwrap();
+        assert_eq!(error.to_string(), "Parse error at line 1, column 8: missing ']'");
+
+        let error = "[1] 2".parse::<Vector<i32>>().err().unwrap();
+        assert_eq!(error.to_string(), "Parse error at line 1, column 5: unexpected '2' after the end of the list");
+
+        let error = "[1, 2]".parse::<Matrix2d<i32>>().err();
+        assert_eq!(
+            error,
+            Some(EtlError::RankMismatch {
+                op: "parsing of Matrix2d",
+                expected: 2,
+                actual: 1,
+            })
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use crate::etl_expr::EtlValueType;
+
+use std::cell::Cell;
+use std::fmt;
+
+// The declaration of PrintOptions
+
+/// The options of the printing of the containers, similar to the print options of NumPy
+///
+/// The options are set per thread, with set_print_options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrintOptions {
+    /// The number of decimals of the values, when the format does not specify it (e.g. {:.3})
+    pub precision: usize,
+    /// The number of values above which the middle of the dimensions is elided
+    pub threshold: usize,
+    /// The number of values printed at the beginning and at the end of an elided dimension
+    pub edge_items: usize,
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        Self {
+            precision: 6,
+            threshold: 1000,
+            edge_items: 3,
+        }
+    }
+}
+
+// The functions of PrintOptions
+
+impl PrintOptions {
+    pub fn with_precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    pub fn with_threshold(mut self, threshold: usize) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn with_edge_items(mut self, edge_items: usize) -> Self {
+        self.edge_items = edge_items;
+        self
+    }
+}
+
+thread_local! {
+    static PRINT_OPTIONS: Cell<PrintOptions> = Cell::new(PrintOptions::default());
+}
+
+/// The print options of the current thread
+pub fn print_options() -> PrintOptions {
+    PRINT_OPTIONS.with(Cell::get)
+}
+
+/// Set the print options of the current thread
+pub fn set_print_options(options: PrintOptions) {
+    PRINT_OPTIONS.with(|cell| cell.set(options));
+}
+
+// The printing of the containers
+//
+// The rows are printed on separate lines, indented to align with the first row, and all the
+// values are padded to the width of the widest one (or to the width of the format). For example,
+// [[1,2,3]
+//  [4,5,6]]
+
+// The indices printed along a dimension, None stands for the elided values
+fn shown_indices(dim: usize, summarize: bool, edge_items: usize) -> Vec<Option<usize>> {
+    if summarize && dim > 2 * edge_items {
+        (0..edge_items)
+            .map(Some)
+            .chain(std::iter::once(None))
+            .chain((dim - edge_items..dim).map(Some))
+            .collect()
+    } else {
+        (0..dim).map(Some).collect()
+    }
+}
+
+struct Printer<'a, T> {
+    data: &'a [T],
+    dims: &'a [usize],
+    summarize: bool,
+    edge_items: usize,
+    precision: usize,
+}
+
+impl<T: EtlValueType> Printer<'_, T> {
+    fn value(&self, i: usize) -> String {
+        format!("{:.*}", self.precision, self.data[i])
+    }
+
+    // The width of the widest printed value
+    fn width(&self, d: usize, offset: usize) -> usize {
+        let stride: usize = self.dims[d + 1..].iter().product();
+
+        shown_indices(self.dims[d], self.summarize, self.edge_items)
+            .into_iter()
+            .flatten()
+            .map(|i| {
+                if d == self.dims.len() - 1 {
+                    self.value(offset + i).chars().count()
+                } else {
+                    self.width(d + 1, offset + i * stride)
+                }
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn print(&self, f: &mut fmt::Formatter<'_>, d: usize, offset: usize, width: usize) -> fmt::Result {
+        let stride: usize = self.dims[d + 1..].iter().product();
+        let last = d == self.dims.len() - 1;
+
+        write!(f, "[")?;
+
+        for (k, index) in shown_indices(self.dims[d], self.summarize, self.edge_items).into_iter().enumerate() {
+            if k > 0 {
+                if last {
+                    write!(f, ",")?;
+                } else {
+                    write!(f, "\n{:1$}", "", d + 1)?;
+                }
+            }
+
+            match index {
+                None => write!(f, "...")?,
+                Some(i) if last => {
+                    let value = self.value(offset + i);
+                    let padding = width.saturating_sub(value.chars().count());
+
+                    match f.align() {
+                        Some(fmt::Alignment::Left) => write!(f, "{value}{:padding$}", "")?,
+                        Some(fmt::Alignment::Center) => write!(f, "{:2$}{value}{:3$}", "", "", padding / 2, padding - padding / 2)?,
+                        _ => write!(f, "{:padding$}{value}", "")?,
+                    }
+                }
+                Some(i) => self.print(f, d + 1, offset + i * stride, width)?,
+            }
+        }
+
+        write!(f, "]")
+    }
+}
+
+/// Print the values of a row-major container with the print options of the current thread
+///
+/// The precision, the width and the alignment of the format are applied to each value.
+pub fn fmt_container<T: EtlValueType>(f: &mut fmt::Formatter<'_>, data: &[T], dims: &[usize]) -> fmt::Result {
+    let options = print_options();
+
+    let printer = Printer {
+        data,
+        dims,
+        summarize: dims.iter().product::<usize>() > options.threshold,
+        edge_items: options.edge_items,
+        precision: f.precision().unwrap_or(options.precision),
+    };
+
+    let width = printer.width(0, 0).max(f.width().unwrap_or(0));
+    printer.print(f, 0, 0, width)
+}
+
+/// Print the name, the value type and the shape of a container, followed by its values
+pub fn fmt_container_debug<T: EtlValueType>(f: &mut fmt::Formatter<'_>, name: &str, data: &[T], dims: &[usize]) -> fmt::Result {
+    // Only keep the name of the type, e.g. f16 and not half::binary16::f16
+    let dtype = std::any::type_name::<T>();
+    let dtype = dtype.rsplit_once("::").filter(|_| !dtype.contains('<')).map_or(dtype, |(_, name)| name);
+
+    write!(f, "{name}<{dtype}> [")?;
+    for (i, dim) in dims.iter().enumerate() {
+        if i > 0 {
+            write!(f, ",")?;
+        }
+        write!(f, "{dim}")?;
+    }
+    writeln!(f, "]")?;
+
+    fmt_container(f, data, dims)
+}
+
+// The tests
+
+#[cfg(test)]
+mod tests {
+    use crate::matrix_2d::Matrix2d;
+    use crate::matrix_3d::Matrix3d;
+    use crate::print::*;
+    use crate::vector::Vector;
+
+    #[test]
+    fn format() {
+        let a = Matrix2d::<f64>::new_iota(2, 3, 8.5);
+
+        assert_eq!(format!("{a:.1}"), "[[ 8.5, 9.5,10.5]\n [11.5,12.5,13.5]]");
+        assert_eq!(format!("{a:6.2}"), "[[  8.50,  9.50, 10.50]\n [ 11.50, 12.50, 13.50]]");
+        assert_eq!(format!("{a:<5.0}"), "[[8    ,10   ,10   ]\n [12   ,12   ,14   ]]");
+
+        let b = Vector::<i32>::new_iota(3, -1);
+        assert_eq!(format!("{b:^4}"), "[ -1 , 0  , 1  ]");
+    }
+
+    #[test]
+    fn summarize() {
+        let a = Vector::<i64>::new_iota(2000, 0);
+        assert_eq!(format!("{a}"), "[   0,   1,   2,...,1997,1998,1999]");
+
+        set_print_options(PrintOptions::default().with_threshold(10).with_edge_items(1).with_precision(2));
+
+        let b = Matrix2d::<i32>::new_iota(4, 5, 1);
+        assert_eq!(format!("{b}"), "[[ 1,..., 5]\n ...\n [16,...,20]]");
+
+        let c = Matrix3d::<f32>::new_iota(3, 2, 2, 0.0);
+        assert_eq!(format!("{c}"), "[[[ 0.00, 1.00]\n  [ 2.00, 3.00]]\n ...\n [[ 8.00, 9.00]\n  [10.00,11.00]]]");
+
+        // The options are only used when the format does not specify them
+        let d = Vector::<f32>::new_iota(2, 0.5);
+        assert_eq!(format!("{d}"), "[0.50,1.50]");
+        assert_eq!(format!("{d:.1}"), "[0.5,1.5]");
+
+        set_print_options(PrintOptions::default());
+        assert_eq!(format!("{b}"), "[[ 1, 2, 3, 4, 5]\n [ 6, 7, 8, 9,10]\n [11,12,13,14,15]\n [16,17,18,19,20]]");
+    }
+
+    #[test]
+    fn debug() {
+        let a = Matrix2d::<f32>::new_iota(2, 2, 1.0);
+        assert_eq!(format!("{a:?}"), "Matrix2d<f32> [2,2]\n[[1.000000,2.000000]\n [3.000000,4.000000]]");
+        assert_eq!(format!("{a:.1?}"), "Matrix2d<f32> [2,2]\n[[1.0,2.0]\n [3.0,4.0]]");
+
+        let b = Vector::<crate::half_float::f16>::new(0);
+        assert_eq!(format!("{b:?}"), "Vector<f16> [0]\n[]");
+    }
+}
@@ -1,3 +1,5 @@
+use crate::base_traits::Abs;
+use crate::etl_error::EtlError;
 use crate::etl_expr::*;
 
 // Reduction Operations
@@ -14,18 +16,13 @@ pub fn sum<T: EtlValueType, Expr: EtlExpr<T>>(expr: &Expr) -> T {
     value
 }
 
-pub fn asum<T: EtlValueType, Expr: EtlExpr<T>>(expr: &Expr) -> T {
+pub fn asum<T: EtlValueType + Abs, Expr: EtlExpr<T>>(expr: &Expr) -> T {
     assert!(expr.size() > 0);
 
     let mut value = T::default();
 
     for i in 0..expr.size() {
-        let v = expr.at(i);
-        if v < T::default() {
-            value += -v;
-        } else {
-            value += v;
-        }
+        value += expr.at(i).abs();
     }
 
     value
@@ -35,25 +32,25 @@ pub fn asum<T: EtlValueType, Expr: EtlExpr<T>>(expr: &Expr) -> T {
 // TODO: I should also implement stddev
 // TODO: Tests and ensure it works with f32
 
-pub fn mean<T: EtlValueType + From<u32>, Expr: EtlExpr<T>>(expr: &Expr) -> Result<T, &'static str> {
+pub fn mean<T: EtlValueType + From<u32>, Expr: EtlExpr<T>>(expr: &Expr) -> Result<T, EtlError> {
     if expr.size() == 0 {
-        return Err("Cannot get mean of empty collection");
+        return Err(EtlError::EmptyInput { op: "mean" });
     }
 
     Ok(sum(expr) / From::from(expr.size() as u32))
 }
 
-pub fn amean<T: EtlValueType + From<u32>, Expr: EtlExpr<T>>(expr: &Expr) -> Result<T, &'static str> {
+pub fn amean<T: EtlValueType + Abs + From<u32>, Expr: EtlExpr<T>>(expr: &Expr) -> Result<T, EtlError> {
     if expr.size() == 0 {
-        return Err("Cannot get mean of empty collection");
+        return Err(EtlError::EmptyInput { op: "amean" });
     }
 
     Ok(asum(expr) / From::from(expr.size() as u32))
 }
 
-pub fn max<T: EtlValueType, Expr: EtlExpr<T>>(expr: &Expr) -> Result<T, &'static str> {
+pub fn max<T: EtlValueType, Expr: EtlExpr<T>>(expr: &Expr) -> Result<T, EtlError> {
     if expr.size() == 0 {
-        return Err("Cannot get max of empty collection");
+        return Err(EtlError::EmptyInput { op: "max" });
     }
 
     let mut max_value = expr.at(0);
@@ -67,9 +64,9 @@ pub fn max<T: EtlValueType, Expr: EtlExpr<T>>(expr: &Expr) -> Result<T, &'static
     Ok(max_value)
 }
 
-pub fn min<T: EtlValueType, Expr: EtlExpr<T>>(expr: &Expr) -> Result<T, &'static str> {
+pub fn min<T: EtlValueType, Expr: EtlExpr<T>>(expr: &Expr) -> Result<T, EtlError> {
     if expr.size() == 0 {
-        return Err("Cannot get min of empty collection");
+        return Err(EtlError::EmptyInput { op: "min" });
     }
 
     let mut min_value = expr.at(0);
@@ -196,4 +193,26 @@ mod tests {
             Err(e) => panic!("Error on min: {e:?}"),
         }
     }
+
+    #[test]
+    fn empty() {
+        let a = Vector::<f64>::new(0);
+
+        assert_eq!(mean(&a), Err(EtlError::EmptyInput { op: "mean" }));
+        assert_eq!(max(&a), Err(EtlError::EmptyInput { op: "max" }));
+        assert!(min(&a).is_err());
+    }
+
+    #[test]
+    fn unsigned() {
+        let a = Vector::<u8>::new_iota(10, 1);
+
+        assert_eq!(sum(&a), 55);
+        assert_eq!(max(&a), Ok(10));
+        assert_eq!(min(&a), Ok(1));
+
+        let b = Vector::<i16>::new_iota(10, -5);
+
+        assert_eq!(asum(&b), 25);
+    }
 }
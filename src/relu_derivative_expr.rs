@@ -71,8 +71,8 @@ impl<T: EtlValueType + Float, Expr: WrappableExpr<T>> EtlExpr<T> for ReluDerivat
         self.expr.value.rows()
     }
 
-    fn columns(&self) -> usize {
-        self.expr.value.columns()
+    fn dim(&self, i: usize) -> usize {
+        self.expr.value.dim(i)
     }
 
     fn at(&self, i: usize) -> T {
@@ -108,6 +108,9 @@ pub fn relu_derivative<T: EtlValueType + Float, Expr: WrappableExpr<T>>(expr: Ex
     ReluDerivativeExpr::<T, Expr>::new(expr)
 }
 
+crate::impl_rank_unary_expr_trait!(Float, ReluDerivativeExpr<T, Expr>);
+crate::impl_fixed_shape_unary_expr_trait!(Float, ReluDerivativeExpr<T, Expr>);
+
 crate::impl_add_op_unary_expr_trait!(Float, ReluDerivativeExpr<T, Expr>);
 crate::impl_sub_op_unary_expr_trait!(Float, ReluDerivativeExpr<T, Expr>);
 crate::impl_mul_op_unary_expr_trait!(Float, ReluDerivativeExpr<T, Expr>);
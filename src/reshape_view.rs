@@ -0,0 +1,212 @@
+use crate::etl_expr::*;
+use crate::shape::Shape;
+
+// The declaration of ReshapeView
+
+/// A view of an expression with a different shape but the same storage
+#[derive(Clone)]
+pub struct ReshapeView<T: EtlValueType, Expr: WrappableExpr<T>, const D: usize> {
+    expr: EtlWrapper<T, Expr::WrappedAs>,
+    shape: Shape<D>,
+}
+
+// The functions of ReshapeView
+
+impl<T: EtlValueType, Expr: WrappableExpr<T>, const D: usize> ReshapeView<T, Expr, D> {
+    pub fn new(expr: Expr, shape: Shape<D>) -> Self {
+        Self { expr: expr.wrap(), shape }
+    }
+
+    pub fn shape(&self) -> &Shape<D> {
+        &self.shape
+    }
+}
+
+// ReshapeView is an EtlExpr
+impl<T: EtlValueType, Expr: WrappableExpr<T>, const D: usize> EtlExpr<T> for ReshapeView<T, Expr, D> {
+    const DIMENSIONS: usize = D;
+    const TYPE: EtlType = EtlType::Value;
+    const THREAD_SAFE: bool = Expr::THREAD_SAFE;
+
+    type Iter<'x>
+        = std::iter::Cloned<std::slice::Iter<'x, T>>
+    where
+        T: 'x,
+        Self: 'x;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.get_data().iter().cloned()
+    }
+
+    fn iter_range(&self, range: std::ops::Range<usize>) -> Self::Iter<'_> {
+        // The range may extend into the padding, the view stops at its size
+        let data = self.get_data();
+        data[range.start.min(data.len())..range.end.min(data.len())].iter().cloned()
+    }
+
+    fn size(&self) -> usize {
+        self.shape.size()
+    }
+
+    fn rows(&self) -> usize {
+        self.shape.dim(0)
+    }
+
+    fn dim(&self, i: usize) -> usize {
+        self.shape.dim(i)
+    }
+
+    fn at(&self, i: usize) -> T {
+        self.get_data()[i]
+    }
+
+    fn get_data(&self) -> &[T] {
+        &self.expr.value.get_data()[..self.shape.size()]
+    }
+}
+
+impl<T: EtlValueType, Expr: WrappableExpr<T>> Etl2d<T> for ReshapeView<T, Expr, 2> {
+    fn at2(&self, i: usize, j: usize) -> T {
+        self.get_data()[self.shape.offset(&[i, j])]
+    }
+}
+
+impl<T: EtlValueType, Expr: WrappableExpr<T>> Etl3d<T> for ReshapeView<T, Expr, 3> {
+    fn at3(&self, i: usize, j: usize, k: usize) -> T {
+        self.get_data()[self.shape.offset(&[i, j, k])]
+    }
+}
+
+impl<T: EtlValueType, Expr: WrappableExpr<T>> Etl4d<T> for ReshapeView<T, Expr, 4> {
+    fn at4(&self, i: usize, j: usize, k: usize, l: usize) -> T {
+        self.get_data()[self.shape.offset(&[i, j, k, l])]
+    }
+}
+
+// ReshapeView is an EtlWrappable
+// ReshapeView wraps as value
+impl<T: EtlValueType, Expr: WrappableExpr<T>, const D: usize> EtlWrappable<T> for ReshapeView<T, Expr, D> {
+    type WrappedAs = ReshapeView<T, Expr, D>;
+
+    fn wrap(self) -> EtlWrapper<T, Self::WrappedAs> {
+        EtlWrapper {
+            value: self,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+// ReshapeView computes as copy
+impl<T: EtlValueType, Expr: WrappableExpr<T>, const D: usize> EtlComputable<T> for ReshapeView<T, Expr, D> {
+    fn to_data(&self) -> Vec<T> {
+        let mut vec = vec![T::default(); padded_size(self.size())];
+        assign_direct(&mut vec, self);
+        vec
+    }
+}
+
+// Operations
+
+pub fn reshape<const D: usize, T: EtlValueType, Expr: WrappableExpr<T>>(expr: Expr, dims: [usize; D]) -> ReshapeView<T, Expr, D> {
+    assert!(D > 0, "reshape needs at least one dimension");
+    assert!(Expr::TYPE.direct(), "reshape can only work on direct expressions");
+
+    let shape = Shape::new(dims);
+
+    if shape.size() != expr.size() {
+        panic!("Invalid reshape ({} elements into {} = {} elements)", expr.size(), shape, shape.size());
+    }
+
+    ReshapeView::<T, Expr, D>::new(expr, shape)
+}
+
+crate::impl_view_ops!([T: EtlValueType, Expr: WrappableExpr<T>, const D: usize] ReshapeView<T, Expr, D>);
+
+// The tests
+
+#[cfg(test)]
+mod tests {
+    use crate::bias_add_expr::bias_add;
+    use crate::etl_expr::{Etl2d, Etl4d, EtlExpr};
+    use crate::matrix_2d::Matrix2d;
+    use crate::matrix_4d::Matrix4d;
+    use crate::reshape_view::reshape;
+    use crate::vector::Vector;
+
+    #[test]
+    fn reshape_4d_to_2d() {
+        let a = Matrix4d::<i64>::new_iota(2, 3, 2, 2, 1);
+
+        let expr = reshape::<2, _, _>(&a, [2, 12]);
+
+        assert_eq!(expr.size(), 24);
+        assert_eq!(expr.rows(), 2);
+        assert_eq!(expr.columns(), 12);
+        assert_eq!(expr.at2(0, 11), 12);
+        assert_eq!(expr.at2(1, 0), 13);
+        assert_eq!(expr.get_data().as_ptr(), a.data.as_ptr());
+    }
+
+    #[test]
+    fn reshape_2d_to_4d() {
+        let a = Matrix2d::<i64>::new_iota(2, 12, 1);
+
+        let expr = reshape(&a, [2, 3, 2, 2]);
+
+        assert_eq!(expr.dim(1), 3);
+        assert_eq!(expr.dim(3), 2);
+        assert_eq!(expr.at4(1, 2, 1, 1), 24);
+        assert_eq!(expr.at4(0, 1, 0, 1), 6);
+
+        let mut b = Matrix4d::<i64>::new(2, 3, 2, 2);
+        b |= reshape(&a, [2, 3, 2, 2]);
+
+        assert_eq!(b.at4(1, 2, 1, 1), 24);
+    }
+
+    #[test]
+    fn reshape_dense() {
+        let a = Matrix4d::<i64>::new_iota(2, 1, 2, 2, 1);
+        let w = Matrix2d::<i64>::new_iota(4, 2, 1);
+        let bias = Vector::<i64>::new_iota(2, 1);
+
+        let mut c = Matrix2d::<i64>::new(2, 2);
+        c |= bias_add(reshape(&a, [2, 4]) * &w, &bias);
+
+        assert_eq!(c.at2(0, 0), 51);
+        assert_eq!(c.at2(0, 1), 62);
+        assert_eq!(c.at2(1, 0), 115);
+        assert_eq!(c.at2(1, 1), 142);
+    }
+
+    #[test]
+    fn reshape_expr() {
+        let a = Matrix2d::<i64>::new_iota(2, 3, 1);
+        let b = Vector::<i64>::new_iota(6, 1);
+
+        let mut c = Vector::<i64>::new(6);
+        c |= reshape(&a, [6]) + &b;
+
+        assert_eq!(c.at(0), 2);
+        assert_eq!(c.at(5), 12);
+    }
+
+    #[test]
+    fn reshape_iter() {
+        let a = Matrix2d::<i64>::new_iota(2, 3, 1);
+
+        let expr = reshape(&a, [6]);
+
+        assert_eq!(expr.iter().count(), 6);
+        assert_eq!(expr.iter_range(0..64).count(), 6);
+        assert_eq!(expr.iter_range(4..64).collect::<Vec<_>>(), vec![5, 6]);
+        assert!(expr.iter().eq(expr.iter_range(0..expr.size())));
+    }
+
+    #[test]
+    #[should_panic]
+    fn reshape_invalid() {
+        let a = Matrix2d::<i64>::new_iota(2, 3, 1);
+        let _expr = reshape(&a, [4, 2]);
+    }
+}
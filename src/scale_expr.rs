@@ -81,11 +81,11 @@ impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> E
         }
     }
 
-    fn columns(&self) -> usize {
+    fn dim(&self, i: usize) -> usize {
         if LeftExpr::DIMENSIONS > 0 {
-            self.lhs.value.columns()
+            self.lhs.value.dim(i)
         } else {
-            self.rhs.value.columns()
+            self.rhs.value.dim(i)
         }
     }
 
@@ -93,6 +93,11 @@ impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> E
     fn at(&self, i: usize) -> T {
         self.lhs.value.at(i) * self.rhs.value.at(i)
     }
+
+    #[inline(always)]
+    fn compute_at(&self, i: usize) -> T::Compute {
+        self.lhs.value.compute_at(i) * self.rhs.value.compute_at(i)
+    }
 }
 
 // ScaleExpr is an EtlWrappable
@@ -125,8 +130,8 @@ impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> E
 
 #[macro_export]
 macro_rules! impl_scale_op_value {
-    ($type:ty) => {
-        impl<'a, T: EtlValueType, RightExpr: WrappableExpr<T>> std::ops::Shr<RightExpr> for &'a $type {
+    ($type:ty $(, const $dim:ident)*) => {
+        impl<'a, T: EtlValueType, $(const $dim: usize,)* RightExpr: WrappableExpr<T>> std::ops::Shr<RightExpr> for &'a $type {
             type Output = $crate::scale_expr::ScaleExpr<T, &'a $type, RightExpr>;
 
             fn shr(self, other: RightExpr) -> Self::Output {
@@ -134,10 +139,11 @@ macro_rules! impl_scale_op_value {
             }
         }
 
-        impl<T: EtlValueType, RightExpr: EtlExpr<T>> std::ops::ShrAssign<RightExpr> for $type {
+        impl<T: EtlValueType, $(const $dim: usize,)* RightExpr: EtlExpr<T>> std::ops::ShrAssign<RightExpr> for $type {
             fn shr_assign(&mut self, other: RightExpr) {
                 validate_assign(self, &other);
-                scale_assign_direct(&mut self.data, &other);
+                let size = self.size();
+                scale_assign_direct(&mut self.data[..size], &other);
             }
         }
     };
@@ -189,7 +195,7 @@ macro_rules! impl_scale_op_binary_expr_simd {
     ($type:ty) => {
         impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>, OuterRightExpr: WrappableExpr<T>> std::ops::Shr<OuterRightExpr> for $type
         where
-            Simd<T, 8>: SimdHelper,
+            T: $crate::base_traits::Vectorizable,
         {
             type Output = $crate::scale_expr::ScaleExpr<T, $type, OuterRightExpr>;
 
@@ -226,6 +232,9 @@ macro_rules! impl_scale_op_unary_expr_trait {
     };
 }
 
+crate::impl_rank_binary_expr!(ScaleExpr<T, LeftExpr, RightExpr>);
+crate::impl_fixed_shape_binary_expr!(ScaleExpr<T, LeftExpr, RightExpr>);
+
 crate::impl_add_op_binary_expr!(ScaleExpr<T, LeftExpr, RightExpr>);
 crate::impl_sub_op_binary_expr!(ScaleExpr<T, LeftExpr, RightExpr>);
 crate::impl_mul_op_binary_expr!(ScaleExpr<T, LeftExpr, RightExpr>);
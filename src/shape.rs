@@ -0,0 +1,115 @@
+use std::fmt;
+
+// The declaration of Shape<D>
+
+/// The dimensions of a D-dimensional row-major container
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Shape<const D: usize> {
+    dims: [usize; D],
+}
+
+// The functions of Shape<D>
+
+impl<const D: usize> Shape<D> {
+    pub fn new(dims: [usize; D]) -> Self {
+        Self { dims }
+    }
+
+    pub fn dims(&self) -> &[usize; D] {
+        &self.dims
+    }
+
+    pub fn dim(&self, i: usize) -> usize {
+        if i >= D {
+            panic!("Invalid dimension access ({i} for {D}D)");
+        }
+
+        self.dims[i]
+    }
+
+    /// Return the number of elements described by the shape
+    pub fn size(&self) -> usize {
+        self.dims.iter().product()
+    }
+
+    /// Return the flat row-major position of the element at `index`
+    pub fn offset(&self, index: &[usize]) -> usize {
+        if index.len() != D {
+            panic!("Invalid number of indices ({} for {D}D)", index.len());
+        }
+
+        let mut offset = 0;
+
+        for (d, (i, dim)) in index.iter().zip(self.dims.iter()).enumerate() {
+            if i >= dim {
+                panic!("Index {i} is out of bounds for dimension {d}!");
+            }
+
+            offset = offset * dim + i;
+        }
+
+        offset
+    }
+}
+
+impl<const D: usize> From<[usize; D]> for Shape<D> {
+    fn from(dims: [usize; D]) -> Self {
+        Self::new(dims)
+    }
+}
+
+impl<const D: usize> fmt::Display for Shape<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+
+        for (i, dim) in self.dims.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{dim}")?;
+        }
+
+        write!(f, "]")
+    }
+}
+
+// The tests
+
+#[cfg(test)]
+mod tests {
+    use crate::shape::Shape;
+
+    #[test]
+    fn size() {
+        let shape = Shape::new([2, 3, 4]);
+
+        assert_eq!(shape.size(), 24);
+        assert_eq!(shape.dim(0), 2);
+        assert_eq!(shape.dim(1), 3);
+        assert_eq!(shape.dim(2), 4);
+    }
+
+    #[test]
+    fn offset() {
+        let shape = Shape::new([2, 3, 4]);
+
+        assert_eq!(shape.offset(&[0, 0, 0]), 0);
+        assert_eq!(shape.offset(&[0, 0, 3]), 3);
+        assert_eq!(shape.offset(&[0, 1, 0]), 4);
+        assert_eq!(shape.offset(&[1, 0, 0]), 12);
+        assert_eq!(shape.offset(&[1, 2, 3]), 23);
+    }
+
+    #[test]
+    #[should_panic]
+    fn offset_out_of_bounds() {
+        let shape = Shape::new([2, 3]);
+        shape.offset(&[0, 3]);
+    }
+
+    #[test]
+    fn print() {
+        let shape = Shape::new([5, 1, 28, 28]);
+        assert_eq!(format!("{shape}"), "[5,1,28,28]");
+    }
+}
@@ -67,8 +67,8 @@ impl<T: EtlValueType + Float, Expr: WrappableExpr<T>> EtlExpr<T> for SigmoidExpr
         self.expr.value.rows()
     }
 
-    fn columns(&self) -> usize {
-        self.expr.value.columns()
+    fn dim(&self, i: usize) -> usize {
+        self.expr.value.dim(i)
     }
 
     fn at(&self, i: usize) -> T {
@@ -107,6 +107,9 @@ pub fn sigmoid<T: EtlValueType + Float, Expr: WrappableExpr<T>>(expr: Expr) -> S
     SigmoidExpr::<T, Expr>::new(expr)
 }
 
+crate::impl_rank_unary_expr_trait!(Float, SigmoidExpr<T, Expr>);
+crate::impl_fixed_shape_unary_expr_trait!(Float, SigmoidExpr<T, Expr>);
+
 crate::impl_add_op_unary_expr_trait!(Float, SigmoidExpr<T, Expr>);
 crate::impl_sub_op_unary_expr_trait!(Float, SigmoidExpr<T, Expr>);
 crate::impl_mul_op_unary_expr_trait!(Float, SigmoidExpr<T, Expr>);
@@ -0,0 +1,241 @@
+use crate::etl_expr::*;
+
+// The declaration of SliceView
+
+/// A contiguous view of the elements start..end of the first dimension of an expression
+#[derive(Clone)]
+pub struct SliceView<T: EtlValueType, Expr: WrappableExpr<T>> {
+    expr: EtlWrapper<T, Expr::WrappedAs>,
+    start: usize,
+    end: usize,
+}
+
+// The functions of SliceView
+
+impl<T: EtlValueType, Expr: WrappableExpr<T>> SliceView<T, Expr> {
+    pub fn new(expr: Expr, start: usize, end: usize) -> Self {
+        Self { expr: expr.wrap(), start, end }
+    }
+
+    fn stride(&self) -> usize {
+        // An empty first dimension has no elements to step over
+        match self.expr.value.dim(0) {
+            0 => 0,
+            rows => self.expr.value.size() / rows,
+        }
+    }
+}
+
+// SliceView is an EtlExpr
+impl<T: EtlValueType, Expr: WrappableExpr<T>> EtlExpr<T> for SliceView<T, Expr> {
+    const DIMENSIONS: usize = Expr::DIMENSIONS;
+    const TYPE: EtlType = EtlType::Value;
+    const THREAD_SAFE: bool = Expr::THREAD_SAFE;
+
+    type Iter<'x>
+        = std::iter::Cloned<std::slice::Iter<'x, T>>
+    where
+        T: 'x,
+        Self: 'x;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.get_data().iter().cloned()
+    }
+
+    fn iter_range(&self, range: std::ops::Range<usize>) -> Self::Iter<'_> {
+        // The view is not padded, but the range may be
+        let data = self.get_data();
+        data[range.start.min(data.len())..range.end.min(data.len())].iter().cloned()
+    }
+
+    fn size(&self) -> usize {
+        (self.end - self.start) * self.stride()
+    }
+
+    fn rows(&self) -> usize {
+        self.end - self.start
+    }
+
+    fn dim(&self, i: usize) -> usize {
+        if i == 0 { self.end - self.start } else { self.expr.value.dim(i) }
+    }
+
+    fn at(&self, i: usize) -> T {
+        self.get_data()[i]
+    }
+
+    fn get_data(&self) -> &[T] {
+        &self.expr.value.get_data()[self.start * self.stride()..self.end * self.stride()]
+    }
+}
+
+// SliceView is an EtlWrappable
+// SliceView wraps as value
+impl<T: EtlValueType, Expr: WrappableExpr<T>> EtlWrappable<T> for SliceView<T, Expr> {
+    type WrappedAs = SliceView<T, Expr>;
+
+    fn wrap(self) -> EtlWrapper<T, Self::WrappedAs> {
+        EtlWrapper {
+            value: self,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+// SliceView computes as copy
+impl<T: EtlValueType, Expr: WrappableExpr<T>> EtlComputable<T> for SliceView<T, Expr> {
+    fn to_data(&self) -> Vec<T> {
+        let mut vec = vec![T::default(); padded_size(self.size())];
+        assign_direct(&mut vec, self);
+        vec
+    }
+}
+
+// Operations
+
+pub fn slice<T: EtlValueType, Expr: WrappableExpr<T>>(expr: Expr, range: std::ops::Range<usize>) -> SliceView<T, Expr> {
+    assert!(Expr::DIMENSIONS > 0, "slice cannot work on scalars");
+    assert!(Expr::TYPE.direct(), "slice can only work on direct expressions");
+    assert!(range.start <= range.end, "Invalid slice range ({}..{})", range.start, range.end);
+    assert!(range.end <= expr.dim(0), "Slice range {}..{} is out of bounds ({})", range.start, range.end, expr.dim(0));
+
+    SliceView::<T, Expr>::new(expr, range.start, range.end)
+}
+
+crate::impl_rank_unary_expr!(SliceView<T, Expr>);
+
+crate::impl_add_op_unary_expr!(SliceView<T, Expr>);
+crate::impl_sub_op_unary_expr!(SliceView<T, Expr>);
+crate::impl_mul_op_unary_expr!(SliceView<T, Expr>);
+crate::impl_div_op_unary_expr!(SliceView<T, Expr>);
+crate::impl_scale_op_unary_expr!(SliceView<T, Expr>);
+
+// The tests
+
+#[cfg(test)]
+mod tests {
+    use crate::batch_softmax_expr::batch_softmax;
+    use crate::bias_add_expr::bias_add;
+    use crate::etl_expr::{Etl2d, Etl4d, EtlExpr};
+    use crate::matrix_2d::Matrix2d;
+    use crate::matrix_4d::Matrix4d;
+    use crate::slice_view::slice;
+    use crate::sub_view::sub;
+    use crate::vector::Vector;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn basic_slice_1d() {
+        let a = Vector::<i64>::new_iota(8, 1);
+
+        let expr = slice(&a, 2..5);
+
+        assert_eq!(expr.size(), 3);
+        assert_eq!(expr.dim(0), 3);
+        assert_eq!(expr.get_data(), &[3, 4, 5]);
+    }
+
+    #[test]
+    fn basic_slice_2d() {
+        let a = Matrix2d::<i64>::new_iota(4, 3, 1);
+
+        let expr = slice(&a, 1..3);
+
+        assert_eq!(expr.size(), 6);
+        assert_eq!(expr.rows(), 2);
+        assert_eq!(expr.columns(), 3);
+        assert_eq!(expr.at2(0, 0), 4);
+        assert_eq!(expr.at2(1, 2), 9);
+
+        let mut b = Matrix2d::<i64>::new(2, 3);
+        b |= slice(&a, 1..3) + slice(&a, 2..4);
+
+        assert_eq!(b.at2(0, 0), 11);
+        assert_eq!(b.at2(1, 2), 21);
+    }
+
+    #[test]
+    fn basic_slice_4d() {
+        let a = Matrix4d::<i64>::new_iota(4, 2, 2, 2, 1);
+
+        let expr = slice(&a, 2..4);
+
+        assert_eq!(expr.size(), 16);
+        assert_eq!(expr.dim(0), 2);
+        assert_eq!(expr.dim(1), 2);
+        assert_eq!(expr.dim(3), 2);
+        assert_eq!(expr.at4(0, 0, 0, 0), 17);
+        assert_eq!(expr.at4(1, 1, 1, 1), 32);
+
+        let mut b = Matrix4d::<i64>::new(2, 2, 2, 2);
+        b |= slice(&a, 2..4);
+        assert_eq!(b.at4(1, 0, 1, 0), 27);
+
+        let mut c = Vector::<i64>::new(2);
+        c |= sub(sub(sub(slice(&a, 2..4), 1), 1), 1);
+        assert_eq!(c.at(0), 31);
+        assert_eq!(c.at(1), 32);
+    }
+
+    #[test]
+    fn slice_gemm() {
+        let a = Matrix2d::<i64>::new_iota(4, 3, 1);
+        let w = Matrix2d::<i64>::new_iota(3, 2, 1);
+        let bias = Vector::<i64>::new_iota(2, 1);
+
+        let mut c = Matrix2d::<i64>::new(2, 2);
+        c |= bias_add(slice(&a, 2..4) * &w, &bias);
+
+        assert_eq!(c.at2(0, 0), 77);
+        assert_eq!(c.at2(0, 1), 102);
+        assert_eq!(c.at2(1, 0), 104);
+        assert_eq!(c.at2(1, 1), 138);
+    }
+
+    #[test]
+    fn slice_batch_softmax() {
+        let mut a = Matrix2d::<f64>::new(3, 2);
+        a[0] = 1.0;
+        a[1] = 2.0;
+        a[2] = 3.0;
+        a[3] = 4.0;
+        a[4] = 1.0;
+        a[5] = 5.0;
+
+        let mut c = Matrix2d::<f64>::new(2, 2);
+        c |= batch_softmax(slice(&a, 1..3));
+
+        assert_relative_eq!(c.at2(0, 0), 0.268941, epsilon = 1e-6);
+        assert_relative_eq!(c.at2(0, 1), 0.731058, epsilon = 1e-6);
+        assert_relative_eq!(c.at2(1, 0), 0.017986, epsilon = 1e-6);
+        assert_relative_eq!(c.at2(1, 1), 0.982013, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn slice_empty() {
+        let a = Matrix2d::<f64>::new(0, 3);
+
+        let b = slice(&a, 0..0);
+        assert_eq!(b.size(), 0);
+        assert_eq!(b.get_data().len(), 0);
+    }
+
+    #[test]
+    fn slice_large() {
+        let a = Matrix2d::<f64>::new_iota(1001, 523, 1.0);
+
+        let mut b = Matrix2d::<f64>::new(999, 523);
+        b |= slice(&a, 1..1000);
+
+        assert_eq!(b.at2(0, 0), 524.0);
+        assert_eq!(b.at2(998, 522), 523.0 * 1000.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn slice_out_of_bounds() {
+        let a = Matrix2d::<i64>::new_iota(4, 3, 1);
+        let _expr = slice(&a, 2..5);
+    }
+}
@@ -75,8 +75,8 @@ impl<T: EtlValueType + Float, Expr: WrappableExpr<T>> EtlExpr<T> for SoftmaxExpr
         self.expr.value.rows()
     }
 
-    fn columns(&self) -> usize {
-        self.expr.value.columns()
+    fn dim(&self, i: usize) -> usize {
+        self.expr.value.dim(i)
     }
 
     fn at(&self, i: usize) -> T {
@@ -120,6 +120,9 @@ pub fn softmax<T: EtlValueType + Float, Expr: WrappableExpr<T>>(expr: Expr) -> S
     SoftmaxExpr::<T, Expr>::new(expr, s)
 }
 
+crate::impl_rank_unary_expr_trait!(Float, SoftmaxExpr<T, Expr>);
+crate::impl_fixed_shape_unary_expr_trait!(Float, SoftmaxExpr<T, Expr>);
+
 crate::impl_add_op_unary_expr_trait!(Float, SoftmaxExpr<T, Expr>);
 crate::impl_sub_op_unary_expr_trait!(Float, SoftmaxExpr<T, Expr>);
 crate::impl_mul_op_unary_expr_trait!(Float, SoftmaxExpr<T, Expr>);
@@ -81,8 +81,8 @@ impl<T: EtlValueType + Float, Expr: WrappableExpr<T>> EtlExpr<T> for StableSoftm
         self.expr.value.rows()
     }
 
-    fn columns(&self) -> usize {
-        self.expr.value.columns()
+    fn dim(&self, i: usize) -> usize {
+        self.expr.value.dim(i)
     }
 
     fn at(&self, i: usize) -> T {
@@ -127,6 +127,9 @@ pub fn stable_softmax<T: EtlValueType + Float, Expr: WrappableExpr<T>>(expr: Exp
     StableSoftmaxExpr::<T, Expr>::new(expr, m, s)
 }
 
+crate::impl_rank_unary_expr_trait!(Float, StableSoftmaxExpr<T, Expr>);
+crate::impl_fixed_shape_unary_expr_trait!(Float, StableSoftmaxExpr<T, Expr>);
+
 crate::impl_add_op_unary_expr_trait!(Float, StableSoftmaxExpr<T, Expr>);
 crate::impl_sub_op_unary_expr_trait!(Float, StableSoftmaxExpr<T, Expr>);
 crate::impl_mul_op_unary_expr_trait!(Float, StableSoftmaxExpr<T, Expr>);
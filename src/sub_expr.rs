@@ -1,3 +1,4 @@
+use crate::etl_error::EtlError;
 use crate::etl_expr::*;
 
 // The declaration of SubExpr
@@ -12,14 +13,25 @@ pub struct SubExpr<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: Wrapp
 
 impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> SubExpr<T, LeftExpr, RightExpr> {
     pub fn new(lhs: LeftExpr, rhs: RightExpr) -> Self {
+        match Self::try_new(lhs, rhs) {
+            Ok(expr) => expr,
+            Err(error) => panic!("{error}"),
+        }
+    }
+
+    pub fn try_new(lhs: LeftExpr, rhs: RightExpr) -> Result<Self, EtlError> {
         if LeftExpr::DIMENSIONS > 0 && RightExpr::DIMENSIONS > 0 && lhs.size() != rhs.size() {
-            panic!("Cannot add expressions of different sizes ({} + {})", lhs.size(), rhs.size());
+            return Err(EtlError::ShapeMismatch {
+                op: "subtraction",
+                lhs: expr_dims(&lhs),
+                rhs: expr_dims(&rhs),
+            });
         }
 
-        Self {
+        Ok(Self {
             lhs: lhs.wrap(),
             rhs: rhs.wrap(),
-        }
+        })
     }
 }
 
@@ -81,11 +93,11 @@ impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> E
         }
     }
 
-    fn columns(&self) -> usize {
+    fn dim(&self, i: usize) -> usize {
         if LeftExpr::DIMENSIONS > 0 {
-            self.lhs.value.columns()
+            self.lhs.value.dim(i)
         } else {
-            self.rhs.value.columns()
+            self.rhs.value.dim(i)
         }
     }
 
@@ -93,6 +105,11 @@ impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> E
     fn at(&self, i: usize) -> T {
         self.lhs.value.at(i) - self.rhs.value.at(i)
     }
+
+    #[inline(always)]
+    fn compute_at(&self, i: usize) -> T::Compute {
+        self.lhs.value.compute_at(i) - self.rhs.value.compute_at(i)
+    }
 }
 
 // SubExpr is an EtlWrappable
@@ -125,8 +142,8 @@ impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>> E
 
 #[macro_export]
 macro_rules! impl_sub_op_value {
-    ($type:ty) => {
-        impl<'a, T: EtlValueType, RightExpr: WrappableExpr<T>> std::ops::Sub<RightExpr> for &'a $type {
+    ($type:ty $(, const $dim:ident)*) => {
+        impl<'a, T: EtlValueType, $(const $dim: usize,)* RightExpr: WrappableExpr<T>> std::ops::Sub<RightExpr> for &'a $type {
             type Output = $crate::sub_expr::SubExpr<T, &'a $type, RightExpr>;
 
             fn sub(self, other: RightExpr) -> Self::Output {
@@ -134,10 +151,11 @@ macro_rules! impl_sub_op_value {
             }
         }
 
-        impl<T: EtlValueType, RightExpr: EtlExpr<T>> std::ops::SubAssign<RightExpr> for $type {
+        impl<T: EtlValueType, $(const $dim: usize,)* RightExpr: EtlExpr<T>> std::ops::SubAssign<RightExpr> for $type {
             fn sub_assign(&mut self, other: RightExpr) {
                 validate_assign(self, &other);
-                sub_assign_direct(&mut self.data, &other);
+                let size = self.size();
+                sub_assign_direct(&mut self.data[..size], &other);
             }
         }
     };
@@ -189,7 +207,7 @@ macro_rules! impl_sub_op_binary_expr_simd {
     ($type:ty) => {
         impl<T: EtlValueType, LeftExpr: WrappableExpr<T>, RightExpr: WrappableExpr<T>, OuterRightExpr: WrappableExpr<T>> std::ops::Sub<OuterRightExpr> for $type
         where
-            Simd<T, 8>: SimdHelper,
+            T: $crate::base_traits::Vectorizable,
         {
             type Output = $crate::sub_expr::SubExpr<T, $type, OuterRightExpr>;
 
@@ -226,6 +244,9 @@ macro_rules! impl_sub_op_unary_expr_trait {
     };
 }
 
+crate::impl_rank_binary_expr!(SubExpr<T, LeftExpr, RightExpr>);
+crate::impl_fixed_shape_binary_expr!(SubExpr<T, LeftExpr, RightExpr>);
+
 crate::impl_add_op_binary_expr!(SubExpr<T, LeftExpr, RightExpr>);
 crate::impl_sub_op_binary_expr!(SubExpr<T, LeftExpr, RightExpr>);
 crate::impl_mul_op_binary_expr!(SubExpr<T, LeftExpr, RightExpr>);
@@ -339,4 +360,38 @@ mod tests {
 
         assert_eq!(c.at(0), 1);
     }
+
+    #[test]
+    fn unsigned() {
+        let a = Matrix2d::<u32>::new_iota(3, 3, 10);
+        let b = Matrix2d::<u32>::new_iota(3, 3, 1);
+
+        let mut c = Matrix2d::<u32>::new(3, 3);
+        c |= &a - &b;
+
+        for i in 0..9 {
+            assert_eq!(c.at(i), 9);
+        }
+    }
+
+    #[test]
+    fn unsigned_padding() {
+        let mut a = Vector::<u8>::new_iota(5, 10);
+        let mut b = Vector::<u8>::new_iota(5, 1);
+
+        // The padding of the operands is not part of the values
+        a.data[6] = 1;
+        b.data[6] = 200;
+        b.data[7] = 255;
+
+        let mut c = Vector::<u8>::new(5);
+        c |= &a - &b;
+        assert_eq!(c.data[..], [9, 9, 9, 9, 9, 0, 0, 0]);
+
+        c -= &a - &b;
+        assert_eq!(c.data[..], [0, 0, 0, 0, 0, 0, 0, 0]);
+
+        let d = Vector::<u8>::new_from_expr(&a - &b);
+        assert_eq!(d.data[..], [9, 9, 9, 9, 9, 0, 0, 0]);
+    }
 }
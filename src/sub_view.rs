@@ -1,4 +1,8 @@
 use crate::etl_expr::*;
+use crate::matrix_2d::Matrix2d;
+use crate::matrix_3d::Matrix3d;
+use crate::matrix_4d::Matrix4d;
+use crate::matrix_view::MatrixViewMut;
 
 // The declaration of SubView
 
@@ -48,20 +52,12 @@ impl<T: EtlValueType, Expr: WrappableExpr<T>> EtlExpr<T> for SubView<T, Expr> {
         self.expr.value.dim(1)
     }
 
-    fn columns(&self) -> usize {
-        self.expr.value.dim(2)
-    }
-
     fn dim(&self, i: usize) -> usize {
         self.expr.value.dim(i + 1)
     }
 
     fn at(&self, i: usize) -> T {
-        self.expr.value.at2(self.index, i)
-    }
-
-    fn at2(&self, i: usize, j: usize) -> T {
-        self.expr.value.at3(self.index, i, j)
+        self.get_data()[i]
     }
 
     fn get_data(&self) -> &[T] {
@@ -69,6 +65,10 @@ impl<T: EtlValueType, Expr: WrappableExpr<T>> EtlExpr<T> for SubView<T, Expr> {
     }
 }
 
+// SubView has one dimension less than its expression
+impl<T: EtlValueType, Expr: WrappableExpr<T> + Etl3d<T>> Etl2d<T> for SubView<T, Expr> {}
+impl<T: EtlValueType, Expr: WrappableExpr<T> + Etl4d<T>> Etl3d<T> for SubView<T, Expr> {}
+
 // SubView is an EtlWrappable
 // SubView wraps as value
 impl<T: EtlValueType, Expr: WrappableExpr<T>> EtlWrappable<T> for SubView<T, Expr> {
@@ -104,6 +104,73 @@ pub fn sub<T: EtlValueType, Expr: WrappableExpr<T>>(expr: Expr, i: usize) -> Sub
     SubView::<T, Expr>::new(expr, i)
 }
 
+// Mutable sub views are only available on containers, since they need to borrow the storage
+
+/// A container from which a mutable view of the i-th sub element can be taken
+pub trait EtlSubMut<T: EtlValueType> {
+    type View<'a>
+    where
+        Self: 'a;
+
+    fn sub_mut(&mut self, i: usize) -> Self::View<'_>;
+}
+
+impl<T: EtlValueType> EtlSubMut<T> for Matrix2d<T> {
+    type View<'a>
+        = MatrixViewMut<'a, T, 1>
+    where
+        Self: 'a;
+
+    fn sub_mut(&mut self, i: usize) -> Self::View<'_> {
+        assert!(i < self.dim(0));
+
+        let n = self.dim(1);
+        MatrixViewMut::new(&mut self.data[i * n..(i + 1) * n], [n])
+    }
+}
+
+impl<T: EtlValueType> EtlSubMut<T> for Matrix3d<T> {
+    type View<'a>
+        = MatrixViewMut<'a, T, 2>
+    where
+        Self: 'a;
+
+    fn sub_mut(&mut self, i: usize) -> Self::View<'_> {
+        assert!(i < self.dim(0));
+
+        let (n, k) = (self.dim(1), self.dim(2));
+        MatrixViewMut::new(&mut self.data[i * n * k..(i + 1) * n * k], [n, k])
+    }
+}
+
+impl<T: EtlValueType> EtlSubMut<T> for Matrix4d<T> {
+    type View<'a>
+        = MatrixViewMut<'a, T, 3>
+    where
+        Self: 'a;
+
+    fn sub_mut(&mut self, i: usize) -> Self::View<'_> {
+        assert!(i < self.dim(0));
+
+        let (c, w, h) = (self.dim(1), self.dim(2), self.dim(3));
+        MatrixViewMut::new(&mut self.data[i * c * w * h..(i + 1) * c * w * h], [c, w, h])
+    }
+}
+
+pub fn sub_mut<T: EtlValueType, Container: EtlSubMut<T>>(container: &mut Container, i: usize) -> Container::View<'_> {
+    container.sub_mut(i)
+}
+
+// A row is simply the sub view of a 2D matrix
+
+pub fn row<T: EtlValueType, Expr: WrappableExpr<T> + Etl2d<T>>(expr: Expr, i: usize) -> SubView<T, Expr> {
+    sub(expr, i)
+}
+
+pub fn row_mut<T: EtlValueType>(matrix: &mut Matrix2d<T>, i: usize) -> MatrixViewMut<'_, T, 1> {
+    matrix.sub_mut(i)
+}
+
 crate::impl_add_op_unary_expr!(SubView<T, Expr>);
 crate::impl_sub_op_unary_expr!(SubView<T, Expr>);
 crate::impl_mul_op_unary_expr!(SubView<T, Expr>);
@@ -114,11 +181,12 @@ crate::impl_scale_op_unary_expr!(SubView<T, Expr>);
 
 #[cfg(test)]
 mod tests {
-    use crate::etl_expr::EtlExpr;
+    use crate::constant::cst;
+    use crate::etl_expr::{Etl2d, Etl3d, Etl4d, EtlExpr};
     use crate::matrix_2d::Matrix2d;
     use crate::matrix_3d::Matrix3d;
     use crate::matrix_4d::Matrix4d;
-    use crate::sub_view::sub;
+    use crate::sub_view::{row, row_mut, sub, sub_mut};
     use crate::vector::Vector;
 
     #[test]
@@ -223,7 +291,6 @@ mod tests {
         assert_eq!(expr.dim(2), 2);
 
         assert_eq!(expr.rows(), 4);
-        assert_eq!(expr.columns(), 3);
 
         let mut b = Matrix3d::<i64>::new(4, 3, 2);
         b |= sub(&a, 1);
@@ -275,4 +342,80 @@ mod tests {
         assert_eq!(b.at(0), 27);
         assert_eq!(b.at(1), 28);
     }
+
+    #[test]
+    fn sub_mut_2d() {
+        let mut a = Matrix2d::<i64>::new(3, 4);
+        let b = Vector::<i64>::new_iota(4, 1);
+
+        sub_mut(&mut a, 1).inplace_axpy(0, 2, &b);
+        let mut row = sub_mut(&mut a, 2);
+        row |= &b + cst(1);
+
+        assert_eq!(a.at2(0, 3), 0);
+        assert_eq!(a.at2(1, 0), 2);
+        assert_eq!(a.at2(1, 3), 8);
+        assert_eq!(a.at2(2, 0), 2);
+        assert_eq!(a.at2(2, 3), 5);
+    }
+
+    #[test]
+    fn sub_mut_3d() {
+        let mut a = Matrix3d::<i64>::new(2, 2, 3);
+        let b = Matrix2d::<i64>::new_iota(2, 3, 1);
+
+        let mut view = sub_mut(&mut a, 1);
+        view |= &b >> &b;
+        view += &b;
+        view -= cst(2);
+        view /= cst(2);
+        view >>= &b;
+
+        assert_eq!(a.at3(0, 1, 2), 0);
+        assert_eq!(a.at3(1, 0, 0), 0);
+        assert_eq!(a.at3(1, 0, 1), 4);
+        assert_eq!(a.at3(1, 1, 2), 120);
+    }
+
+    #[test]
+    fn sub_mut_4d() {
+        let mut a = Matrix4d::<i64>::new(2, 2, 2, 2);
+        let b = Matrix3d::<i64>::new_iota(2, 2, 2, 1);
+
+        let mut view = sub_mut(&mut a, 1);
+        view |= &b;
+
+        assert_eq!(a.at4(0, 1, 1, 1), 0);
+        assert_eq!(a.at4(1, 0, 0, 0), 1);
+        assert_eq!(a.at4(1, 1, 1, 1), 8);
+    }
+
+    #[test]
+    #[should_panic]
+    fn sub_mut_invalid_shape() {
+        let mut a = Matrix3d::<i64>::new(2, 2, 3);
+        let b = Matrix2d::<i64>::new_iota(2, 2, 1);
+
+        let mut view = sub_mut(&mut a, 0);
+        view |= &b;
+    }
+
+    #[test]
+    fn row_2d() {
+        let mut a = Matrix2d::<i64>::new_iota(3, 2, 1);
+        let b = Matrix2d::<i64>::new_iota(3, 2, 1);
+
+        let mut c = Vector::<i64>::new(2);
+        c |= row(&a, 2) + row(&b, 0);
+
+        assert_eq!(c.at(0), 6);
+        assert_eq!(c.at(1), 8);
+
+        let mut view = row_mut(&mut a, 1);
+        view += row(&b, 2);
+
+        assert_eq!(a.at2(1, 0), 8);
+        assert_eq!(a.at2(1, 1), 10);
+        assert_eq!(a.at2(2, 0), 5);
+    }
 }
@@ -0,0 +1,502 @@
+use crate::aligned_buffer::AlignedBuffer;
+use crate::base_traits::Constants;
+use crate::etl_error::EtlError;
+use crate::etl_expr::*;
+use crate::print::{fmt_container, fmt_container_debug};
+use crate::shape::Shape;
+
+use std::{fmt, ops::BitOrAssign};
+
+use rand::Rng;
+use rand_distr::*;
+
+// The declaration of Tensor<T, D>
+
+/// A D-dimensional container with a runtime shape
+///
+/// The storage is row-major and padded, exactly like the fixed-rank containers
+#[derive(Clone)]
+pub struct Tensor<T: EtlValueType, const D: usize> {
+    pub data: AlignedBuffer<T>,
+    shape: Shape<D>,
+}
+
+// The functions of Tensor<T, D>
+
+impl<T: EtlValueType, const D: usize> Tensor<T, D> {
+    pub fn new(dims: [usize; D]) -> Self {
+        assert!(D > 0, "Tensor must have at least one dimension");
+
+        let shape = Shape::new(dims);
+
+        Self {
+            data: AlignedBuffer::new(padded_size(shape.size())),
+            shape,
+        }
+    }
+
+    pub fn new_from_expr<Expr: EtlExpr<T>>(expr: Expr) -> Self {
+        assert_eq!(Expr::DIMENSIONS, D);
+
+        let mut dims = [0; D];
+        for (i, dim) in dims.iter_mut().enumerate() {
+            *dim = expr.dim(i);
+        }
+
+        let mut tensor = Self::new(dims);
+
+        let size = expr.size();
+        assign_direct(&mut tensor.data[..size], &expr);
+
+        tensor
+    }
+
+    pub fn try_new_from_expr<Expr: EtlExpr<T>>(expr: Expr) -> Result<Self, EtlError> {
+        if Expr::DIMENSIONS != D {
+            return Err(EtlError::RankMismatch {
+                op: "construction of Tensor",
+                expected: D,
+                actual: Expr::DIMENSIONS,
+            });
+        }
+
+        Ok(Self::new_from_expr(expr))
+    }
+
+    pub fn new_copy(rhs: &Tensor<T, D>) -> Self {
+        Self {
+            data: rhs.data.clone(),
+            shape: rhs.shape,
+        }
+    }
+
+    pub fn new_iota(dims: [usize; D], value: T) -> Self {
+        let mut tensor = Self::new(dims);
+        tensor.iota_fill(value);
+        tensor
+    }
+
+    pub fn new_rand(dims: [usize; D]) -> Self
+    where
+        rand::distr::StandardUniform: rand::distr::Distribution<T>,
+    {
+        let mut tensor = Self::new(dims);
+        tensor.rand_fill();
+        tensor
+    }
+
+    pub fn new_rand_normal(dims: [usize; D]) -> Self
+    where
+        StandardNormal: Distribution<T>,
+        T: EtlValueType + rand_distr::num_traits::Float,
+    {
+        let mut tensor = Self::new(dims);
+        tensor.rand_fill_normal();
+        tensor
+    }
+
+    pub fn new_rand_normal_ms(dims: [usize; D], mean: T, stddev: T) -> Self
+    where
+        StandardNormal: Distribution<T>,
+        T: EtlValueType + rand_distr::num_traits::Float,
+    {
+        let mut tensor = Self::new(dims);
+        tensor.rand_fill_normal_ms(mean, stddev);
+        tensor
+    }
+
+    pub fn shape(&self) -> &Shape<D> {
+        &self.shape
+    }
+
+    pub fn at_nd(&self, index: &[usize]) -> T {
+        self.data[self.shape.offset(index)]
+    }
+
+    pub fn at_nd_mut(&mut self, index: &[usize]) -> &mut T {
+        &mut self.data[self.shape.offset(index)]
+    }
+
+    pub fn clear(&mut self) {
+        self.data.fill(T::default());
+    }
+
+    pub fn fill(&mut self, value: T) {
+        let size = self.size();
+        self.data[..size].fill(value);
+    }
+
+    pub fn iota_fill(&mut self, value: T) {
+        let size = self.size();
+        iota_direct(&mut self.data[..size], value);
+    }
+
+    pub fn rand_fill(&mut self)
+    where
+        rand::distr::StandardUniform: rand::distr::Distribution<T>,
+    {
+        let mut rng = rand::rng();
+        let size = self.size();
+
+        for value in self.data[..size].iter_mut() {
+            *value = rng.random::<T>();
+        }
+    }
+
+    pub fn rand_fill_normal(&mut self)
+    where
+        StandardNormal: Distribution<T>,
+        T: EtlValueType + rand_distr::num_traits::Float,
+    {
+        let mut rng = rand::rng();
+        let n = <T as Constants>::zero();
+        let p = <T as Constants>::one();
+        let normal = Normal::new(n, p).unwrap();
+        let size = self.size();
+
+        for value in self.data[..size].iter_mut() {
+            *value = normal.sample(&mut rng);
+        }
+    }
+
+    pub fn rand_fill_normal_ms(&mut self, mean: T, stddev: T)
+    where
+        StandardNormal: Distribution<T>,
+        T: EtlValueType + rand_distr::num_traits::Float,
+    {
+        let mut rng = rand::rng();
+        let normal = Normal::new(mean, stddev).unwrap();
+        let size = self.size();
+
+        for value in self.data[..size].iter_mut() {
+            *value = normal.sample(&mut rng);
+        }
+    }
+
+    pub fn inplace_axpy<RightExpr: EtlExpr<T>>(&mut self, alpha: T, beta: T, y: RightExpr) {
+        validate_assign(self, &y);
+        let size = self.size();
+        axpy_direct(&mut self.data[..size], alpha, beta, &y);
+    }
+
+    /// Assign an expression that reads this container, evaluated with evaluate, e.g.
+    /// `x.assign_with(|x| evaluate((&b >> x) + &z))` for `x = b * x + z`
+    pub fn assign_with(&mut self, f: impl FnOnce(&Self) -> Evaluated<T>) {
+        let rhs = f(self);
+        let size = self.size();
+        assign_evaluated(expr_dims(self), &mut self.data[..size], rhs);
+    }
+
+    pub fn try_assign<RightExpr: EtlExpr<T>>(&mut self, rhs: RightExpr) -> Result<(), EtlError> {
+        try_validate_assign(self, &rhs)?;
+        let size = self.size();
+        assign_direct(&mut self.data[..size], &rhs);
+        Ok(())
+    }
+}
+
+impl<T: EtlValueType, const D: usize> EtlExpr<T> for Tensor<T, D> {
+    const DIMENSIONS: usize = D;
+    const TYPE: EtlType = EtlType::Value;
+    const THREAD_SAFE: bool = true;
+
+    type Iter<'x>
+        = std::iter::Cloned<std::slice::Iter<'x, T>>
+    where
+        T: 'x;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.data.iter().cloned()
+    }
+
+    fn iter_range(&self, range: std::ops::Range<usize>) -> Self::Iter<'_> {
+        self.data[range].iter().cloned()
+    }
+
+    fn size(&self) -> usize {
+        self.shape.size()
+    }
+
+    fn rows(&self) -> usize {
+        self.shape.dim(0)
+    }
+
+    fn dim(&self, i: usize) -> usize {
+        self.shape.dim(i)
+    }
+
+    #[inline(always)]
+    fn at(&self, i: usize) -> T {
+        self.data[i]
+    }
+
+    fn get_data(&self) -> &[T] {
+        &self.data
+    }
+}
+
+impl<T: EtlValueType> Etl2d<T> for Tensor<T, 2> {
+    fn at2(&self, i1: usize, i2: usize) -> T {
+        self.at_nd(&[i1, i2])
+    }
+}
+
+impl<T: EtlValueType> Etl3d<T> for Tensor<T, 3> {
+    fn at3(&self, i1: usize, i2: usize, i3: usize) -> T {
+        self.at_nd(&[i1, i2, i3])
+    }
+}
+
+impl<T: EtlValueType> Etl4d<T> for Tensor<T, 4> {
+    fn at4(&self, i1: usize, i2: usize, i3: usize, i4: usize) -> T {
+        self.at_nd(&[i1, i2, i3, i4])
+    }
+}
+
+impl<T: EtlValueType, const D: usize> EtlExpr<T> for &Tensor<T, D> {
+    const DIMENSIONS: usize = D;
+    const TYPE: EtlType = EtlType::Value;
+    const THREAD_SAFE: bool = true;
+
+    type Iter<'x>
+        = std::iter::Cloned<std::slice::Iter<'x, T>>
+    where
+        T: 'x,
+        Self: 'x;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.data.iter().cloned()
+    }
+
+    fn iter_range(&self, range: std::ops::Range<usize>) -> Self::Iter<'_> {
+        self.data[range].iter().cloned()
+    }
+
+    fn size(&self) -> usize {
+        self.shape.size()
+    }
+
+    fn rows(&self) -> usize {
+        self.shape.dim(0)
+    }
+
+    fn dim(&self, i: usize) -> usize {
+        self.shape.dim(i)
+    }
+
+    #[inline(always)]
+    fn at(&self, i: usize) -> T {
+        self.data[i]
+    }
+
+    fn get_data(&self) -> &[T] {
+        &self.data
+    }
+}
+
+impl<T: EtlValueType> Etl2d<T> for &Tensor<T, 2> {
+    fn at2(&self, i1: usize, i2: usize) -> T {
+        self.at_nd(&[i1, i2])
+    }
+}
+
+impl<T: EtlValueType> Etl3d<T> for &Tensor<T, 3> {
+    fn at3(&self, i1: usize, i2: usize, i3: usize) -> T {
+        self.at_nd(&[i1, i2, i3])
+    }
+}
+
+impl<T: EtlValueType> Etl4d<T> for &Tensor<T, 4> {
+    fn at4(&self, i1: usize, i2: usize, i3: usize, i4: usize) -> T {
+        self.at_nd(&[i1, i2, i3, i4])
+    }
+}
+
+// Tensor<T, D> wraps as reference
+impl<'a, T: EtlValueType, const D: usize> EtlWrappable<T> for &'a Tensor<T, D> {
+    type WrappedAs = &'a Tensor<T, D>;
+
+    fn wrap(self) -> EtlWrapper<T, Self::WrappedAs> {
+        EtlWrapper {
+            value: self,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+// Tensor<T, D> computes as itself
+impl<T: EtlValueType, const D: usize> EtlComputable<T> for &Tensor<T, D> {
+    fn to_data(&self) -> Vec<T> {
+        self.data.to_vec()
+    }
+}
+
+impl<T: EtlValueType, const D: usize> fmt::Display for Tensor<T, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_container(f, &self.data, self.shape.dims())
+    }
+}
+
+impl<T: EtlValueType, const D: usize> fmt::Debug for Tensor<T, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_container_debug(f, "Tensor", &self.data, self.shape.dims())
+    }
+}
+
+// Operator overloading for Tensor<T, D>
+
+impl<T: EtlValueType, const D: usize> std::ops::Index<usize> for Tensor<T, D> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.data[index]
+    }
+}
+
+impl<T: EtlValueType, const D: usize> std::ops::IndexMut<usize> for Tensor<T, D> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        &mut self.data[index]
+    }
+}
+
+// Since we can't overload Assign, we settle for BitOrAssign
+impl<T: EtlValueType, const D: usize, RightExpr: EtlExpr<T>> BitOrAssign<RightExpr> for Tensor<T, D> {
+    fn bitor_assign(&mut self, rhs: RightExpr) {
+        validate_assign(self, &rhs);
+        let size = self.size();
+        assign_direct(&mut self.data[..size], &rhs);
+    }
+}
+
+// Operations
+
+crate::impl_add_op_value!(Tensor<T, D>, const D);
+crate::impl_sub_op_value!(Tensor<T, D>, const D);
+crate::impl_mul_op_value!(Tensor<T, D>, const D);
+crate::impl_div_op_value!(Tensor<T, D>, const D);
+crate::impl_scale_op_value!(Tensor<T, D>, const D);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::matrix_2d::Matrix2d;
+    use crate::sigmoid_expr::sigmoid;
+    use crate::sub_view::sub;
+
+    #[test]
+    fn construct_5d() {
+        let tensor = Tensor::<i64, 5>::new([2, 3, 4, 5, 6]);
+        assert_eq!(tensor.size(), 720);
+        assert_eq!(tensor.rows(), 2);
+        assert_eq!(tensor.dim(0), 2);
+        assert_eq!(tensor.dim(1), 3);
+        assert_eq!(tensor.dim(2), 4);
+        assert_eq!(tensor.dim(3), 5);
+        assert_eq!(tensor.dim(4), 6);
+    }
+
+    #[test]
+    fn at_nd() {
+        let mut tensor = Tensor::<i64, 5>::new_iota([2, 3, 4, 5, 6], 0);
+
+        assert_eq!(tensor.at_nd(&[0, 0, 0, 0, 0]), 0);
+        assert_eq!(tensor.at_nd(&[0, 0, 0, 0, 5]), 5);
+        assert_eq!(tensor.at_nd(&[0, 0, 0, 1, 0]), 6);
+        assert_eq!(tensor.at_nd(&[1, 2, 3, 4, 5]), 719);
+
+        *tensor.at_nd_mut(&[1, 0, 0, 0, 1]) = 42;
+        assert_eq!(tensor.at(361), 42);
+    }
+
+    #[test]
+    #[should_panic]
+    fn at_nd_out_of_bounds() {
+        let tensor = Tensor::<i64, 3>::new([2, 3, 4]);
+        tensor.at_nd(&[0, 3, 0]);
+    }
+
+    #[test]
+    fn at2() {
+        let tensor = Tensor::<i64, 2>::new_iota([2, 3], 1);
+
+        assert_eq!(tensor.at2(0, 0), 1);
+        assert_eq!(tensor.at2(0, 2), 3);
+        assert_eq!(tensor.at2(1, 0), 4);
+        assert_eq!(tensor.at2(1, 2), 6);
+    }
+
+    #[test]
+    fn print() {
+        let a = Tensor::<i32, 1>::new_iota([3], 1);
+        let b = Tensor::<i32, 2>::new_iota([3, 2], 1);
+        let c = Tensor::<i32, 3>::new_iota([2, 2, 2], 1);
+
+        assert_eq!(format!("{a}"), "[1,2,3]");
+        assert_eq!(format!("{b}"), "[[1,2]\n [3,4]\n [5,6]]");
+        assert_eq!(format!("{c}"), "[[[1,2]\n  [3,4]]\n [[5,6]\n  [7,8]]]");
+    }
+
+    #[test]
+    fn compound() {
+        let mut a = Tensor::<i64, 5>::new_iota([2, 1, 2, 1, 2], 1);
+        let b = Tensor::<i64, 5>::new_iota([2, 1, 2, 1, 2], 10);
+
+        a += &b;
+
+        assert_eq!(a.at(0), 11);
+        assert_eq!(a.at(7), 8 + 17);
+
+        a |= &a.clone() + &b;
+
+        assert_eq!(a.at(0), 21);
+        assert_eq!(a.at(7), 8 + 17 + 17);
+    }
+
+    #[test]
+    fn expressions() {
+        let a = Tensor::<f64, 4>::new_iota([2, 2, 2, 2], 1.0);
+        let mut b = Tensor::<f64, 4>::new([2, 2, 2, 2]);
+
+        b |= sigmoid(&a) >> &a;
+
+        for i in 0..16 {
+            let x = (i + 1) as f64;
+            approx::assert_relative_eq!(b.at(i), x / (1.0 + (-x).exp()), epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn gemm() {
+        let a = Tensor::<i64, 2>::new_iota([2, 3], 1);
+        let b = Matrix2d::<i64>::new_iota(3, 2, 7);
+        let mut c = Tensor::<i64, 2>::new([2, 2]);
+
+        c |= &a * &b;
+
+        assert_eq!(c.at2(0, 0), 58);
+        assert_eq!(c.at2(0, 1), 64);
+        assert_eq!(c.at2(1, 0), 139);
+        assert_eq!(c.at2(1, 1), 154);
+    }
+
+    #[test]
+    fn sub_view() {
+        let a = Tensor::<i64, 3>::new_iota([2, 3, 2], 1);
+        let mut b = Tensor::<i64, 2>::new([3, 2]);
+
+        b |= sub(&a, 1);
+
+        assert_eq!(b.at2(0, 0), 7);
+        assert_eq!(b.at2(2, 1), 12);
+    }
+
+    #[test]
+    fn from_expr() {
+        let a = Tensor::<i64, 3>::new_iota([2, 3, 2], 1);
+        let b = Tensor::<i64, 3>::new_from_expr(&a + &a);
+
+        assert_eq!(b.shape(), a.shape());
+        assert_eq!(b.at3(1, 2, 1), 24);
+    }
+}
@@ -1,11 +1,18 @@
+use crate::aligned_buffer::AlignedBuffer;
 use crate::etl_expr::*;
 
+use std::sync::OnceLock;
+
 // The declaration of TransposeExpr
 
+/// A lazy transposed view of a 2D expression
+///
+/// Elements are read with a stride from the data of the sub expression. If the sub expression is
+/// not direct, its values (not transposed) are computed once, when they are first read.
 #[derive(Clone)]
 pub struct TransposeExpr<T: EtlValueType, Expr: WrappableExpr<T>> {
     expr: EtlWrapper<T, Expr::WrappedAs>,
-    pub temp: Vec<T>,
+    source: OnceLock<AlignedBuffer<T>>,
 }
 
 // The functions of TransposeExpr
@@ -13,107 +20,92 @@ pub struct TransposeExpr<T: EtlValueType, Expr: WrappableExpr<T>> {
 impl<T: EtlValueType, Expr: WrappableExpr<T>> TransposeExpr<T, Expr> {
     pub fn new(expr: Expr) -> Self {
         if Expr::DIMENSIONS != 2 {
-            panic!("Invalid vector matrix multiplication dimensions ({}D)", Expr::DIMENSIONS);
+            panic!("Invalid transpose dimensions ({}D)", Expr::DIMENSIONS);
         }
 
-        let mut expr = Self {
+        Self {
             expr: expr.wrap(),
-            temp: Vec::<T>::new(),
-        };
-
-        let mut temp = vec![T::default(); padded_size(expr.size())];
-        expr.compute_transpose_impl(&mut temp);
-        expr.temp = temp;
-
-        expr
-    }
-
-    fn compute_transpose(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        output[..self.temp.len()].copy_from_slice(&self.temp[..]);
-    }
-
-    fn compute_transpose_add(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (n, value) in output.iter_mut().enumerate() {
-            *value += self.temp[n];
+            source: OnceLock::new(),
         }
     }
 
-    fn compute_transpose_sub(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
-
-        for (n, value) in output.iter_mut().enumerate() {
-            *value -= self.temp[n];
+    fn source_data(&self) -> &[T] {
+        if Expr::TYPE.direct() {
+            self.expr.value.get_data()
+        } else {
+            self.source.get_or_init(|| workspace_data(&self.expr.value))
         }
     }
 
-    fn compute_transpose_scale(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
+    fn compute_transpose_impl(&self, output: &mut [T]) {
+        let m = self.expr.value.rows();
+        let n = self.expr.value.dim(1);
+        let expr = self.source_data();
 
-        for (n, value) in output.iter_mut().enumerate() {
-            *value *= self.temp[n];
+        for row in 0..m {
+            for column in 0..n {
+                output[column * m + row] = expr[row * n + column];
+            }
         }
     }
+}
 
-    fn compute_transpose_div(&self, output: &mut [T]) {
-        assert!(!self.temp.is_empty());
+// The iterator for transpose expressions
 
-        for (n, value) in output.iter_mut().enumerate() {
-            *value /= self.temp[n];
-        }
-    }
+pub struct TransposeIter<'a, T: EtlValueType> {
+    data: &'a [T],
+    m: usize,
+    n: usize,
+    index: usize,
+    end: usize,
+}
 
-    fn compute_transpose_impl(&self, output: &mut [T]) {
-        if Expr::DIMENSIONS == 2 {
-            let m = self.expr.value.rows();
-            let n = self.expr.value.columns();
-
-            let functor = |out: &mut [T], expr: &[T]| {
-                for row in 0..m {
-                    for column in 0..n {
-                        out[column * m + row] = expr[row * n + column];
-                    }
-                }
-            };
-
-            forward_data_unary(output, &self.expr.value, functor);
+impl<T: EtlValueType> Iterator for TransposeIter<'_, T> {
+    type Item = T;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index < self.end {
+            // Element (i / m, i % m) of the transpose is element (i % m, i / m) of the sub expression
+            let value = self.data[(self.index % self.m) * self.n + self.index / self.m];
+            self.index += 1;
+            Some(value)
         } else {
-            panic!("This code should be unreachable!");
+            None
         }
     }
 
-    fn validate_transpose<OutputExpr: EtlExpr<T>>(&self, expr: &OutputExpr) {
-        if Expr::DIMENSIONS == 2 {
-            if expr.rows() != self.expr.value.columns() || expr.columns() != self.expr.value.rows() {
-                panic!("Invalid dimensions for assignment of GEMM result");
-            }
-        } else {
-            panic!("This code should be unreachable!");
-        }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.index;
+        (remaining, Some(remaining))
     }
 }
 
 // TransposeExpr is an EtlExpr
 impl<T: EtlValueType, Expr: WrappableExpr<T>> EtlExpr<T> for TransposeExpr<T, Expr> {
     const DIMENSIONS: usize = 2;
-    const TYPE: EtlType = EtlType::Smart;
+    const TYPE: EtlType = EtlType::Unaligned;
     const THREAD_SAFE: bool = true;
+    const TRANSPOSED: bool = true;
 
     type Iter<'x>
-        = std::iter::Cloned<std::slice::Iter<'x, T>>
+        = TransposeIter<'x, T>
     where
         T: 'x,
         Self: 'x;
 
     fn iter(&self) -> Self::Iter<'_> {
-        self.temp.iter().cloned()
+        self.iter_range(0..self.size())
     }
 
     fn iter_range(&self, range: std::ops::Range<usize>) -> Self::Iter<'_> {
-        self.temp[range].iter().cloned()
+        TransposeIter {
+            data: self.source_data(),
+            m: self.expr.value.rows(),
+            n: self.expr.value.dim(1),
+            index: range.start,
+            end: range.end.min(self.size()),
+        }
     }
 
     fn size(&self) -> usize {
@@ -121,43 +113,30 @@ impl<T: EtlValueType, Expr: WrappableExpr<T>> EtlExpr<T> for TransposeExpr<T, Ex
     }
 
     fn rows(&self) -> usize {
-        self.expr.value.columns()
-    }
-
-    fn columns(&self) -> usize {
-        self.expr.value.rows()
-    }
-
-    fn validate_assign<OutputExpr: EtlExpr<T>>(&self, expr: &OutputExpr) {
-        self.validate_transpose(expr);
-    }
-
-    fn compute_into(&self, output: &mut Vec<T>) {
-        self.compute_transpose(output);
-    }
-
-    fn compute_into_add(&self, output: &mut Vec<T>) {
-        self.compute_transpose_add(output);
+        self.expr.value.dim(1)
     }
 
-    fn compute_into_sub(&self, output: &mut Vec<T>) {
-        self.compute_transpose_sub(output);
-    }
-
-    fn compute_into_scale(&self, output: &mut Vec<T>) {
-        self.compute_transpose_scale(output);
+    fn dim(&self, i: usize) -> usize {
+        match i {
+            0 => self.rows(),
+            1 => self.expr.value.rows(),
+            _ => panic!("Invalid dimension access ({i} for 2D)"),
+        }
     }
 
-    fn compute_into_div(&self, output: &mut Vec<T>) {
-        self.compute_transpose_div(output);
+    fn at(&self, i: usize) -> T {
+        self.at2(i / self.columns(), i % self.columns())
     }
 
-    fn at(&self, i: usize) -> T {
-        self.temp[i]
+    fn get_transposed_data(&self) -> &[T] {
+        self.source_data()
     }
+}
 
-    fn get_data(&self) -> &[T] {
-        &self.temp
+// TransposeExpr is an Etl2d
+impl<T: EtlValueType, Expr: WrappableExpr<T>> Etl2d<T> for TransposeExpr<T, Expr> {
+    fn at2(&self, row: usize, column: usize) -> T {
+        self.source_data()[column * self.expr.value.dim(1) + row]
     }
 }
 
@@ -173,13 +152,18 @@ impl<T: EtlValueType, Expr: WrappableExpr<T>> EtlWrappable<T> for TransposeExpr<
     }
 }
 
-// TransposeExpr computes as copy
+// TransposeExpr computes as a transposed copy
 impl<T: EtlValueType, Expr: WrappableExpr<T>> EtlComputable<T> for TransposeExpr<T, Expr> {
     fn to_data(&self) -> Vec<T> {
-        self.temp.clone()
+        let mut vec = vec![T::default(); padded_size(self.size())];
+        self.compute_transpose_impl(&mut vec);
+        vec
     }
 }
 
+// The transpose of a fixed matrix has the fixed shape of the transposed matrix
+impl<T: EtlValueType, const R: usize, const C: usize, Expr: WrappableExpr<T> + FixedShape<[[(); C]; R]>> FixedShape<[[(); R]; C]> for TransposeExpr<T, Expr> {}
+
 // Operations
 
 crate::impl_add_op_unary_expr!(TransposeExpr<T, Expr>);
@@ -196,9 +180,10 @@ pub fn transpose<T: EtlValueType, Expr: WrappableExpr<T>>(expr: Expr) -> Transpo
 
 #[cfg(test)]
 mod tests {
-    use crate::etl_expr::EtlExpr;
+    use crate::etl_expr::{Etl2d, EtlExpr};
     use crate::matrix_2d::Matrix2d;
     use crate::transpose_expr::transpose;
+    use crate::vector::Vector;
 
     #[test]
     fn transpose_a() {
@@ -249,4 +234,61 @@ mod tests {
         assert_eq!(c.at2(1, 2), a.at2(2, 1));
         assert_eq!(c.at2(2, 2), a.at2(2, 2));
     }
+
+    #[test]
+    fn transpose_lazy() {
+        let a = Matrix2d::<i64>::new_iota(2, 3, 1);
+
+        let expr = transpose(&a);
+
+        assert_eq!(expr.rows(), 3);
+        assert_eq!(expr.columns(), 2);
+        assert_eq!(expr.at2(2, 1), 6);
+        assert_eq!(expr.at(1), 4);
+
+        let values: Vec<i64> = expr.iter().collect();
+        assert_eq!(values, vec![1, 4, 2, 5, 3, 6]);
+
+        let values: Vec<i64> = expr.iter_range(3..5).collect();
+        assert_eq!(values, vec![5, 3]);
+    }
+
+    #[test]
+    fn transpose_expr() {
+        let a = Matrix2d::<i64>::new_iota(2, 3, 1);
+        let b = Matrix2d::<i64>::new_iota(3, 2, 1);
+
+        let mut c = Matrix2d::<i64>::new(3, 2);
+        c |= transpose(&a + &a) + &b;
+
+        assert_eq!(c.at2(0, 0), 3);
+        assert_eq!(c.at2(0, 1), 10);
+        assert_eq!(c.at2(2, 1), 18);
+
+        c |= transpose(transpose(&b));
+        assert_eq!(c.at2(2, 1), 6);
+    }
+
+    #[test]
+    fn transpose_expr_deferred() {
+        let a = Matrix2d::<i64>::new_iota(2, 3, 1);
+        let b = Matrix2d::<i64>::new_iota(2, 3, 2);
+
+        // The sum is only computed when the transpose is read
+        let expr = transpose(&a + &b);
+        assert!(expr.source.get().is_none());
+
+        assert_eq!(expr.at2(2, 1), 13);
+        assert!(expr.source.get().is_some());
+    }
+
+    #[test]
+    fn transpose_vector() {
+        let a = Matrix2d::<i64>::new_iota(3, 1, 1);
+
+        let mut b = Vector::<i64>::new(3);
+        b |= transpose(&a);
+
+        assert_eq!(b.at(2), 3);
+    }
 }
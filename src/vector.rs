@@ -1,5 +1,8 @@
+use crate::aligned_buffer::AlignedBuffer;
 use crate::base_traits::Constants;
+use crate::etl_error::EtlError;
 use crate::etl_expr::*;
+use crate::print::{fmt_container, fmt_container_debug};
 
 use std::{fmt, ops::BitOrAssign};
 
@@ -10,7 +13,7 @@ use rand_distr::*;
 
 #[derive(Clone)]
 pub struct Vector<T: EtlValueType> {
-    pub data: Vec<T>,
+    pub data: AlignedBuffer<T>,
     size: usize,
 }
 
@@ -19,7 +22,7 @@ pub struct Vector<T: EtlValueType> {
 impl<T: EtlValueType> Vector<T> {
     pub fn new(size: usize) -> Self {
         Self {
-            data: vec![T::default(); padded_size(size)],
+            data: AlignedBuffer::new(padded_size(size)),
             size,
         }
     }
@@ -28,20 +31,31 @@ impl<T: EtlValueType> Vector<T> {
         assert_eq!(Expr::DIMENSIONS, 1);
 
         let mut vec = Self {
-            data: vec![T::default(); padded_size(expr.size())],
+            data: AlignedBuffer::new(padded_size(expr.size())),
             size: expr.size(),
         };
 
-        for (lhs, rhs) in vec.data.iter_mut().zip(expr.iter()) {
-            *lhs = rhs;
-        }
+        let size = expr.size();
+        assign_direct(&mut vec.data[..size], &expr);
 
         vec
     }
 
+    pub fn try_new_from_expr<Expr: EtlExpr<T>>(expr: Expr) -> Result<Self, EtlError> {
+        if Expr::DIMENSIONS != 1 {
+            return Err(EtlError::RankMismatch {
+                op: "construction of Vector",
+                expected: 1,
+                actual: Expr::DIMENSIONS,
+            });
+        }
+
+        Ok(Self::new_from_expr(expr))
+    }
+
     pub fn new_iota(size: usize, value: T) -> Self {
         let mut vec = Self {
-            data: vec![T::default(); padded_size(size)],
+            data: AlignedBuffer::new(padded_size(size)),
             size,
         };
         vec.iota_fill(value);
@@ -76,15 +90,13 @@ impl<T: EtlValueType> Vector<T> {
     }
 
     pub fn fill(&mut self, constant: T) {
-        self.data.fill(constant);
+        let size = self.size();
+        self.data[..size].fill(constant);
     }
 
     pub fn iota_fill(&mut self, value: T) {
-        let mut acc = value;
-        for value in self.data.iter_mut() {
-            *value = acc;
-            acc += T::one();
-        }
+        let size = self.size();
+        iota_direct(&mut self.data[..size], value);
     }
 
     pub fn rand_fill(&mut self)
@@ -92,8 +104,9 @@ impl<T: EtlValueType> Vector<T> {
         rand::distr::StandardUniform: rand::distr::Distribution<T>,
     {
         let mut rng = rand::rng();
+        let size = self.size();
 
-        for value in self.data.iter_mut() {
+        for value in self.data[..size].iter_mut() {
             *value = rng.random::<T>();
         }
     }
@@ -107,15 +120,32 @@ impl<T: EtlValueType> Vector<T> {
         let n = <T as Constants>::zero();
         let p = <T as Constants>::one();
         let normal = Normal::new(n, p).unwrap();
+        let size = self.size();
 
-        for value in self.data.iter_mut() {
+        for value in self.data[..size].iter_mut() {
             *value = normal.sample(&mut rng);
         }
     }
 
     pub fn inplace_axpy<RightExpr: EtlExpr<T>>(&mut self, alpha: T, beta: T, y: RightExpr) {
         validate_assign(self, &y);
-        axpy_direct(&mut self.data, alpha, beta, &y);
+        let size = self.size();
+        axpy_direct(&mut self.data[..size], alpha, beta, &y);
+    }
+
+    /// Assign an expression that reads this container, evaluated with evaluate, e.g.
+    /// `x.assign_with(|x| evaluate((&b >> x) + &z))` for `x = b * x + z`
+    pub fn assign_with(&mut self, f: impl FnOnce(&Self) -> Evaluated<T>) {
+        let rhs = f(self);
+        let size = self.size();
+        assign_evaluated(expr_dims(self), &mut self.data[..size], rhs);
+    }
+
+    pub fn try_assign<RightExpr: EtlExpr<T>>(&mut self, rhs: RightExpr) -> Result<(), EtlError> {
+        try_validate_assign(self, &rhs)?;
+        let size = self.size();
+        assign_direct(&mut self.data[..size], &rhs);
+        Ok(())
     }
 
     pub fn direct_iter(&self) -> std::slice::Iter<'_, T> {
@@ -216,22 +246,19 @@ impl<'a, T: EtlValueType> EtlWrappable<T> for &'a Vector<T> {
 // Vector<T> computes as itself
 impl<T: EtlValueType> EtlComputable<T> for &Vector<T> {
     fn to_data(&self) -> Vec<T> {
-        self.data.clone()
+        self.data.to_vec()
     }
 }
 
 impl<T: EtlValueType> fmt::Display for Vector<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "[")?;
-
-        for i in 0..self.size() {
-            if i > 0 {
-                write!(f, ",")?;
-            }
-            write!(f, "{:.6}", self.data[i])?;
-        }
+        fmt_container(f, &self.data, &[self.size])
+    }
+}
 
-        write!(f, "]")
+impl<T: EtlValueType> fmt::Debug for Vector<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_container_debug(f, "Vector", &self.data, &[self.size])
     }
 }
 
@@ -255,7 +282,8 @@ impl<T: EtlValueType> std::ops::IndexMut<usize> for Vector<T> {
 impl<T: EtlValueType, RightExpr: EtlExpr<T>> BitOrAssign<RightExpr> for Vector<T> {
     fn bitor_assign(&mut self, rhs: RightExpr) {
         validate_assign(self, &rhs);
-        assign_direct(&mut self.data, &rhs);
+        let size = self.size();
+        assign_direct(&mut self.data[..size], &rhs);
     }
 }
 
@@ -310,6 +338,20 @@ mod tests {
         assert_eq!(c.at(4), 11);
     }
 
+    #[test]
+    fn iota_unsigned() {
+        let mut vec = Vector::<u8>::new_iota(5, 250);
+
+        assert_eq!(vec.data[..5], [250, 251, 252, 253, 254]);
+        assert_eq!(vec.data[5..], [0, 0, 0]);
+
+        vec.iota_fill(251);
+        assert_eq!(vec.at(4), 255);
+
+        vec.fill(7);
+        assert_eq!(vec.data[..], [7, 7, 7, 7, 7, 0, 0, 0]);
+    }
+
     #[test]
     fn normal() {
         let _vec = Vector::<f64>::new_rand_normal(3);
@@ -409,4 +451,22 @@ mod tests {
         assert_eq!(a[1], 13);
         assert_eq!(a[2], 43);
     }
+
+    #[test]
+    fn assign_with() {
+        let mut x = Vector::<i64>::new_iota(5, 1);
+        let b = Vector::<i64>::new_iota(5, 2);
+        let z = Vector::<i64>::new_iota(5, 3);
+
+        x.assign_with(|x| evaluate((&b >> x) + &z));
+
+        assert_eq!(x.at(0), 5);
+        assert_eq!(x.at(1), 10);
+        assert_eq!(x.at(4), 37);
+
+        x.assign_with(|x| evaluate(x - crate::constant::cst(5)));
+
+        assert_eq!(x.at(0), 0);
+        assert_eq!(x.at(4), 32);
+    }
 }
@@ -0,0 +1,229 @@
+use std::alloc::{Layout, alloc, dealloc, handle_alloc_error};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// The declaration of the workspace
+
+/// The counters of the workspaces of all the threads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WorkspaceStats {
+    /// The number of scratch buffers that had to be allocated
+    pub allocations: usize,
+    /// The number of scratch buffers that were reused, i.e. the number of allocations avoided
+    pub reuses: usize,
+}
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static REUSES: AtomicUsize = AtomicUsize::new(0);
+
+/// The default maximum number of bytes of free blocks kept by the workspace of a thread
+pub const DEFAULT_WORKSPACE_LIMIT: usize = 64 << 20;
+
+// The free blocks of a thread, indexed by size in bytes (they all have the same alignment)
+struct Workspace {
+    blocks: HashMap<usize, Vec<(NonNull<u8>, Layout)>>,
+    bytes: usize,
+    limit: usize,
+}
+
+impl Default for Workspace {
+    fn default() -> Self {
+        Self {
+            blocks: HashMap::new(),
+            bytes: 0,
+            limit: DEFAULT_WORKSPACE_LIMIT,
+        }
+    }
+}
+
+impl Workspace {
+    fn pop(&mut self, size: usize) -> Option<NonNull<u8>> {
+        let (ptr, _) = self.blocks.get_mut(&size)?.pop()?;
+        self.bytes -= size;
+        Some(ptr)
+    }
+
+    // Keep the block, unless it would make the workspace larger than its limit
+    fn push(&mut self, ptr: NonNull<u8>, layout: Layout) {
+        if self.bytes + layout.size() > self.limit {
+            // Safety: the block was allocated with this same layout
+            unsafe { dealloc(ptr.as_ptr(), layout) };
+            return;
+        }
+
+        self.bytes += layout.size();
+        self.blocks.entry(layout.size()).or_default().push((ptr, layout));
+    }
+
+    fn clear(&mut self) {
+        for (ptr, layout) in self.blocks.drain().flat_map(|(_, blocks)| blocks) {
+            // Safety: the block was allocated with this same layout
+            unsafe { dealloc(ptr.as_ptr(), layout) };
+        }
+
+        self.bytes = 0;
+    }
+}
+
+impl Drop for Workspace {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+thread_local! {
+    static WORKSPACE: RefCell<Workspace> = RefCell::new(Workspace::default());
+}
+
+// The functions of the workspace
+
+// Borrow a block from the workspace of the current thread, or allocate a new one
+pub(crate) fn acquire(layout: Layout) -> NonNull<u8> {
+    let reused = WORKSPACE.try_with(|workspace| workspace.borrow_mut().pop(layout.size())).ok().flatten();
+
+    if let Some(ptr) = reused {
+        REUSES.fetch_add(1, Ordering::Relaxed);
+        return ptr;
+    }
+
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+
+    // Safety: the buffers never ask for zero-sized blocks
+    let raw = unsafe { alloc(layout) };
+    NonNull::new(raw).unwrap_or_else(|| handle_alloc_error(layout))
+}
+
+// Give a block back to the workspace of the current thread
+pub(crate) fn release(ptr: NonNull<u8>, layout: Layout) {
+    let kept = WORKSPACE.try_with(|workspace| workspace.borrow_mut().push(ptr, layout));
+
+    // The thread is shutting down, there is no workspace to give it back to
+    if kept.is_err() {
+        // Safety: the block was allocated with this same layout
+        unsafe { dealloc(ptr.as_ptr(), layout) };
+    }
+}
+
+/// Returns the counters of the workspaces since the start of the program
+pub fn workspace_stats() -> WorkspaceStats {
+    WorkspaceStats {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed),
+        reuses: REUSES.load(Ordering::Relaxed),
+    }
+}
+
+/// Release the free scratch buffers kept by the workspace of the current thread
+pub fn clear_workspace() {
+    WORKSPACE.with(|workspace| workspace.borrow_mut().clear());
+}
+
+/// The number of bytes of the free scratch buffers kept by the workspace of the current thread
+pub fn workspace_size() -> usize {
+    WORKSPACE.with(|workspace| workspace.borrow().bytes)
+}
+
+/// Set the maximum number of bytes of free scratch buffers kept by the workspace of the current
+/// thread (DEFAULT_WORKSPACE_LIMIT by default)
+///
+/// The buffers released beyond the limit are freed. The buffers already kept are released when
+/// they do not fit in the new limit.
+pub fn set_workspace_limit(limit: usize) {
+    WORKSPACE.with(|workspace| {
+        let mut workspace = workspace.borrow_mut();
+
+        workspace.limit = limit;
+        if workspace.bytes > limit {
+            workspace.clear();
+        }
+    });
+}
+
+// The tests
+
+#[cfg(test)]
+mod tests {
+    use crate::aligned_buffer::AlignedBuffer;
+    use crate::etl_expr::EtlExpr;
+    use crate::matrix_2d::Matrix2d;
+    use crate::workspace::*;
+
+    #[test]
+    fn reuse() {
+        clear_workspace();
+
+        let before = workspace_stats();
+
+        let a = AlignedBuffer::<f32>::from_workspace(1000);
+        let ptr = a.as_ptr();
+        drop(a);
+
+        let b = AlignedBuffer::<f32>::from_workspace(1000);
+        assert_eq!(b.as_ptr(), ptr);
+        assert!(b.iter().all(|v| *v == 0.0));
+
+        // A different size cannot reuse the block
+        let c = AlignedBuffer::<f32>::from_workspace(2000);
+        assert_ne!(c.as_ptr(), ptr);
+
+        let after = workspace_stats();
+        assert!(after.allocations >= before.allocations + 2);
+        assert!(after.reuses > before.reuses);
+    }
+
+    #[test]
+    fn limit() {
+        clear_workspace();
+        set_workspace_limit(10_000);
+
+        // Varying sizes cannot reuse the blocks, only the ones that fit in the limit are kept
+        for len in 1000..1100 {
+            drop(AlignedBuffer::<f32>::from_workspace(len));
+            assert!(workspace_size() <= 10_000);
+        }
+
+        assert!(workspace_size() > 0);
+
+        set_workspace_limit(1000);
+        assert_eq!(workspace_size(), 0);
+
+        set_workspace_limit(DEFAULT_WORKSPACE_LIMIT);
+    }
+
+    #[test]
+    fn smart_temps() {
+        let a = Matrix2d::<f32>::new_iota(13, 17, 1.0);
+        let b = Matrix2d::<f32>::new_iota(17, 19, 2.0);
+
+        // The first evaluation fills the workspace
+        let expected = (&a * &b).at(42);
+
+        let before = workspace_stats();
+
+        for _ in 0..10 {
+            assert_eq!((&a * &b).at(42), expected);
+        }
+
+        let after = workspace_stats();
+        assert!(after.reuses >= before.reuses + 10);
+    }
+
+    #[test]
+    fn non_direct_operands() {
+        let a = Matrix2d::<f32>::new_iota(13, 17, 1.0);
+        let b = Matrix2d::<f32>::new_iota(17, 19, 2.0);
+
+        let mut c = Matrix2d::<f32>::new(13, 19);
+        c |= (&a + &a) * &b;
+
+        let before = workspace_stats();
+
+        for _ in 0..10 {
+            c |= (&a + &a) * &b;
+        }
+
+        let after = workspace_stats();
+        assert!(after.reuses >= before.reuses + 10);
+    }
+}